@@ -0,0 +1,335 @@
+use crate::{
+    nbt::{TagData, TagKind, TagList},
+    types::{Container, DEFAULT_SIGN_COLOR, DyeColor, ItemStack, Sign, SignText, max_stack_size},
+};
+
+/// Java block entity ids of containers and the Bedrock ids they become
+const CONTAINERS: [(&str, &str); 11] = [
+    ("chest", "Chest"),
+    ("trapped_chest", "Chest"),
+    ("barrel", "Barrel"),
+    ("shulker_box", "ShulkerBox"),
+    ("hopper", "Hopper"),
+    ("dispenser", "Dispenser"),
+    ("dropper", "Dropper"),
+    ("furnace", "Furnace"),
+    ("blast_furnace", "BlastFurnace"),
+    ("smoker", "Smoker"),
+    ("brewing_stand", "BrewingStand"),
+];
+
+/// Plain text of a Java text component, e.g. `{"text":"Hello"}` or `"Hello"`. Formatting is
+/// dropped and the `text` of nested components is joined in order.
+fn plain_text(json: &str) -> String {
+    let json = json.trim();
+    if !json.starts_with(['"', '{', '[']) {
+        return json.to_string();
+    }
+    let mut text = String::new();
+    let mut chars = json.chars().peekable();
+    let mut key: Option<String> = None;
+    while let Some(c) = chars.next() {
+        if c != '"' {
+            continue;
+        }
+        let mut string = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => break,
+                '\\' => match chars.next() {
+                    Some('n') => string.push('\n'),
+                    Some('t') => string.push('\t'),
+                    Some('u') => {
+                        let code: String = chars.by_ref().take(4).collect();
+                        if let Some(c) =
+                            u32::from_str_radix(&code, 16).ok().and_then(char::from_u32)
+                        {
+                            string.push(c);
+                        }
+                    }
+                    Some(c) => string.push(c),
+                    None => break,
+                },
+                c => string.push(c),
+            }
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.next_if_eq(&':').is_some() {
+            key = Some(string);
+        } else {
+            if json.starts_with('"') || key.as_deref() == Some("text") {
+                text.push_str(&string);
+            }
+            key = None;
+        }
+    }
+    text
+}
+
+/// Java text component holding plain text
+fn json_text(text: &str) -> String {
+    let mut json = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Text of a Java message, saved as JSON before 1.21.5 and as a string or compound after
+fn message_text(message: &TagData) -> String {
+    match message {
+        TagData::String(json) => plain_text(json),
+        TagData::Compound(_) => match message.find_tag("text") {
+            Some(TagData::String(text)) => text.clone(),
+            _ => String::new(),
+        },
+        _ => String::new(),
+    }
+}
+
+fn sign_color(name: &str) -> u32 {
+    match DyeColor::from_name(name) {
+        Some(DyeColor::Black) | None => DEFAULT_SIGN_COLOR,
+        Some(color) => color.argb(),
+    }
+}
+
+fn sign_color_name(argb: u32) -> &'static str {
+    DyeColor::ALL
+        .into_iter()
+        .find(|color| color.argb() == argb)
+        .map_or("black", DyeColor::name)
+}
+
+/// Lines of Java sign text, without the empty lines Java pads signs to four lines with
+fn sign_lines<'a>(messages: impl Iterator<Item = &'a TagData>) -> Vec<String> {
+    let mut lines: Vec<String> = messages.map(message_text).collect();
+    while lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+    lines
+}
+
+fn java_sign_text(data: &TagData) -> Option<SignText> {
+    let Some(TagData::List(_, _, messages)) = data.find_tag("messages") else {
+        return None;
+    };
+    Some(SignText {
+        lines: sign_lines(messages.iter()),
+        color: match data.find_tag("color") {
+            Some(TagData::String(color)) => sign_color(color),
+            _ => DEFAULT_SIGN_COLOR,
+        },
+        glowing: matches!(data.find_tag("has_glowing_text"), Some(TagData::Byte(1))),
+    })
+}
+
+fn java_sign(data: &TagData) -> Sign {
+    // signs saved before 1.20 have a single side with one tag per line
+    let legacy = || SignText {
+        lines: sign_lines((1..=4).filter_map(|line| data.find_tag(&format!("Text{}", line)))),
+        color: match data.find_tag("Color") {
+            Some(TagData::String(color)) => sign_color(color),
+            _ => DEFAULT_SIGN_COLOR,
+        },
+        glowing: matches!(data.find_tag("GlowingText"), Some(TagData::Byte(1))),
+    };
+    Sign {
+        front: data
+            .find_tag("front_text")
+            .and_then(java_sign_text)
+            .or_else(|| Some(legacy())),
+        back: data.find_tag("back_text").and_then(java_sign_text),
+        waxed: matches!(data.find_tag("is_waxed"), Some(TagData::Byte(1))),
+    }
+}
+
+fn java_item(item: &TagData) -> Option<TagData> {
+    let (Some(TagData::Byte(slot)), Some(TagData::String(id))) =
+        (item.find_tag("Slot"), item.find_tag("id"))
+    else {
+        return None;
+    };
+    // items saved before 1.20.5 have a byte `Count`
+    let count = match (item.find_tag("count"), item.find_tag("Count")) {
+        (Some(TagData::Int(count)), _) => *count,
+        (_, Some(TagData::Byte(count))) => *count as i32,
+        _ => 1,
+    };
+    // counts are capped at the Bedrock stack size, which can be smaller than the Java one
+    if count <= 0 {
+        return None;
+    }
+    let count = count.min(max_stack_size(id) as i32) as u8;
+    Some(ItemStack::new(id, count).ok()?.as_slot_tag(*slot as u8))
+}
+
+/// Bedrock loot tables are files in the behavior pack, Java ones are namespaced ids
+fn bedrock_loot_table(path: &str) -> String {
+    let path = path.split_once(':').map_or(path, |(_, path)| path);
+    format!("loot_tables/{}.json", path)
+}
+
+fn java_loot_table(path: &str) -> String {
+    let path = path.strip_prefix("loot_tables/").unwrap_or(path);
+    format!("minecraft:{}", path.strip_suffix(".json").unwrap_or(path))
+}
+
+fn java_container(data: &TagData) -> Container {
+    let items = match data.find_tag("Items") {
+        Some(TagData::List(_, _, items)) => items.iter().filter_map(java_item).collect(),
+        _ => vec![],
+    };
+    Container {
+        items,
+        loot_table: match (data.find_tag("LootTable"), data.find_tag("LootTableSeed")) {
+            (Some(TagData::String(path)), seed) => Some((
+                bedrock_loot_table(path),
+                match seed {
+                    Some(TagData::Long(seed)) => *seed as i32,
+                    _ => 0,
+                },
+            )),
+            _ => None,
+        },
+        custom_name: data.find_tag("CustomName").map(message_text),
+    }
+}
+
+/// Block entity compound with the id and position tags both editions share
+fn block_entity(id: &str, data: &TagData, movable: bool) -> TagData {
+    let mut block_entity = TagList::from(vec![("id".to_string(), TagData::String(id.to_string()))]);
+    if movable {
+        block_entity.add_tag("isMovable", TagData::Byte(1));
+    }
+    for axis in ["x", "y", "z"] {
+        if let Some(TagData::Int(position)) = data.find_tag(axis) {
+            block_entity.add_tag(axis, TagData::Int(*position));
+        }
+    }
+    TagData::Compound(block_entity)
+}
+
+/// Converts a Java block entity to Bedrock. Containers keep their items, custom name and loot
+/// table; signs keep the plain text, color and glow of both sides. Item ids are kept as they
+/// are. Returns `None` for other block entities.
+pub fn java_block_entity_to_bedrock(data: &TagData) -> Option<TagData> {
+    let Some(TagData::String(id)) = data.find_tag("id") else {
+        return None;
+    };
+    let name = id.strip_prefix("minecraft:").unwrap_or(id);
+    if let Some((_, bedrock_id)) = CONTAINERS.iter().find(|(java, _)| *java == name) {
+        let mut bedrock = block_entity(bedrock_id, data, true);
+        bedrock.add_tag("Findable", TagData::Byte(0));
+        java_container(data).write_tags(&mut bedrock);
+        return Some(bedrock);
+    }
+    let bedrock_id = match name {
+        "sign" => "Sign",
+        "hanging_sign" => "HangingSign",
+        _ => return None,
+    };
+    let mut bedrock = block_entity(bedrock_id, data, true);
+    java_sign(data).write_tags(&mut bedrock);
+    Some(bedrock)
+}
+
+fn java_text_tag(text: &SignText) -> TagData {
+    let mut lines: Vec<TagData> = text
+        .lines
+        .iter()
+        .take(4)
+        .map(|line| TagData::String(json_text(line)))
+        .collect();
+    // Java signs always have four lines
+    lines.resize(4, TagData::String(json_text("")));
+    TagData::Compound(TagList::from(vec![
+        (
+            "color".to_string(),
+            TagData::String(sign_color_name(text.color).to_string()),
+        ),
+        (
+            "has_glowing_text".to_string(),
+            TagData::Byte(text.glowing as i8),
+        ),
+        (
+            "messages".to_string(),
+            TagData::List(TagKind::String, 4, lines),
+        ),
+    ]))
+}
+
+/// Converts a Bedrock block entity to Java, the reverse of `java_block_entity_to_bedrock`.
+/// Trapped chests share the `Chest` id in Bedrock and come out as chests. Items are written
+/// the way Java saves them since 1.20.5.
+pub fn bedrock_block_entity_to_java(data: &TagData) -> Option<TagData> {
+    let Some(TagData::String(id)) = data.find_tag("id") else {
+        return None;
+    };
+    if let Some((java_id, _)) = CONTAINERS.iter().find(|(_, bedrock)| bedrock == id) {
+        let container = Container::from_tag(data).unwrap_or_default();
+        let mut java = block_entity(&format!("minecraft:{}", java_id), data, false);
+        let items: Vec<TagData> = container
+            .items
+            .iter()
+            .filter_map(|item| {
+                let (Some(TagData::Byte(slot)), Some(TagData::String(name))) =
+                    (item.find_tag("Slot"), item.find_tag("Name"))
+                else {
+                    return None;
+                };
+                let count = match item.find_tag("Count") {
+                    Some(TagData::Byte(count)) => *count as u8 as i32,
+                    _ => 1,
+                };
+                (!name.is_empty() && count > 0).then(|| {
+                    TagData::Compound(TagList::from(vec![
+                        ("Slot".to_string(), TagData::Byte(*slot)),
+                        ("count".to_string(), TagData::Int(count)),
+                        ("id".to_string(), TagData::String(name.clone())),
+                    ]))
+                })
+            })
+            .collect();
+        java.add_tag(
+            "Items",
+            TagData::List(TagKind::Compound, items.len() as u32, items),
+        );
+        if let Some((path, seed)) = &container.loot_table {
+            java.add_tag("LootTable", TagData::String(java_loot_table(path)));
+            java.add_tag("LootTableSeed", TagData::Long(*seed as i64));
+        }
+        if let Some(name) = &container.custom_name {
+            java.add_tag("CustomName", TagData::String(json_text(name)));
+        }
+        return Some(java);
+    }
+    let java_id = match id.as_str() {
+        "Sign" => "minecraft:sign",
+        "HangingSign" => "minecraft:hanging_sign",
+        _ => return None,
+    };
+    // signs saved before signs had two sides keep their text in the block entity itself
+    let sign = Sign::from_tag(data).unwrap_or_else(|| Sign {
+        front: SignText::from_tag(data),
+        back: None,
+        waxed: false,
+    });
+    let mut java = block_entity(java_id, data, false);
+    let empty = SignText {
+        lines: vec![],
+        color: DEFAULT_SIGN_COLOR,
+        glowing: false,
+    };
+    for (tag, text) in [("front_text", &sign.front), ("back_text", &sign.back)] {
+        java.add_tag(tag, java_text_tag(text.as_ref().unwrap_or(&empty)));
+    }
+    java.add_tag("is_waxed", TagData::Byte(sign.waxed as i8));
+    Some(java)
+}
@@ -0,0 +1,968 @@
+use std::collections::HashMap;
+
+use crate::types::{
+    BlockState, BlockType, Cardinal, DOOR_DIRECTION, Facing, LEGACY_DIRECTION, WEIRDO_DIRECTION,
+    block_info,
+};
+
+/// Blocks whose Java and Bedrock ids differ only in name, Java first
+const RENAMES: [(&str, &str); 43] = [
+    ("oak_door", "wooden_door"),
+    ("oak_trapdoor", "trapdoor"),
+    ("oak_fence_gate", "fence_gate"),
+    ("oak_button", "wooden_button"),
+    ("oak_pressure_plate", "wooden_pressure_plate"),
+    ("oak_sign", "standing_sign"),
+    ("oak_wall_sign", "wall_sign"),
+    ("dark_oak_sign", "darkoak_standing_sign"),
+    ("dark_oak_wall_sign", "darkoak_wall_sign"),
+    ("bricks", "brick_block"),
+    ("cobblestone_stairs", "stone_stairs"),
+    ("stone_stairs", "normal_stone_stairs"),
+    ("stone_slab", "normal_stone_slab"),
+    ("end_stone_bricks", "end_bricks"),
+    ("end_stone_brick_stairs", "end_brick_stairs"),
+    ("prismarine_brick_stairs", "prismarine_bricks_stairs"),
+    ("nether_bricks", "nether_brick"),
+    ("red_nether_bricks", "red_nether_brick"),
+    ("copper_block", "copper"),
+    ("waxed_copper_block", "waxed_copper"),
+    ("rooted_dirt", "dirt_with_roots"),
+    ("terracotta", "hardened_clay"),
+    ("magma_block", "magma"),
+    ("snow_block", "snow"),
+    ("snow", "snow_layer"),
+    ("cobweb", "web"),
+    ("lily_pad", "waterlily"),
+    ("dead_bush", "deadbush"),
+    ("sugar_cane", "reeds"),
+    ("melon", "melon_block"),
+    ("jack_o_lantern", "lit_pumpkin"),
+    ("slime_block", "slime"),
+    ("note_block", "noteblock"),
+    ("spawner", "mob_spawner"),
+    ("stonecutter", "stonecutter_block"),
+    ("shulker_box", "undyed_shulker_box"),
+    ("powered_rail", "golden_rail"),
+    ("nether_quartz_ore", "quartz_ore"),
+    ("dirt_path", "grass_path"),
+    ("nether_portal", "portal"),
+    ("piston_head", "piston_arm_collision"),
+    ("tripwire", "trip_wire"),
+    ("beetroots", "beetroot"),
+];
+
+/// Stones of infested blocks, which are all `monster_egg` in Bedrock, Java first
+const MONSTER_EGG_STONES: [(&str, &str); 6] = [
+    ("stone", "stone"),
+    ("cobblestone", "cobblestone"),
+    ("stone_bricks", "stone_brick"),
+    ("mossy_stone_bricks", "mossy_stone_brick"),
+    ("cracked_stone_bricks", "cracked_stone_brick"),
+    ("chiseled_stone_bricks", "chiseled_stone_brick"),
+];
+
+/// Bedrock beetroot `growth` for each Java `age`
+const BEETROOT_GROWTH: [i32; 4] = [0, 3, 4, 7];
+
+/// Java rail `shape`s, indexed by Bedrock `rail_direction`
+const RAIL_SHAPES: [&str; 10] = [
+    "north_south",
+    "east_west",
+    "ascending_east",
+    "ascending_west",
+    "ascending_north",
+    "ascending_south",
+    "south_east",
+    "south_west",
+    "north_west",
+    "north_east",
+];
+
+/// Blocks that are lit in Bedrock by a `lit_` id rather than a state
+const LIT_BLOCKS: [&str; 6] = [
+    "furnace",
+    "blast_furnace",
+    "smoker",
+    "redstone_lamp",
+    "redstone_ore",
+    "deepslate_redstone_ore",
+];
+
+const VINE_SIDES: [&str; 4] = ["south", "west", "north", "east"];
+
+/// Mob heads, with the same ids on Java and Bedrock. Java has a wall block for each one,
+/// e.g. `skeleton_wall_skull`, which Bedrock keeps in `facing_direction`.
+const SKULLS: [&str; 7] = [
+    "skeleton_skull",
+    "wither_skeleton_skull",
+    "zombie_head",
+    "player_head",
+    "creeper_head",
+    "dragon_head",
+    "piglin_head",
+];
+
+/// Blocks that have `pillar_axis` on Bedrock but no `axis` on Java
+const UNROTATED_PILLARS: [&str; 4] = [
+    "quartz_block",
+    "chiseled_quartz_block",
+    "smooth_quartz",
+    "purpur_block",
+];
+
+/// Java block states, which are all strings
+struct JavaStates(HashMap<String, String>);
+
+impl JavaStates {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+    fn flag(&self, name: &str) -> Option<BlockState> {
+        Some(BlockState::Bool((self.get(name)? == "true") as u8))
+    }
+    fn is(&self, name: &str, value: &str) -> Option<BlockState> {
+        Some(BlockState::Bool((self.get(name)? == value) as u8))
+    }
+    fn int(&self, name: &str, offset: i32) -> Option<BlockState> {
+        Some(BlockState::Int(
+            self.get(name)?.parse::<i32>().ok()? + offset,
+        ))
+    }
+    fn cardinal(&self) -> Option<Cardinal> {
+        Cardinal::from_name(self.get("facing")?)
+    }
+}
+
+fn int_direction(table: &[Cardinal; 4], direction: Cardinal) -> BlockState {
+    BlockState::Int(table.iter().position(|c| *c == direction).unwrap() as i32)
+}
+
+/// Table used by the `direction` state of the block
+fn direction_table(name: &str) -> &'static [Cardinal; 4] {
+    if name.ends_with("trapdoor") {
+        &WEIRDO_DIRECTION
+    } else if name.ends_with("_door") {
+        &DOOR_DIRECTION
+    } else {
+        &LEGACY_DIRECTION
+    }
+}
+
+fn is_piston(name: &str) -> bool {
+    matches!(
+        name,
+        "piston"
+            | "sticky_piston"
+            | "piston_head"
+            | "piston_arm_collision"
+            | "sticky_piston_arm_collision"
+    )
+}
+
+fn monster_egg_stone(name: &str) -> Option<&'static (&'static str, &'static str)> {
+    let stone = name.strip_prefix("infested_")?;
+    MONSTER_EGG_STONES.iter().find(|(java, _)| *java == stone)
+}
+
+/// Pistons count their horizontal `facing_direction`s the other way round
+fn piston_facing(facing: Facing) -> Facing {
+    match facing {
+        Facing::Down | Facing::Up => facing,
+        horizontal => horizontal.opposite(),
+    }
+}
+
+fn rename_to_bedrock(name: &str) -> String {
+    match RENAMES.iter().find(|(java, _)| *java == name) {
+        Some((_, bedrock)) => bedrock.to_string(),
+        None => name.to_string(),
+    }
+}
+
+fn rename_to_java(name: &str) -> String {
+    match RENAMES.iter().find(|(_, bedrock)| *bedrock == name) {
+        Some((java, _)) => java.to_string(),
+        None => name.to_string(),
+    }
+}
+
+/// Bedrock id of a Java block, without the namespace
+fn bedrock_name(name: &str, java: &JavaStates) -> String {
+    match name {
+        "cave_air" | "void_air" => return "air".to_string(),
+        "grass" => return "short_grass".to_string(),
+        "wall_torch" => return "torch".to_string(),
+        "soul_wall_torch" => return "soul_torch".to_string(),
+        "redstone_torch" | "redstone_wall_torch" if java.get("lit") == Some("false") => {
+            return "unlit_redstone_torch".to_string();
+        }
+        "redstone_wall_torch" => return "redstone_torch".to_string(),
+        "repeater" | "comparator" => {
+            let powered = if java.get("powered") == Some("true") {
+                "powered"
+            } else {
+                "unpowered"
+            };
+            return format!("{}_{}", powered, name);
+        }
+        "daylight_detector" if java.get("inverted") == Some("true") => {
+            return "daylight_detector_inverted".to_string();
+        }
+        "light" => return format!("light_block_{}", java.get("level").unwrap_or("15")),
+        "piston_head" if java.get("type") == Some("sticky") => {
+            return "sticky_piston_arm_collision".to_string();
+        }
+        _ if monster_egg_stone(name).is_some() => return "monster_egg".to_string(),
+        "water_cauldron" | "lava_cauldron" | "powder_snow_cauldron" => {
+            return "cauldron".to_string();
+        }
+        _ if LIT_BLOCKS.contains(&name) && java.get("lit") == Some("true") => {
+            return format!("lit_{}", name);
+        }
+        _ => {}
+    }
+    if let Some((mob, kind)) = name.split_once("_wall_")
+        && SKULLS.contains(&format!("{}_{}", mob, kind).as_str())
+    {
+        return format!("{}_{}", mob, kind);
+    }
+    if let Some(wood) = name.strip_suffix("_wall_hanging_sign") {
+        return format!("{}_hanging_sign", wood);
+    }
+    if name.ends_with("_wall_banner") {
+        return "wall_banner".to_string();
+    }
+    if name.ends_with("_banner") {
+        return "standing_banner".to_string();
+    }
+    if name.ends_with("_bed") {
+        return "bed".to_string();
+    }
+    let renamed = rename_to_bedrock(name);
+    if java.get("type") == Some("double")
+        && let Some(slab) = renamed.strip_suffix("_slab")
+    {
+        return format!("{}_double_slab", slab);
+    }
+    if renamed != name {
+        return renamed;
+    }
+    if let Some(wood) = name.strip_suffix("_sign")
+        && !name.ends_with("_wall_sign")
+        && !name.ends_with("_hanging_sign")
+    {
+        return format!("{}_standing_sign", wood);
+    }
+    renamed
+}
+
+/// Value of a Bedrock state worked out from the Java states, `None` if the Java block does not
+/// say
+fn bedrock_state(state: &str, name: &str, java: &JavaStates) -> Option<BlockState> {
+    Some(match state {
+        "weirdo_direction" => int_direction(&WEIRDO_DIRECTION, java.cardinal()?),
+        "direction" => int_direction(direction_table(name), java.cardinal()?),
+        "upside_down_bit" => java.is("half", "top")?,
+        "upper_block_bit" => java.is("half", "upper")?,
+        "minecraft:vertical_half" => BlockState::String(
+            if java.get("type")? == "top" {
+                "top"
+            } else {
+                "bottom"
+            }
+            .to_string(),
+        ),
+        "pillar_axis" => BlockState::String(java.get("axis")?.to_string()),
+        "door_hinge_bit" => java.is("hinge", "right")?,
+        "open_bit" if name == "lever" => java.flag("powered")?,
+        "open_bit" => java.flag("open")?,
+        "in_wall_bit" => java.flag("in_wall")?,
+        "head_piece_bit" => java.is("part", "head")?,
+        "occupied_bit" => java.flag("occupied")?,
+        "minecraft:cardinal_direction" | "minecraft:facing_direction" => {
+            BlockState::String(java.get("facing")?.to_string())
+        }
+        "facing_direction" => {
+            let facing = if name == "jigsaw" {
+                let (facing, _) = java.get("orientation")?.split_once('_')?;
+                Facing::from_name(facing)?
+            } else if SKULLS.contains(&name) {
+                // heads on the floor keep their rotation in the block entity
+                Facing::Up
+            } else {
+                match (java.get("face"), Facing::from_name(java.get("facing")?)?) {
+                    (Some("floor"), _) => Facing::Up,
+                    (Some("ceiling"), _) => Facing::Down,
+                    (_, facing) if is_piston(name) => piston_facing(facing),
+                    (_, facing) => facing,
+                }
+            };
+            BlockState::Int(facing.facing_direction())
+        }
+        // which way the top of an upwards or downwards facing jigsaw block points
+        "rotation" => {
+            let (_, top) = java.get("orientation")?.split_once('_')?;
+            let top = Cardinal::from_name(top)?;
+            BlockState::Int(Cardinal::CLOCKWISE.iter().position(|c| *c == top)? as i32)
+        }
+        "torch_facing_direction" => BlockState::String(
+            if name.ends_with("wall_torch") {
+                java.get("facing")?
+            } else {
+                "top"
+            }
+            .to_string(),
+        ),
+        "ground_sign_direction" => java.int("rotation", 0)?,
+        "hanging" if name.ends_with("hanging_sign") => {
+            BlockState::Bool(!name.ends_with("wall_hanging_sign") as u8)
+        }
+        "hanging" => java.flag("hanging")?,
+        "attached_bit" => java.flag("attached")?,
+        "persistent_bit" => java.flag("persistent")?,
+        "liquid_depth" => java.int("level", 0)?,
+        "growth" if name == "beetroots" => {
+            BlockState::Int(*BEETROOT_GROWTH.get(java.get("age")?.parse::<usize>().ok()?)?)
+        }
+        "growth" | "age" | "kelp_age" => java.int("age", 0)?,
+        "age_bit" => java.is("stage", "1")?,
+        "candles" => java.int("candles", -1)?,
+        "lit" => java.flag("lit")?,
+        "extinguished" => java.is("lit", "false")?,
+        "height" => java.int("layers", -1)?,
+        "redstone_signal" => java.int("power", 0).or_else(|| {
+            Some(BlockState::Int(if java.get("powered")? == "true" {
+                15
+            } else {
+                0
+            }))
+        })?,
+        "button_pressed_bit" | "output_lit_bit" | "rail_data_bit" | "powered_bit" => {
+            java.flag("powered")?
+        }
+        "triggered_bit" => java.flag("triggered")?,
+        "toggle_bit" if name == "hopper" => java.is("enabled", "false")?,
+        "toggle_bit" => java.flag("powered")?,
+        "repeater_delay" => java.int("delay", -1)?,
+        "output_subtract_bit" => java.is("mode", "subtract")?,
+        "rail_direction" => {
+            let shape = java.get("shape")?;
+            BlockState::Int(RAIL_SHAPES.iter().position(|s| *s == shape)? as i32)
+        }
+        "moisturized_amount" => java.int("moisture", 0)?,
+        "bite_counter" => java.int("bites", 0)?,
+        "cluster_count" => java.int("pickles", -1)?,
+        "dead_bit" => java.is("waterlogged", "false")?,
+        "respawn_anchor_charge" => java.int("charges", 0)?,
+        "composter_fill_level" => java.int("level", 0)?,
+        "fill_level" if name == "lava_cauldron" => BlockState::Int(6),
+        "fill_level" => BlockState::Int(java.get("level")?.parse::<i32>().ok()? * 2),
+        "cauldron_liquid" => BlockState::String(
+            match name {
+                "lava_cauldron" => "lava",
+                "powder_snow_cauldron" => "powder_snow",
+                _ => "water",
+            }
+            .to_string(),
+        ),
+        "stability" => java.int("distance", 0)?,
+        "explode_bit" => java.flag("unstable")?,
+        "brewing_stand_slot_a_bit" => java.flag("has_bottle_0")?,
+        "brewing_stand_slot_b_bit" => java.flag("has_bottle_1")?,
+        "brewing_stand_slot_c_bit" => java.flag("has_bottle_2")?,
+        "lever_direction" => {
+            let facing = java.get("facing")?;
+            let axis = match facing {
+                "north" | "south" => "north_south",
+                _ => "east_west",
+            };
+            BlockState::String(match java.get("face")? {
+                "floor" => format!("up_{}", axis),
+                "ceiling" => format!("down_{}", axis),
+                _ => facing.to_string(),
+            })
+        }
+        "vine_direction_bits" => BlockState::Int(
+            VINE_SIDES
+                .iter()
+                .enumerate()
+                .filter(|(_, side)| java.get(side) == Some("true"))
+                .map(|(i, _)| 1 << i)
+                .sum(),
+        ),
+        "attachment" => BlockState::String(
+            match java.get("attachment").or(java.get("face"))? {
+                "floor" => "standing",
+                "ceiling" => "hanging",
+                "double_wall" => "multiple",
+                _ => "side",
+            }
+            .to_string(),
+        ),
+        "structure_block_type" => BlockState::String(java.get("mode")?.to_string()),
+        "conditional_bit" => java.flag("conditional")?,
+        "wall_post_bit" => java.flag("up")?,
+        "orientation" => BlockState::String(java.get("orientation")?.to_string()),
+        "crafting" => java.flag("crafting")?,
+        "portal_axis" => BlockState::String(java.get("axis")?.to_string()),
+        "end_portal_eye_bit" => java.flag("eye")?,
+        "disarmed_bit" => java.flag("disarmed")?,
+        "monster_egg_stone_type" => BlockState::String(monster_egg_stone(name)?.1.to_string()),
+        _ => {
+            let side = state.strip_prefix("wall_connection_type_")?;
+            BlockState::String(
+                match java.get(side)? {
+                    "low" => "short",
+                    "tall" => "tall",
+                    _ => "none",
+                }
+                .to_string(),
+            )
+        }
+    })
+}
+
+/// Converts a Java block, e.g. `minecraft:oak_stairs[facing=north,half=top]`, to the Bedrock
+/// block it looks like. Java states may be strings, ints or booleans, as parsed by
+/// `BlockType::from_str`. Every state of the Bedrock block is written; states the Java block
+/// does not decide take their default value. Returns `None` for blocks without a Bedrock
+/// counterpart in the bundled registry.
+pub fn java_to_bedrock(java: &BlockType) -> Option<BlockType> {
+    let name = java.type_id.strip_prefix("minecraft:")?;
+    let states = JavaStates(
+        java.states
+            .iter()
+            .map(|(state, value)| {
+                let value = match value {
+                    BlockState::String(value) => value.clone(),
+                    BlockState::Int(value) => value.to_string(),
+                    BlockState::Bool(value) => (*value != 0).to_string(),
+                };
+                (state.clone(), value)
+            })
+            .collect(),
+    );
+    let info = block_info(&format!("minecraft:{}", bedrock_name(name, &states)))?;
+    let mut bedrock = info.default_block();
+    for (state, values) in &info.states {
+        if let Some(value) = bedrock_state(state, name, &states).filter(|v| values.allows(v)) {
+            bedrock = bedrock.set_state(state, &value);
+        }
+    }
+    Some(bedrock)
+}
+
+/// Bedrock state values, read without caring whether the state is an int or a bool
+struct BedrockStates<'a>(&'a BlockType);
+
+impl BedrockStates<'_> {
+    fn get(&self, name: &str) -> Option<&BlockState> {
+        self.0
+            .states
+            .iter()
+            .find(|(state, _)| state == name)
+            .map(|(_, value)| value)
+    }
+    fn int(&self, name: &str) -> Option<i32> {
+        match self.get(name)? {
+            BlockState::Int(value) => Some(*value),
+            BlockState::Bool(value) => Some(*value as i32),
+            BlockState::String(_) => None,
+        }
+    }
+    fn string(&self, name: &str) -> Option<&str> {
+        match self.get(name)? {
+            BlockState::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Java id of a Bedrock block, without the namespace
+fn java_name(name: &str, bedrock: &BedrockStates) -> String {
+    let renamed = rename_to_java(name);
+    if renamed != name {
+        return renamed;
+    }
+    let wall_torch = !matches!(
+        bedrock.string("torch_facing_direction"),
+        None | Some("top" | "unknown")
+    );
+    match name {
+        "torch" if wall_torch => return "wall_torch".to_string(),
+        "soul_torch" if wall_torch => return "soul_wall_torch".to_string(),
+        "redstone_torch" | "unlit_redstone_torch" if wall_torch => {
+            return "redstone_wall_torch".to_string();
+        }
+        "unlit_redstone_torch" => return "redstone_torch".to_string(),
+        "powered_repeater" | "unpowered_repeater" => return "repeater".to_string(),
+        "powered_comparator" | "unpowered_comparator" => return "comparator".to_string(),
+        "daylight_detector_inverted" => return "daylight_detector".to_string(),
+        "standing_banner" => return "white_banner".to_string(),
+        "wall_banner" => return "white_wall_banner".to_string(),
+        "bed" => return "red_bed".to_string(),
+        "sticky_piston_arm_collision" => return "piston_head".to_string(),
+        "monster_egg" => {
+            let stone = bedrock.string("monster_egg_stone_type").unwrap_or("stone");
+            let (java, _) = MONSTER_EGG_STONES
+                .iter()
+                .find(|(_, bedrock)| *bedrock == stone)
+                .unwrap_or(&MONSTER_EGG_STONES[0]);
+            return format!("infested_{}", java);
+        }
+        _ if SKULLS.contains(&name) && matches!(bedrock.int("facing_direction"), Some(2..=5)) => {
+            let (mob, kind) = name.rsplit_once('_').unwrap();
+            return format!("{}_wall_{}", mob, kind);
+        }
+        "cauldron" => {
+            return match (
+                bedrock.string("cauldron_liquid"),
+                bedrock.int("fill_level").unwrap_or(0),
+            ) {
+                (_, 0) => "cauldron",
+                (Some("lava"), _) => "lava_cauldron",
+                (Some("powder_snow"), _) => "powder_snow_cauldron",
+                _ => "water_cauldron",
+            }
+            .to_string();
+        }
+        _ => {}
+    }
+    if name.starts_with("light_block_") {
+        return "light".to_string();
+    }
+    if let Some(lit) = name.strip_prefix("lit_")
+        && LIT_BLOCKS.contains(&lit)
+    {
+        return lit.to_string();
+    }
+    if let Some(slab) = name.strip_suffix("_double_slab") {
+        return rename_to_java(&format!("{}_slab", slab));
+    }
+    if let Some(wood) = name.strip_suffix("_standing_sign") {
+        return format!("{}_sign", wood.replace("darkoak", "dark_oak"));
+    }
+    if let Some(wood) = name.strip_suffix("_hanging_sign")
+        && bedrock.int("hanging") == Some(0)
+    {
+        return format!("{}_wall_hanging_sign", wood);
+    }
+    name.to_string()
+}
+
+/// Java states worked out from the Bedrock states
+fn java_states(name: &str, java_name: &str, bedrock: &BedrockStates) -> Vec<(String, String)> {
+    let mut java: Vec<(&str, String)> = vec![];
+    let flag = |value: i32| (value != 0).to_string();
+
+    // states the Bedrock block keeps in its id
+    if LIT_BLOCKS.contains(&java_name) {
+        java.push(("lit", name.starts_with("lit_").to_string()));
+    }
+    match java_name {
+        "redstone_torch" | "redstone_wall_torch" => {
+            java.push(("lit", (name != "unlit_redstone_torch").to_string()))
+        }
+        "repeater" => java.push(("powered", name.starts_with("powered_").to_string())),
+        "daylight_detector" => java.push(("inverted", name.ends_with("inverted").to_string())),
+        "light" => java.push(("level", name.trim_start_matches("light_block_").to_string())),
+        "piston_head" => java.push((
+            "type",
+            if name.starts_with("sticky_") {
+                "sticky"
+            } else {
+                "normal"
+            }
+            .to_string(),
+        )),
+        _ if name.ends_with("_double_slab") => java.push(("type", "double".to_string())),
+        _ => {}
+    }
+
+    for (state, value) in &bedrock.0.states {
+        let int = bedrock.int(state).unwrap_or(0);
+        let string = bedrock.string(state).unwrap_or("");
+        match state.as_str() {
+            "weirdo_direction" => {
+                if let Some(facing) = WEIRDO_DIRECTION.get(int as usize) {
+                    java.push(("facing", facing.name().to_string()));
+                }
+            }
+            "direction" => {
+                if let Some(facing) = direction_table(name).get(int as usize) {
+                    java.push(("facing", facing.name().to_string()));
+                }
+            }
+            "upside_down_bit" => {
+                java.push(("half", if int != 0 { "top" } else { "bottom" }.into()))
+            }
+            "upper_block_bit" => {
+                java.push(("half", if int != 0 { "upper" } else { "lower" }.into()))
+            }
+            "minecraft:vertical_half" if !name.ends_with("_double_slab") => {
+                java.push(("type", string.to_string()))
+            }
+            "pillar_axis" if !UNROTATED_PILLARS.contains(&java_name) => {
+                java.push(("axis", string.to_string()))
+            }
+            "door_hinge_bit" => {
+                java.push(("hinge", if int != 0 { "right" } else { "left" }.into()))
+            }
+            "open_bit" if name == "lever" => java.push(("powered", flag(int))),
+            "open_bit" => java.push(("open", flag(int))),
+            "in_wall_bit" => java.push(("in_wall", flag(int))),
+            "head_piece_bit" => java.push(("part", if int != 0 { "head" } else { "foot" }.into())),
+            "occupied_bit" => java.push(("occupied", flag(int))),
+            "minecraft:cardinal_direction" | "minecraft:facing_direction" => {
+                java.push(("facing", string.to_string()))
+            }
+            "facing_direction" => {
+                let Some(facing) = Facing::from_facing_direction(int) else {
+                    continue;
+                };
+                if name == "jigsaw" {
+                    let top = match facing {
+                        Facing::Down | Facing::Up => Cardinal::CLOCKWISE
+                            .get(bedrock.int("rotation").unwrap_or(0) as usize)
+                            .map_or("north", |c| c.name()),
+                        _ => "up",
+                    };
+                    java.push(("orientation", format!("{}_{}", facing.name(), top)));
+                } else if java_name.ends_with("_button") {
+                    let face = match facing {
+                        Facing::Up => "floor",
+                        Facing::Down => "ceiling",
+                        _ => "wall",
+                    };
+                    java.push(("face", face.to_string()));
+                    if face == "wall" {
+                        java.push(("facing", facing.name().to_string()));
+                    }
+                } else if SKULLS.contains(&name) {
+                    if java_name != name {
+                        java.push(("facing", facing.name().to_string()));
+                    }
+                } else if is_piston(name) {
+                    java.push(("facing", piston_facing(facing).name().to_string()));
+                } else if !name.ends_with("hanging_sign") || java_name.contains("_wall_") {
+                    java.push(("facing", facing.name().to_string()));
+                }
+            }
+            "torch_facing_direction" => {
+                if !matches!(string, "top" | "unknown") {
+                    java.push(("facing", string.to_string()));
+                }
+            }
+            "ground_sign_direction" => {
+                if !java_name.ends_with("wall_hanging_sign") {
+                    java.push(("rotation", int.to_string()));
+                }
+            }
+            "hanging" if !name.ends_with("hanging_sign") => java.push(("hanging", flag(int))),
+            "attached_bit" => java.push(("attached", flag(int))),
+            "persistent_bit" => java.push(("persistent", flag(int))),
+            "liquid_depth" | "composter_fill_level" => java.push(("level", int.to_string())),
+            "growth" if name == "beetroot" => {
+                let age = BEETROOT_GROWTH.iter().rposition(|growth| *growth <= int);
+                java.push(("age", age.unwrap_or(0).to_string()))
+            }
+            "growth" | "age" | "kelp_age" => java.push(("age", int.to_string())),
+            "age_bit" => java.push(("stage", int.to_string())),
+            "candles" => java.push(("candles", (int + 1).to_string())),
+            "lit" => java.push(("lit", flag(int))),
+            "extinguished" => java.push(("lit", flag(1 - int))),
+            "height" => java.push(("layers", (int + 1).to_string())),
+            "redstone_signal" => {
+                if name == "redstone_wire"
+                    || name.starts_with("daylight_detector")
+                    || name.ends_with("weighted_pressure_plate")
+                {
+                    java.push(("power", int.to_string()));
+                } else {
+                    java.push(("powered", flag(int)));
+                }
+            }
+            "button_pressed_bit" | "output_lit_bit" | "rail_data_bit" | "powered_bit" => {
+                java.push(("powered", flag(int)))
+            }
+            "triggered_bit" => java.push(("triggered", flag(int))),
+            "toggle_bit" if name == "hopper" => java.push(("enabled", flag(1 - int))),
+            "toggle_bit" => java.push(("powered", flag(int))),
+            "repeater_delay" => java.push(("delay", (int + 1).to_string())),
+            "output_subtract_bit" => {
+                java.push(("mode", if int != 0 { "subtract" } else { "compare" }.into()))
+            }
+            "rail_direction" => {
+                if let Some(shape) = RAIL_SHAPES.get(int as usize) {
+                    java.push(("shape", shape.to_string()));
+                }
+            }
+            "moisturized_amount" => java.push(("moisture", int.to_string())),
+            "bite_counter" => java.push(("bites", int.to_string())),
+            "cluster_count" => java.push(("pickles", (int + 1).to_string())),
+            "dead_bit" => java.push(("waterlogged", flag(1 - int))),
+            "respawn_anchor_charge" => java.push(("charges", int.to_string())),
+            "fill_level" if matches!(java_name, "water_cauldron" | "powder_snow_cauldron") => {
+                java.push(("level", ((int + 1) / 2).clamp(1, 3).to_string()))
+            }
+            "stability" => java.push(("distance", int.to_string())),
+            "explode_bit" => java.push(("unstable", flag(int))),
+            "brewing_stand_slot_a_bit" => java.push(("has_bottle_0", flag(int))),
+            "brewing_stand_slot_b_bit" => java.push(("has_bottle_1", flag(int))),
+            "brewing_stand_slot_c_bit" => java.push(("has_bottle_2", flag(int))),
+            "lever_direction" => {
+                // floor and ceiling levers only say which axis they lie along
+                let mut parts = string.split('_');
+                let (face, facing) = match (parts.next(), parts.next()) {
+                    (Some("up"), Some(facing)) => ("floor", facing),
+                    (Some("down"), Some(facing)) => ("ceiling", facing),
+                    _ => ("wall", string),
+                };
+                java.push(("face", face.to_string()));
+                java.push(("facing", facing.to_string()));
+            }
+            "vine_direction_bits" => {
+                for (i, side) in VINE_SIDES.iter().enumerate() {
+                    java.push((side, flag(int & (1 << i))));
+                }
+            }
+            "attachment" => {
+                let (state, value) = match (name, string) {
+                    ("bell", "standing") => ("attachment", "floor"),
+                    ("bell", "hanging") => ("attachment", "ceiling"),
+                    ("bell", "multiple") => ("attachment", "double_wall"),
+                    ("bell", _) => ("attachment", "single_wall"),
+                    (_, "standing") => ("face", "floor"),
+                    (_, "hanging") => ("face", "ceiling"),
+                    _ => ("face", "wall"),
+                };
+                java.push((state, value.to_string()));
+            }
+            "structure_block_type" => java.push(("mode", string.to_string())),
+            "conditional_bit" => java.push(("conditional", flag(int))),
+            "wall_post_bit" => java.push(("up", flag(int))),
+            "orientation" => java.push(("orientation", string.to_string())),
+            "crafting" => java.push(("crafting", flag(int))),
+            "portal_axis" => {
+                if matches!(string, "x" | "z") {
+                    java.push(("axis", string.to_string()));
+                }
+            }
+            "end_portal_eye_bit" => java.push(("eye", flag(int))),
+            "disarmed_bit" => java.push(("disarmed", flag(int))),
+            _ => {
+                if let (Some(side), BlockState::String(value)) =
+                    (state.strip_prefix("wall_connection_type_"), value)
+                {
+                    let value = match value.as_str() {
+                        "short" => "low",
+                        other => other,
+                    };
+                    java.push((side, value.to_string()));
+                }
+            }
+        }
+    }
+    java.into_iter()
+        .map(|(state, value)| (state.to_string(), value))
+        .collect()
+}
+
+/// Converts a Bedrock block to the Java block it looks like, with every state as a string.
+/// Java states the Bedrock block does not decide, such as the shape of stairs, are left out
+/// and take their default value in game. Colors that Bedrock keeps in block entities, like
+/// those of beds and banners, are not known here, so beds come out red and banners white.
+/// Returns `None` for blocks missing from the bundled registry.
+pub fn bedrock_to_java(bedrock: &BlockType) -> Option<BlockType> {
+    let name = bedrock.type_id.strip_prefix("minecraft:")?;
+    block_info(&bedrock.type_id)?;
+    let states = BedrockStates(bedrock);
+    let java_name = java_name(name, &states);
+    Some(java_states(name, &java_name, &states).iter().fold(
+        BlockType::new(&format!("minecraft:{}", java_name)),
+        |block, (state, value)| block.set_state(state, &BlockState::String(value.clone())),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(input: &str) -> BlockType {
+        input.parse().unwrap()
+    }
+
+    /// States in name order, as Java to Bedrock to Java does not keep their order
+    fn sorted(mut block: BlockType) -> BlockType {
+        block.states.sort_by(|(a, _), (b, _)| a.cmp(b));
+        block
+    }
+
+    #[test]
+    fn stairs_and_slabs() {
+        assert_eq!(
+            java_to_bedrock(&block("oak_stairs[facing=north,half=top,shape=outer_left]")),
+            Some(block(
+                r#"oak_stairs["weirdo_direction"=3,"upside_down_bit"=true]"#
+            ))
+        );
+        assert_eq!(
+            java_to_bedrock(&block("stone_brick_slab[type=top]")),
+            Some(block(
+                r#"stone_brick_slab["minecraft:vertical_half"="top"]"#
+            ))
+        );
+        assert_eq!(
+            java_to_bedrock(&block("stone_brick_slab[type=double]")),
+            Some(block(
+                r#"stone_brick_double_slab["minecraft:vertical_half"="bottom"]"#
+            ))
+        );
+        assert_eq!(
+            bedrock_to_java(&block(
+                r#"stone_brick_double_slab["minecraft:vertical_half"="bottom"]"#
+            )),
+            Some(block("stone_brick_slab[type=double]"))
+        );
+    }
+
+    #[test]
+    fn renames() {
+        for (java, bedrock) in [
+            ("cobweb", "web"),
+            ("bricks", "brick_block"),
+            ("cobblestone_stairs", "stone_stairs"),
+            ("stone_stairs", "normal_stone_stairs"),
+            ("cave_air", "air"),
+        ] {
+            let bedrock = format!("minecraft:{}", bedrock);
+            assert_eq!(
+                java_to_bedrock(&block(java)).map(|block| block.type_id),
+                Some(bedrock.clone())
+            );
+        }
+        assert_eq!(
+            bedrock_to_java(&block("web")),
+            Some(block("minecraft:cobweb"))
+        );
+    }
+
+    #[test]
+    fn missing_states_take_their_default() {
+        assert_eq!(
+            java_to_bedrock(&block("oak_log")),
+            Some(block(r#"oak_log["pillar_axis"="y"]"#))
+        );
+        assert_eq!(
+            java_to_bedrock(&block("quartz_block")),
+            Some(block(r#"quartz_block["pillar_axis"="y"]"#))
+        );
+    }
+
+    #[test]
+    fn pillar_axis_only_where_java_has_axis() {
+        assert_eq!(
+            bedrock_to_java(&block(r#"quartz_block["pillar_axis"="x"]"#)),
+            Some(block("quartz_block"))
+        );
+        assert_eq!(
+            bedrock_to_java(&block(r#"purpur_block["pillar_axis"="z"]"#)),
+            Some(block("purpur_block"))
+        );
+        assert_eq!(
+            bedrock_to_java(&block(r#"quartz_pillar["pillar_axis"="x"]"#)),
+            Some(block("quartz_pillar[axis=x]"))
+        );
+    }
+
+    #[test]
+    fn skulls() {
+        assert_eq!(
+            java_to_bedrock(&block("creeper_head[rotation=4]")),
+            Some(block(r#"creeper_head["facing_direction"=1]"#))
+        );
+        assert_eq!(
+            java_to_bedrock(&block("wither_skeleton_wall_skull[facing=west]")),
+            Some(block(r#"wither_skeleton_skull["facing_direction"=4]"#))
+        );
+        assert_eq!(
+            bedrock_to_java(&block(r#"wither_skeleton_skull["facing_direction"=4]"#)),
+            Some(block("wither_skeleton_wall_skull[facing=west]"))
+        );
+        assert_eq!(
+            bedrock_to_java(&block(r#"zombie_head["facing_direction"=1]"#)),
+            Some(block("zombie_head"))
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        for java in [
+            "stone",
+            "oak_stairs[facing=west,half=bottom]",
+            "spruce_door[facing=south,half=upper,hinge=right,open=true]",
+            "birch_trapdoor[facing=east,half=top,open=false]",
+            "oak_log[axis=z]",
+            "piston[facing=north]",
+            "lever[face=floor,facing=east,powered=true]",
+            "lever[face=wall,facing=north,powered=false]",
+            "jigsaw[orientation=up_east]",
+            "repeater[delay=3,facing=south,powered=true]",
+            "rail[shape=north_east]",
+            "wall_torch[facing=west]",
+            "redstone_wall_torch[facing=east,lit=false]",
+            "furnace[facing=north,lit=true]",
+            "wheat[age=5]",
+            "beetroots[age=3]",
+            "water_cauldron[level=2]",
+            "cobblestone_wall[east=low,north=tall,south=none,up=true,west=none]",
+            "light[level=7]",
+            "infested_cracked_stone_bricks",
+            "dark_oak_sign[rotation=9]",
+            "player_wall_head[facing=south]",
+        ] {
+            let java = block(java);
+            let bedrock = java_to_bedrock(&java).unwrap_or_else(|| panic!("{}", java));
+            assert_eq!(bedrock.validate(), Ok(()), "{}", bedrock);
+            assert_eq!(
+                bedrock_to_java(&bedrock).map(sorted),
+                Some(sorted(java.states.iter().fold(
+                    BlockType::new(&java.type_id),
+                    |block, (name, value)| {
+                        let value = match value {
+                            BlockState::String(value) => value.clone(),
+                            BlockState::Int(value) => value.to_string(),
+                            BlockState::Bool(value) => (*value != 0).to_string(),
+                        };
+                        block.set_state(name, &BlockState::String(value))
+                    }
+                ))),
+                "{} -> {}",
+                java,
+                bedrock
+            );
+        }
+    }
+
+    #[test]
+    fn unmapped_blocks() {
+        assert_eq!(java_to_bedrock(&block("not_a_block")), None);
+        assert_eq!(java_to_bedrock(&block("mymod:thing")), None);
+        assert_eq!(bedrock_to_java(&block("skull")), None);
+        assert_eq!(bedrock_to_java(&block("mymod:thing")), None);
+
+        let mut converter = crate::convert::Converter::new();
+        for java in ["stone", "not_a_block", "not_a_block", "mymod:thing"] {
+            converter.java_to_bedrock(&block(java));
+        }
+        assert_eq!(
+            converter.report().unmapped_blocks,
+            [
+                ("minecraft:not_a_block".to_string(), 2),
+                ("mymod:thing".to_string(), 1)
+            ]
+            .into()
+        );
+    }
+}
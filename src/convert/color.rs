@@ -0,0 +1,117 @@
+use crate::types::{BlockType, DyeColor, block_info};
+
+/// Average colors of the concrete, wool and terracotta of each dye color, in `DyeColor::ALL`
+/// order
+const DYED: [([u8; 3], [u8; 3], [u8; 3]); 16] = [
+    ([207, 213, 214], [234, 236, 237], [210, 178, 161]),
+    ([224, 97, 1], [241, 118, 20], [162, 84, 38]),
+    ([169, 48, 159], [190, 69, 180], [150, 88, 109]),
+    ([36, 137, 199], [58, 175, 217], [113, 109, 138]),
+    ([241, 175, 21], [249, 198, 40], [186, 133, 35]),
+    ([94, 169, 24], [112, 185, 26], [104, 118, 53]),
+    ([214, 101, 143], [238, 141, 172], [162, 78, 79]),
+    ([55, 58, 62], [63, 68, 72], [58, 42, 36]),
+    ([125, 125, 115], [142, 142, 135], [135, 107, 98]),
+    ([21, 119, 136], [21, 138, 145], [87, 91, 91]),
+    ([100, 32, 156], [122, 42, 173], [118, 70, 86]),
+    ([45, 47, 143], [53, 57, 157], [74, 60, 91]),
+    ([96, 60, 32], [114, 72, 41], [77, 51, 36]),
+    ([73, 91, 36], [85, 110, 28], [76, 83, 42]),
+    ([142, 33, 33], [161, 39, 35], [143, 61, 47]),
+    ([8, 10, 15], [21, 21, 26], [37, 23, 16]),
+];
+
+/// Average colors of full, opaque blocks with little pattern to them, besides the dyed ones
+const BLOCKS: [(&str, [u8; 3]); 57] = [
+    ("stone", [126, 126, 126]),
+    ("cobblestone", [128, 127, 128]),
+    ("smooth_stone", [159, 159, 159]),
+    ("granite", [149, 103, 86]),
+    ("diorite", [189, 188, 189]),
+    ("andesite", [136, 136, 137]),
+    ("deepslate", [80, 80, 83]),
+    ("blackstone", [42, 36, 41]),
+    ("tuff", [108, 109, 102]),
+    ("calcite", [223, 224, 220]),
+    ("dripstone_block", [134, 107, 92]),
+    ("dirt", [134, 96, 67]),
+    ("mud", [60, 57, 61]),
+    ("packed_mud", [142, 106, 79]),
+    ("mud_bricks", [137, 104, 79]),
+    ("moss_block", [89, 109, 45]),
+    ("sand", [219, 207, 163]),
+    ("red_sand", [191, 103, 33]),
+    ("sandstone", [216, 203, 155]),
+    ("red_sandstone", [181, 98, 31]),
+    ("gravel", [132, 127, 127]),
+    ("clay", [160, 166, 179]),
+    ("hardened_clay", [152, 94, 68]),
+    ("oak_planks", [162, 131, 79]),
+    ("spruce_planks", [115, 85, 49]),
+    ("birch_planks", [192, 175, 121]),
+    ("jungle_planks", [160, 115, 81]),
+    ("acacia_planks", [168, 90, 50]),
+    ("dark_oak_planks", [67, 43, 20]),
+    ("mangrove_planks", [117, 54, 48]),
+    ("cherry_planks", [226, 178, 172]),
+    ("crimson_planks", [101, 49, 71]),
+    ("warped_planks", [43, 105, 99]),
+    ("brick_block", [150, 97, 83]),
+    ("stone_bricks", [122, 121, 122]),
+    ("nether_brick", [44, 21, 26]),
+    ("red_nether_brick", [69, 7, 9]),
+    ("netherrack", [97, 38, 38]),
+    ("end_stone", [219, 222, 158]),
+    ("obsidian", [15, 10, 24]),
+    ("quartz_block", [235, 229, 222]),
+    ("purpur_block", [169, 125, 169]),
+    ("prismarine", [99, 156, 151]),
+    ("dark_prismarine", [51, 91, 75]),
+    ("gold_block", [246, 208, 61]),
+    ("iron_block", [220, 220, 220]),
+    ("diamond_block", [98, 237, 228]),
+    ("emerald_block", [42, 203, 87]),
+    ("lapis_block", [30, 67, 140]),
+    ("redstone_block", [175, 24, 5]),
+    ("coal_block", [16, 15, 15]),
+    ("netherite_block", [66, 61, 66]),
+    ("amethyst_block", [133, 97, 191]),
+    ("snow", [249, 254, 254]),
+    ("packed_ice", [141, 180, 250]),
+    ("bone_block", [229, 225, 207]),
+    ("hay_block", [166, 136, 38]),
+];
+
+/// Distance between two colors, weighting the channels by how much the eye notices them
+fn distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    let mean_red = (a[0] as u32 + b[0] as u32) / 2;
+    let [r, g, b] = [0, 1, 2].map(|i| (a[i] as i32 - b[i] as i32).pow(2) as u32);
+    (((512 + mean_red) * r) >> 8) + 4 * g + (((767 - mean_red) * b) >> 8)
+}
+
+/// Bedrock block whose average color is closest to `rgb`, chosen among full blocks without
+/// much of a pattern, like concrete, wool, terracotta, planks and stone. The block has the
+/// default value of each of its states.
+pub fn nearest_block(rgb: [u8; 3]) -> BlockType {
+    let dyed =
+        DyeColor::ALL
+            .into_iter()
+            .zip(DYED)
+            .flat_map(|(color, (concrete, wool, terracotta))| {
+                [
+                    (format!("{}_concrete", color.name()), concrete),
+                    (format!("{}_wool", color.name()), wool),
+                    (format!("{}_terracotta", color.name()), terracotta),
+                ]
+            });
+    let (name, _) = BLOCKS
+        .iter()
+        .map(|(name, color)| (name.to_string(), *color))
+        .chain(dyed)
+        .min_by_key(|(_, color)| distance(rgb, *color))
+        .expect("the color table is not empty");
+    // pillars like hay and bone blocks need their axis
+    block_info(&format!("minecraft:{}", name))
+        .expect("the color table only has vanilla blocks")
+        .default_block()
+}
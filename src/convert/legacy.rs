@@ -0,0 +1,698 @@
+use crate::{
+    convert::java_to_bedrock,
+    types::{BlockState, BlockType, DyeColor},
+};
+
+/// Wood types of `planks`, `sapling` and the other blocks that keep them in their data
+const WOODS: [&str; 6] = ["oak", "spruce", "birch", "jungle", "acacia", "dark_oak"];
+
+/// Facing of dispensers, pistons and other blocks that can face any way
+const FACING: [&str; 6] = ["down", "up", "north", "south", "west", "east"];
+
+/// Facing of blocks that keep it in the lowest two bits, like beds and fence gates
+const HORIZONTAL: [&str; 4] = ["south", "west", "north", "east"];
+
+const STAIRS: [&str; 4] = ["east", "west", "south", "north"];
+const DOOR: [&str; 4] = ["east", "south", "west", "north"];
+const TRAPDOOR: [&str; 4] = ["north", "south", "west", "east"];
+const RAIL_SHAPES: [&str; 10] = [
+    "north_south",
+    "east_west",
+    "ascending_east",
+    "ascending_west",
+    "ascending_north",
+    "ascending_south",
+    "south_east",
+    "south_west",
+    "north_west",
+    "north_east",
+];
+/// Slabs of `stone_slab`, `double_stone_slab` and the `stone_slab2` pair after them
+const STONE_SLABS: [&str; 8] = [
+    "smooth_stone",
+    "sandstone",
+    "petrified_oak",
+    "cobblestone",
+    "brick",
+    "stone_brick",
+    "nether_brick",
+    "quartz",
+];
+
+fn block(name: &str, states: &[(&str, &str)]) -> BlockType {
+    let mut block = BlockType::new(&format!("minecraft:{}", name));
+    for (state, value) in states {
+        block = block.set_state(state, &BlockState::String(value.to_string()));
+    }
+    block
+}
+
+fn flag(set: bool) -> &'static str {
+    if set { "true" } else { "false" }
+}
+
+fn color(data: u8) -> &'static str {
+    DyeColor::ALL[data as usize & 15].name()
+}
+
+fn wood(data: u8) -> Option<&'static str> {
+    WOODS.get(data as usize).copied()
+}
+
+fn facing(data: u8) -> Option<&'static str> {
+    FACING.get(data as usize & 7).copied()
+}
+
+/// Horizontal facing of blocks that only use ids 2 to 5 of `FACING`, like chests and ladders
+fn wall_facing(data: u8) -> &'static str {
+    match data & 7 {
+        3 => "south",
+        4 => "west",
+        5 => "east",
+        _ => "north",
+    }
+}
+
+fn axis(data: u8) -> &'static str {
+    match (data >> 2) & 3 {
+        1 => "x",
+        2 => "z",
+        _ => "y",
+    }
+}
+
+fn half(data: u8) -> &'static str {
+    if data & 8 != 0 { "top" } else { "bottom" }
+}
+
+fn slab(name: &str, data: u8) -> BlockType {
+    block(&format!("{}_slab", name), &[("type", half(data))])
+}
+
+fn stairs(name: &str, data: u8) -> BlockType {
+    block(
+        &format!("{}_stairs", name),
+        &[
+            ("facing", STAIRS[data as usize & 3]),
+            ("half", if data & 4 != 0 { "top" } else { "bottom" }),
+        ],
+    )
+}
+
+fn door(name: &str, data: u8) -> BlockType {
+    // the upper half keeps the hinge, the lower half the facing and whether it is open
+    if data & 8 != 0 {
+        block(
+            &format!("{}_door", name),
+            &[
+                ("half", "upper"),
+                ("hinge", if data & 1 != 0 { "right" } else { "left" }),
+                ("powered", flag(data & 2 != 0)),
+            ],
+        )
+    } else {
+        block(
+            &format!("{}_door", name),
+            &[
+                ("half", "lower"),
+                ("facing", DOOR[data as usize & 3]),
+                ("open", flag(data & 4 != 0)),
+            ],
+        )
+    }
+}
+
+fn trapdoor(name: &str, data: u8) -> BlockType {
+    block(
+        &format!("{}_trapdoor", name),
+        &[
+            ("facing", TRAPDOOR[data as usize & 3]),
+            ("open", flag(data & 4 != 0)),
+            ("half", half(data)),
+        ],
+    )
+}
+
+fn fence_gate(name: &str, data: u8) -> BlockType {
+    block(
+        &format!("{}_fence_gate", name),
+        &[
+            ("facing", HORIZONTAL[data as usize & 3]),
+            ("open", flag(data & 4 != 0)),
+        ],
+    )
+}
+
+/// Torch or wall torch, `prefix` being empty or e.g. `redstone_`
+fn torch(prefix: &str, data: u8, lit: Option<bool>) -> BlockType {
+    let mut torch = match data {
+        1..=4 => block(
+            &format!("{}wall_torch", prefix),
+            &[(
+                "facing",
+                ["east", "west", "south", "north"][data as usize - 1],
+            )],
+        ),
+        _ => block(&format!("{}torch", prefix), &[]),
+    };
+    if let Some(lit) = lit {
+        torch = torch.set_state("lit", &BlockState::String(flag(lit).to_string()));
+    }
+    torch
+}
+
+fn button(name: &str, data: u8) -> BlockType {
+    let (face, facing) = match data & 7 {
+        0 => ("ceiling", "north"),
+        1 => ("wall", "east"),
+        2 => ("wall", "west"),
+        3 => ("wall", "south"),
+        4 => ("wall", "north"),
+        _ => ("floor", "north"),
+    };
+    block(
+        &format!("{}_button", name),
+        &[
+            ("face", face),
+            ("facing", facing),
+            ("powered", flag(data & 8 != 0)),
+        ],
+    )
+}
+
+fn rail(name: &str, data: u8, powerable: bool) -> BlockType {
+    if !powerable {
+        return block(name, &[("shape", RAIL_SHAPES[data as usize % 10])]);
+    }
+    block(
+        name,
+        &[
+            ("shape", RAIL_SHAPES[(data & 7) as usize % 6]),
+            ("powered", flag(data & 8 != 0)),
+        ],
+    )
+}
+
+fn diode(name: &str, data: u8, powered: bool) -> BlockType {
+    let mut diode = block(
+        name,
+        &[
+            ("facing", HORIZONTAL[data as usize & 3]),
+            ("powered", flag(powered)),
+        ],
+    );
+    let (state, value) = if name == "repeater" {
+        ("delay", ((data >> 2) + 1).to_string())
+    } else {
+        (
+            "mode",
+            if data & 4 != 0 { "subtract" } else { "compare" }.to_string(),
+        )
+    };
+    diode = diode.set_state(state, &BlockState::String(value));
+    diode
+}
+
+fn int(name: &str, state: &str, value: u8) -> BlockType {
+    block(name, &[(state, &value.to_string())])
+}
+
+/// Converts a block of a pre-1.13 Java world or MCEdit schematic, given as its numeric id and
+/// data value, to the Java block it became in 1.13. States kept in block entities or worked
+/// out from neighbours, like the color of beds or the shape of stairs, are left at their
+/// defaults. Returns `None` for ids without a block, like 36, and for invalid data.
+pub fn legacy_to_java(id: u16, data: u8) -> Option<BlockType> {
+    let data = data & 15;
+    let named = |name: &str| Some(block(name, &[]));
+    let variant = |names: &[&str]| names.get(data as usize).and_then(|name| named(name));
+    match id {
+        0 => named("air"),
+        1 => variant(&[
+            "stone",
+            "granite",
+            "polished_granite",
+            "diorite",
+            "polished_diorite",
+            "andesite",
+            "polished_andesite",
+        ]),
+        2 => named("grass_block"),
+        3 => variant(&["dirt", "coarse_dirt", "podzol"]),
+        4 => named("cobblestone"),
+        5 => named(&format!("{}_planks", wood(data)?)),
+        6 => Some(int(
+            &format!("{}_sapling", wood(data & 7)?),
+            "stage",
+            data >> 3,
+        )),
+        7 => named("bedrock"),
+        8 | 9 => Some(int("water", "level", data)),
+        10 | 11 => Some(int("lava", "level", data)),
+        12 => variant(&["sand", "red_sand"]),
+        13 => named("gravel"),
+        14 => named("gold_ore"),
+        15 => named("iron_ore"),
+        16 => named("coal_ore"),
+        17 | 162 => {
+            let wood = if id == 17 {
+                WOODS[data as usize & 3]
+            } else {
+                WOODS.get(4 + (data as usize & 3))?
+            };
+            // logs with bark on all sides are wood
+            let kind = if data >> 2 == 3 { "wood" } else { "log" };
+            Some(block(
+                &format!("{}_{}", wood, kind),
+                &[("axis", axis(data))],
+            ))
+        }
+        18 | 161 => {
+            let wood = if id == 18 {
+                WOODS[data as usize & 3]
+            } else {
+                WOODS.get(4 + (data as usize & 3))?
+            };
+            Some(block(
+                &format!("{}_leaves", wood),
+                &[("persistent", flag(data & 4 != 0))],
+            ))
+        }
+        19 => variant(&["sponge", "wet_sponge"]),
+        20 => named("glass"),
+        21 => named("lapis_ore"),
+        22 => named("lapis_block"),
+        23 | 158 => Some(block(
+            if id == 23 { "dispenser" } else { "dropper" },
+            &[
+                ("facing", facing(data)?),
+                ("triggered", flag(data & 8 != 0)),
+            ],
+        )),
+        24 => variant(&["sandstone", "chiseled_sandstone", "cut_sandstone"]),
+        25 => named("note_block"),
+        26 => Some(block(
+            "red_bed",
+            &[
+                ("facing", HORIZONTAL[data as usize & 3]),
+                ("occupied", flag(data & 4 != 0)),
+                ("part", if data & 8 != 0 { "head" } else { "foot" }),
+            ],
+        )),
+        27 => Some(rail("powered_rail", data, true)),
+        28 => Some(rail("detector_rail", data, true)),
+        29 | 33 => Some(block(
+            if id == 29 { "sticky_piston" } else { "piston" },
+            &[("facing", facing(data)?), ("extended", flag(data & 8 != 0))],
+        )),
+        30 => named("cobweb"),
+        31 => variant(&["dead_bush", "short_grass", "fern"]),
+        32 => named("dead_bush"),
+        34 => Some(block(
+            "piston_head",
+            &[
+                ("facing", facing(data)?),
+                ("type", if data & 8 != 0 { "sticky" } else { "normal" }),
+            ],
+        )),
+        35 => named(&format!("{}_wool", color(data))),
+        37 => named("dandelion"),
+        38 => variant(&[
+            "poppy",
+            "blue_orchid",
+            "allium",
+            "azure_bluet",
+            "red_tulip",
+            "orange_tulip",
+            "white_tulip",
+            "pink_tulip",
+            "oxeye_daisy",
+        ]),
+        39 => named("brown_mushroom"),
+        40 => named("red_mushroom"),
+        41 => named("gold_block"),
+        42 => named("iron_block"),
+        43 => match data {
+            8 => named("smooth_stone"),
+            9 => named("smooth_sandstone"),
+            15 => named("smooth_quartz"),
+            _ => Some(block(
+                &format!("{}_slab", STONE_SLABS[data as usize & 7]),
+                &[("type", "double")],
+            )),
+        },
+        44 => Some(slab(STONE_SLABS[data as usize & 7], data)),
+        45 => named("bricks"),
+        46 => named("tnt"),
+        47 => named("bookshelf"),
+        48 => named("mossy_cobblestone"),
+        49 => named("obsidian"),
+        50 => Some(torch("", data, None)),
+        51 => named("fire"),
+        52 => named("spawner"),
+        53 => Some(stairs("oak", data)),
+        54 | 130 | 146 => Some(block(
+            match id {
+                54 => "chest",
+                130 => "ender_chest",
+                _ => "trapped_chest",
+            },
+            &[("facing", wall_facing(data))],
+        )),
+        55 => Some(int("redstone_wire", "power", data)),
+        56 => named("diamond_ore"),
+        57 => named("diamond_block"),
+        58 => named("crafting_table"),
+        59 => Some(int("wheat", "age", data & 7)),
+        60 => Some(int("farmland", "moisture", data & 7)),
+        61 | 62 => Some(block(
+            "furnace",
+            &[("facing", wall_facing(data)), ("lit", flag(id == 62))],
+        )),
+        63 => Some(int("oak_sign", "rotation", data)),
+        64 => Some(door("oak", data)),
+        65 => Some(block("ladder", &[("facing", wall_facing(data))])),
+        66 => Some(rail("rail", data, false)),
+        67 => Some(stairs("cobblestone", data)),
+        68 => Some(block("oak_wall_sign", &[("facing", wall_facing(data))])),
+        69 => {
+            let (face, facing) = match data & 7 {
+                0 => ("ceiling", "west"),
+                1 => ("wall", "east"),
+                2 => ("wall", "west"),
+                3 => ("wall", "south"),
+                4 => ("wall", "north"),
+                5 => ("floor", "north"),
+                6 => ("floor", "west"),
+                _ => ("ceiling", "north"),
+            };
+            Some(block(
+                "lever",
+                &[
+                    ("face", face),
+                    ("facing", facing),
+                    ("powered", flag(data & 8 != 0)),
+                ],
+            ))
+        }
+        70 | 72 => Some(block(
+            if id == 70 {
+                "stone_pressure_plate"
+            } else {
+                "oak_pressure_plate"
+            },
+            &[("powered", flag(data & 1 != 0))],
+        )),
+        71 => Some(door("iron", data)),
+        73 | 74 => Some(block("redstone_ore", &[("lit", flag(id == 74))])),
+        75 | 76 => Some(torch("redstone_", data, Some(id == 76))),
+        77 => Some(button("stone", data)),
+        78 => Some(int("snow", "layers", (data & 7) + 1)),
+        79 => named("ice"),
+        80 => named("snow_block"),
+        81 => Some(int("cactus", "age", data)),
+        82 => named("clay"),
+        83 => Some(int("sugar_cane", "age", data)),
+        84 => named("jukebox"),
+        85 => named("oak_fence"),
+        86 | 91 => Some(block(
+            if id == 86 {
+                "carved_pumpkin"
+            } else {
+                "jack_o_lantern"
+            },
+            &[("facing", HORIZONTAL[data as usize & 3])],
+        )),
+        87 => named("netherrack"),
+        88 => named("soul_sand"),
+        89 => named("glowstone"),
+        90 => Some(block(
+            "nether_portal",
+            &[("axis", if data == 2 { "z" } else { "x" })],
+        )),
+        92 => Some(int("cake", "bites", data.min(6))),
+        93 | 94 => Some(diode("repeater", data, id == 94)),
+        95 => named(&format!("{}_stained_glass", color(data))),
+        96 => Some(trapdoor("oak", data)),
+        97 => variant(&[
+            "infested_stone",
+            "infested_cobblestone",
+            "infested_stone_bricks",
+            "infested_mossy_stone_bricks",
+            "infested_cracked_stone_bricks",
+            "infested_chiseled_stone_bricks",
+        ]),
+        98 => variant(&[
+            "stone_bricks",
+            "mossy_stone_bricks",
+            "cracked_stone_bricks",
+            "chiseled_stone_bricks",
+        ]),
+        // the faces of mushroom blocks are left at their default, all cap
+        99 | 100 => match data {
+            10 | 15 => named("mushroom_stem"),
+            _ if id == 99 => named("brown_mushroom_block"),
+            _ => named("red_mushroom_block"),
+        },
+        101 => named("iron_bars"),
+        102 => named("glass_pane"),
+        103 => named("melon"),
+        104 => Some(int("pumpkin_stem", "age", data & 7)),
+        105 => Some(int("melon_stem", "age", data & 7)),
+        106 => Some(block(
+            "vine",
+            &[
+                ("south", flag(data & 1 != 0)),
+                ("west", flag(data & 2 != 0)),
+                ("north", flag(data & 4 != 0)),
+                ("east", flag(data & 8 != 0)),
+                ("up", flag(data == 0)),
+            ],
+        )),
+        107 => Some(fence_gate("oak", data)),
+        108 => Some(stairs("brick", data)),
+        109 => Some(stairs("stone_brick", data)),
+        110 => named("mycelium"),
+        111 => named("lily_pad"),
+        112 => named("nether_bricks"),
+        113 => named("nether_brick_fence"),
+        114 => Some(stairs("nether_brick", data)),
+        115 => Some(int("nether_wart", "age", data & 3)),
+        116 => named("enchanting_table"),
+        117 => named("brewing_stand"),
+        118 => match data & 3 {
+            0 => named("cauldron"),
+            level => Some(int("water_cauldron", "level", level)),
+        },
+        119 => named("end_portal"),
+        120 => Some(block(
+            "end_portal_frame",
+            &[
+                ("facing", HORIZONTAL[data as usize & 3]),
+                ("eye", flag(data & 4 != 0)),
+            ],
+        )),
+        121 => named("end_stone"),
+        122 => named("dragon_egg"),
+        123 | 124 => Some(block("redstone_lamp", &[("lit", flag(id == 124))])),
+        125 => Some(block(
+            &format!("{}_slab", wood(data & 7)?),
+            &[("type", "double")],
+        )),
+        126 => Some(slab(wood(data & 7)?, data)),
+        127 => Some(block(
+            "cocoa",
+            &[
+                ("facing", HORIZONTAL[data as usize & 3]),
+                ("age", &(data >> 2).min(2).to_string()),
+            ],
+        )),
+        128 => Some(stairs("sandstone", data)),
+        129 => named("emerald_ore"),
+        131 => Some(block(
+            "tripwire_hook",
+            &[
+                ("facing", HORIZONTAL[data as usize & 3]),
+                ("attached", flag(data & 4 != 0)),
+                ("powered", flag(data & 8 != 0)),
+            ],
+        )),
+        132 => named("tripwire"),
+        133 => named("emerald_block"),
+        134 => Some(stairs("spruce", data)),
+        135 => Some(stairs("birch", data)),
+        136 => Some(stairs("jungle", data)),
+        137 | 210 | 211 => Some(block(
+            match id {
+                137 => "command_block",
+                210 => "repeating_command_block",
+                _ => "chain_command_block",
+            },
+            &[
+                ("facing", facing(data)?),
+                ("conditional", flag(data & 8 != 0)),
+            ],
+        )),
+        138 => named("beacon"),
+        139 => variant(&["cobblestone_wall", "mossy_cobblestone_wall"]),
+        140 => named("flower_pot"),
+        141 => Some(int("carrots", "age", data & 7)),
+        142 => Some(int("potatoes", "age", data & 7)),
+        143 => Some(button("oak", data)),
+        145 => Some(block(
+            ["anvil", "chipped_anvil", "damaged_anvil"].get(data as usize >> 2)?,
+            &[("facing", HORIZONTAL[data as usize & 3])],
+        )),
+        147 => Some(int("light_weighted_pressure_plate", "power", data)),
+        148 => Some(int("heavy_weighted_pressure_plate", "power", data)),
+        149 | 150 => Some(diode("comparator", data, id == 150 || data & 8 != 0)),
+        151 | 178 => Some(block(
+            "daylight_detector",
+            &[("power", &data.to_string()), ("inverted", flag(id == 178))],
+        )),
+        152 => named("redstone_block"),
+        153 => named("nether_quartz_ore"),
+        154 => Some(block(
+            "hopper",
+            &[
+                // hoppers cannot face up, 1 is unused
+                ("facing", facing(data).filter(|facing| *facing != "up")?),
+                ("enabled", flag(data & 8 == 0)),
+            ],
+        )),
+        155 => match data {
+            0 => named("quartz_block"),
+            1 => named("chiseled_quartz_block"),
+            2..=4 => Some(block(
+                "quartz_pillar",
+                &[("axis", ["y", "x", "z"][data as usize - 2])],
+            )),
+            _ => None,
+        },
+        156 => Some(stairs("quartz", data)),
+        157 => Some(rail("activator_rail", data, true)),
+        159 => named(&format!("{}_terracotta", color(data))),
+        160 => named(&format!("{}_stained_glass_pane", color(data))),
+        163 => Some(stairs("acacia", data)),
+        164 => Some(stairs("dark_oak", data)),
+        165 => named("slime_block"),
+        166 => named("barrier"),
+        167 => Some(trapdoor("iron", data)),
+        168 => variant(&["prismarine", "prismarine_bricks", "dark_prismarine"]),
+        169 => named("sea_lantern"),
+        170 | 216 => Some(block(
+            if id == 170 { "hay_block" } else { "bone_block" },
+            &[("axis", axis(data))],
+        )),
+        171 => named(&format!("{}_carpet", color(data))),
+        172 => named("terracotta"),
+        173 => named("coal_block"),
+        174 => named("packed_ice"),
+        175 => {
+            // the upper half does not know which plant it is
+            let plant = [
+                "sunflower",
+                "lilac",
+                "tall_grass",
+                "large_fern",
+                "rose_bush",
+                "peony",
+            ]
+            .get(data as usize & 7)?;
+            Some(block(
+                plant,
+                &[("half", if data & 8 != 0 { "upper" } else { "lower" })],
+            ))
+        }
+        176 => Some(int("white_banner", "rotation", data)),
+        177 => Some(block("white_wall_banner", &[("facing", wall_facing(data))])),
+        179 => variant(&[
+            "red_sandstone",
+            "chiseled_red_sandstone",
+            "cut_red_sandstone",
+        ]),
+        180 => Some(stairs("red_sandstone", data)),
+        181 => match data {
+            8 => named("smooth_red_sandstone"),
+            _ => Some(block("red_sandstone_slab", &[("type", "double")])),
+        },
+        182 => Some(slab("red_sandstone", data)),
+        183..=187 => Some(fence_gate(
+            ["spruce", "birch", "jungle", "dark_oak", "acacia"][id as usize - 183],
+            data,
+        )),
+        188..=192 => named(&format!(
+            "{}_fence",
+            ["spruce", "birch", "jungle", "dark_oak", "acacia"][id as usize - 188]
+        )),
+        193..=197 => Some(door(
+            ["spruce", "birch", "jungle", "acacia", "dark_oak"][id as usize - 193],
+            data,
+        )),
+        198 => Some(block("end_rod", &[("facing", facing(data)?)])),
+        199 => named("chorus_plant"),
+        200 => Some(int("chorus_flower", "age", data.min(5))),
+        201 => named("purpur_block"),
+        202 => Some(block("purpur_pillar", &[("axis", axis(data))])),
+        203 => Some(stairs("purpur", data)),
+        204 => Some(block("purpur_slab", &[("type", "double")])),
+        205 => Some(slab("purpur", data)),
+        206 => named("end_stone_bricks"),
+        207 => Some(int("beetroots", "age", data & 3)),
+        208 => named("dirt_path"),
+        209 => named("end_gateway"),
+        212 => Some(int("frosted_ice", "age", data & 3)),
+        213 => named("magma_block"),
+        214 => named("nether_wart_block"),
+        215 => named("red_nether_bricks"),
+        217 => named("structure_void"),
+        218 => Some(block(
+            "observer",
+            &[("facing", facing(data)?), ("powered", flag(data & 8 != 0))],
+        )),
+        219..=234 => Some(block(
+            &format!("{}_shulker_box", color(id as u8 - 219)),
+            &[("facing", facing(data)?)],
+        )),
+        235..=250 => Some(block(
+            &format!("{}_glazed_terracotta", color(id as u8 - 235)),
+            &[("facing", HORIZONTAL[data as usize & 3])],
+        )),
+        251 => named(&format!("{}_concrete", color(data))),
+        252 => named(&format!("{}_concrete_powder", color(data))),
+        255 => Some(block(
+            "structure_block",
+            &[(
+                "mode",
+                ["save", "load", "corner", "data"].get(data as usize)?,
+            )],
+        )),
+        _ => None,
+    }
+}
+
+/// Converts a legacy block, see `legacy_to_java`, to the Bedrock block it looks like
+pub fn legacy_to_bedrock(id: u16, data: u8) -> Option<BlockType> {
+    java_to_bedrock(&legacy_to_java(id, data)?)
+}
+
+/// Java id of a block entity saved before 1.11, e.g. `Chest` or `Trap`, or `id` itself if it
+/// is already namespaced
+pub(crate) fn legacy_block_entity_id(id: &str) -> String {
+    let name = match id {
+        "Trap" => "dispenser",
+        "Cauldron" => "brewing_stand",
+        "MobSpawner" => "mob_spawner",
+        "RecordPlayer" => "jukebox",
+        "Music" => "noteblock",
+        "Control" => "command_block",
+        "EnchantTable" => "enchanting_table",
+        "Airportal" => "end_portal",
+        "EndGateway" => "end_gateway",
+        "FlowerPot" => "flower_pot",
+        "DLDetector" => "daylight_detector",
+        "Structure" => "structure_block",
+        id if id.contains(':') => return id.to_string(),
+        id => return format!("minecraft:{}", id.to_lowercase()),
+    };
+    format!("minecraft:{}", name)
+}
@@ -0,0 +1,111 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::{nbt::TagData, types::BlockType};
+
+mod block_entity;
+mod blocks;
+mod color;
+mod legacy;
+
+pub use block_entity::{bedrock_block_entity_to_java, java_block_entity_to_bedrock};
+pub use blocks::{bedrock_to_java, java_to_bedrock};
+pub use color::nearest_block;
+pub use legacy::{legacy_to_bedrock, legacy_to_java};
+
+pub(crate) use legacy::legacy_block_entity_id;
+
+/// Blocks and block entities a conversion had no mapping for, with how often each came up
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConversionReport {
+    /// Keyed by block id, or by `id:data` for legacy numeric blocks
+    pub unmapped_blocks: BTreeMap<String, u32>,
+    /// Keyed by block entity `id`
+    pub unmapped_block_entities: BTreeMap<String, u32>,
+}
+
+impl ConversionReport {
+    pub fn is_empty(&self) -> bool {
+        self.unmapped_blocks.is_empty() && self.unmapped_block_entities.is_empty()
+    }
+    pub(crate) fn add_block(&mut self, type_id: &str) {
+        *self.unmapped_blocks.entry(type_id.to_string()).or_default() += 1;
+    }
+    pub(crate) fn add_legacy_block(&mut self, id: u16, data: u8) {
+        self.add_block(&format!("{}:{}", id, data));
+    }
+    pub(crate) fn add_block_entity(&mut self, data: &TagData) {
+        let id = match data.find_tag("id") {
+            Some(TagData::String(id)) => id.clone(),
+            _ => String::new(),
+        };
+        *self.unmapped_block_entities.entry(id).or_default() += 1;
+    }
+}
+
+impl fmt::Display for ConversionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (kind, unmapped) in [
+            ("block", &self.unmapped_blocks),
+            ("block entity", &self.unmapped_block_entities),
+        ] {
+            for (id, count) in unmapped {
+                writeln!(f, "unmapped {} '{}' ({}x)", kind, id, count)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Converts blocks and block entities one at a time, keeping count of the ones without a
+/// mapping
+#[derive(Clone, Debug, Default)]
+pub struct Converter {
+    report: ConversionReport,
+}
+
+impl Converter {
+    pub fn new() -> Self {
+        Converter::default()
+    }
+    pub fn java_to_bedrock(&mut self, java: &BlockType) -> Option<BlockType> {
+        let bedrock = java_to_bedrock(java);
+        if bedrock.is_none() {
+            self.report.add_block(&java.type_id);
+        }
+        bedrock
+    }
+    pub fn bedrock_to_java(&mut self, bedrock: &BlockType) -> Option<BlockType> {
+        let java = bedrock_to_java(bedrock);
+        if java.is_none() {
+            self.report.add_block(&bedrock.type_id);
+        }
+        java
+    }
+    pub fn legacy_to_bedrock(&mut self, id: u16, data: u8) -> Option<BlockType> {
+        let bedrock = legacy_to_bedrock(id, data);
+        if bedrock.is_none() {
+            self.report.add_legacy_block(id, data);
+        }
+        bedrock
+    }
+    pub fn java_block_entity_to_bedrock(&mut self, data: &TagData) -> Option<TagData> {
+        let bedrock = java_block_entity_to_bedrock(data);
+        if bedrock.is_none() {
+            self.report.add_block_entity(data);
+        }
+        bedrock
+    }
+    pub fn bedrock_block_entity_to_java(&mut self, data: &TagData) -> Option<TagData> {
+        let java = bedrock_block_entity_to_java(data);
+        if java.is_none() {
+            self.report.add_block_entity(data);
+        }
+        java
+    }
+    pub fn report(&self) -> &ConversionReport {
+        &self.report
+    }
+    pub fn into_report(self) -> ConversionReport {
+        self.report
+    }
+}
@@ -0,0 +1,169 @@
+use std::fmt;
+
+use crate::types::{Cardinal, Facing, Vec3};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// The data does not describe a valid `.mcstructure`
+    InvalidStructure(String),
+    /// The block cannot hold items
+    NotAContainer(String),
+    /// The block does not support the block entity being written, e.g. sign text on a chest
+    UnexpectedBlock {
+        type_id: String,
+        expected: &'static str,
+    },
+    /// The slot is past the last slot of the container
+    InvalidSlot {
+        type_id: String,
+        slot: u8,
+        slots: u8,
+    },
+    /// The count is 0 or more than fits in one slot
+    InvalidItemCount {
+        type_id: String,
+        count: u8,
+        max: u8,
+    },
+    /// The item cannot be stored inside the container, e.g. a shulker box in a shulker box
+    InvalidNesting {
+        item: String,
+        container: String,
+    },
+    BundleFull {
+        weight: u32,
+        capacity: u32,
+    },
+    /// The text is not a valid block, see `BlockType::from_str`
+    InvalidBlockString {
+        input: String,
+        reason: String,
+    },
+    /// The block is not in the bundled block registry
+    UnknownBlock(String),
+    /// The block has no such state, or the state has a value the block does not allow
+    InvalidBlockState {
+        type_id: String,
+        state: String,
+        reason: String,
+    },
+    /// No structure pool has this name, or the pool has no pieces
+    UnknownPool(String),
+    /// The position already holds a block entity of another kind, e.g. a sign's where a chest's
+    /// is being written
+    BlockEntityMismatch {
+        existing: String,
+        expected: String,
+    },
+    /// The rows of a snake chain layout turn along their own direction, which would fold the
+    /// chain onto itself
+    InvalidChainLayout {
+        direction: Cardinal,
+        turn: Facing,
+    },
+    /// The palette has no block at this index
+    UnknownPaletteIndex(i32),
+    /// The position is outside of the structure
+    OutOfBounds {
+        loc: Vec3<i32>,
+        size: Vec3<i32>,
+    },
+    /// The size is negative on some axis, or holds more blocks than a structure can index
+    InvalidSize(Vec3<i32>),
+    /// The minimum corner of a region is past its maximum corner on some axis
+    InvalidRegion {
+        min: Vec3<i32>,
+        max: Vec3<i32>,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidStructure(reason) => write!(f, "invalid structure: {}", reason),
+            Error::NotAContainer(type_id) => write!(f, "'{}' is not a container", type_id),
+            Error::UnexpectedBlock { type_id, expected } => {
+                write!(f, "'{}' is not a {}", type_id, expected)
+            }
+            Error::InvalidSlot {
+                type_id,
+                slot,
+                slots,
+            } => write!(
+                f,
+                "slot {} is out of range for '{}', which has {} slots",
+                slot, type_id, slots
+            ),
+            Error::InvalidItemCount {
+                type_id,
+                count,
+                max,
+            } => write!(
+                f,
+                "count {} of '{}' is not between 1 and {}",
+                count, type_id, max
+            ),
+            Error::InvalidNesting { item, container } => {
+                write!(f, "'{}' cannot be placed inside '{}'", item, container)
+            }
+            Error::BundleFull { weight, capacity } => write!(
+                f,
+                "bundle contents weigh {}, more than its capacity of {}",
+                weight, capacity
+            ),
+            Error::InvalidBlockString { input, reason } => {
+                write!(f, "invalid block '{}': {}", input, reason)
+            }
+            Error::UnknownBlock(type_id) => write!(f, "unknown block '{}'", type_id),
+            Error::InvalidBlockState {
+                type_id,
+                state,
+                reason,
+            } => write!(f, "invalid state '{}' of '{}': {}", state, type_id, reason),
+            Error::UnknownPool(name) => write!(f, "no structure pool with pieces named '{}'", name),
+            Error::BlockEntityMismatch { existing, expected } => write!(
+                f,
+                "block entity '{}' is in the way of a '{}' block entity",
+                existing, expected
+            ),
+            Error::InvalidChainLayout { direction, turn } => write!(
+                f,
+                "chain rows running {} cannot turn {}",
+                direction.name(),
+                turn.name()
+            ),
+            Error::UnknownPaletteIndex(index) => {
+                write!(f, "the palette has no block at index {}", index)
+            }
+            Error::OutOfBounds { loc, size } => write!(
+                f,
+                "position ({}, {}, {}) is outside of the structure of size ({}, {}, {})",
+                loc.x(),
+                loc.y(),
+                loc.z(),
+                size.x(),
+                size.y(),
+                size.z()
+            ),
+            Error::InvalidSize(size) => write!(
+                f,
+                "invalid structure size ({}, {}, {})",
+                size.x(),
+                size.y(),
+                size.z()
+            ),
+            Error::InvalidRegion { min, max } => write!(
+                f,
+                "region from ({}, {}, {}) to ({}, {}, {}) is inverted",
+                min.x(),
+                min.y(),
+                min.z(),
+                max.x(),
+                max.y(),
+                max.z()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -1,3 +1,5 @@
+pub mod convert;
+pub mod error;
 pub mod nbt;
 pub mod structure;
 pub mod types;
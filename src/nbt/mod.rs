@@ -1,6 +1,21 @@
-#[derive(Clone, Debug)]
+use crate::error::Error;
+
+/// Deepest nesting of lists and compounds that is read, like Java Edition's limit
+const MAX_DEPTH: usize = 512;
+
+fn invalid(reason: String) -> Error {
+    Error::InvalidStructure(reason)
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct TagList(Vec<(String, TagData)>);
 
+impl Default for TagList {
+    fn default() -> Self {
+        TagList::new()
+    }
+}
+
 impl TagList {
     pub fn new () -> TagList {
         TagList (vec![])
@@ -10,7 +25,7 @@ impl TagList {
         for (id, data) in tags {
             new.add_tag(&id, data);
         }
-        return new;
+        new
     }
     pub fn print(&self, indent: usize) {
         for (id, data) in &self.0 {
@@ -47,34 +62,46 @@ impl TagList {
         }
         None
     }
-    fn read_next_tag(&mut self, r: &mut NbtReader) {
-        let kind = TagKind::from_u8(r.next_byte());
+    /// Non-mutable counterpart of `get_tag`
+    pub fn find_tag (&self, id: &str) -> Option<&TagData> {
+        self.0.iter().find(|tag| tag.0 == id).map(|tag| &tag.1)
+    }
+    pub fn remove_tag (&mut self, id: &str) -> Option<TagData> {
+        let idx = self.0.iter().position(|tag| tag.0 == id)?;
+        Some(self.0.remove(idx).1)
+    }
+    pub fn iter (&self) -> std::slice::Iter<'_, (String, TagData)> {
+        self.0.iter()
+    }
+    fn read_next_tag(&mut self, r: &mut NbtReader) -> Result<(), Error> {
+        let kind = TagKind::from_u8(r.next_byte()?)?;
 
-        let id_size = r.u16_from_next_bytes();
-        
-        let mut id = Vec::<u8>::new();
-        for _ in 0..id_size {
-            id.push(r.next_byte())
-        }
-        let id = String::from_utf8(id);
-        match id {
-            Err(_err) => panic!("Invalid id name"),
-            Ok(id) => {
-                self.add_tag(&id, TagData::from_bytes(r, &kind));
-            },
-        }
+        let id_size = r.u16_from_next_bytes()?;
+        let id = String::from_utf8(r.take(id_size as usize)?.to_vec())
+            .map_err(|_| invalid("tag name is not valid UTF-8".to_string()))?;
+        let data = TagData::from_bytes(r, &kind)?;
+        self.add_tag(&id, data);
+        Ok(())
+    }
+}
+
+impl IntoIterator for TagList {
+    type Item = (String, TagData);
+    type IntoIter = std::vec::IntoIter<(String, TagData)>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
     }
 }
 
 macro_rules! define_conversion_from_fn {
     ($fn_name:ident, $type:ty, $size:literal) => {
-        pub fn $fn_name(&mut self) -> $type {
-            let bytes = self.next_bytes::<$size>();
-            if self.little_endian {
+        pub fn $fn_name(&mut self) -> Result<$type, Error> {
+            let bytes = self.next_bytes::<$size>()?;
+            Ok(if self.little_endian {
                 <$type>::from_le_bytes(bytes)
             } else {
                 <$type>::from_be_bytes(bytes)
-            }
+            })
         }
     };
 }
@@ -113,6 +140,8 @@ pub struct NbtReader {
     little_endian: bool,
     bytes: Vec<u8>,
     ptr: usize,
+    /// lists and compounds currently being read
+    depth: usize,
 }
 
 impl NbtReader {
@@ -124,6 +153,7 @@ impl NbtReader {
             little_endian,
             bytes,
             ptr: 0,
+            depth: 0,
         }
     }
     define_conversion_from_fn! {u16_from_next_bytes, u16, 2}
@@ -133,20 +163,39 @@ impl NbtReader {
     define_conversion_from_fn! {i64_from_next_bytes, i64, 8}
     define_conversion_from_fn! {f32_from_next_bytes, f32, 4}
     define_conversion_from_fn! {f64_from_next_bytes, f64, 8}
-    pub fn next_byte(&mut self) -> u8 {
-        let byte = self.bytes[self.ptr];
-        self.ptr += 1;
-        byte
+    /// Reads the next `len` bytes, failing if the data ends before that
+    pub fn take(&mut self, len: usize) -> Result<&[u8], Error> {
+        if len > self.bytes.len() - self.ptr {
+            return Err(invalid("unexpected end of NBT data".to_string()));
+        }
+        self.ptr += len;
+        Ok(&self.bytes[self.ptr - len..self.ptr])
+    }
+    pub fn next_byte(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
     }
-    pub fn next_bytes<const N: usize>(&mut self) -> [u8; N] {
+    pub fn next_bytes<const N: usize>(&mut self) -> Result<[u8; N], Error> {
         let mut bytes = [0u8; N];
-        for i in 0..N {
-            bytes[i] = self.next_byte();
-        }
-        bytes
+        bytes.copy_from_slice(self.take(N)?);
+        Ok(bytes)
+    }
+    pub fn peek_byte(&self, offset: usize) -> Result<u8, Error> {
+        self.bytes
+            .get(self.ptr + offset)
+            .copied()
+            .ok_or_else(|| invalid("unexpected end of NBT data".to_string()))
     }
-    pub fn peek_byte(&self, offset: usize) -> u8 {
-        self.bytes[self.ptr + offset]
+    /// Length of an array or list, which must not be negative
+    fn next_len(&mut self) -> Result<usize, Error> {
+        let len = self.i32_from_next_bytes()?;
+        usize::try_from(len).map_err(|_| invalid(format!("negative length {}", len)))
+    }
+    fn enter(&mut self) -> Result<(), Error> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(invalid(format!("tags nested deeper than {}", MAX_DEPTH)));
+        }
+        Ok(())
     }
 }
 
@@ -180,8 +229,8 @@ impl NbtWriter {
 }
 
 impl TagKind {
-    pub fn from_u8(value: u8) -> TagKind {
-        match value {
+    pub fn from_u8(value: u8) -> Result<TagKind, Error> {
+        Ok(match value {
             0 => TagKind::End,
             1 => TagKind::Byte,
             2 => TagKind::Short,
@@ -195,8 +244,8 @@ impl TagKind {
             10 => TagKind::Compound,
             11 => TagKind::IntArray,
             12 => TagKind::LongArray,
-            _ => panic!("Unknown tag 0x{:02X}", value),
-        }
+            _ => return Err(invalid(format!("unknown tag 0x{:02X}", value))),
+        })
     }
 }
 
@@ -214,24 +263,27 @@ impl NbtTree {
     pub fn add_entry(&mut self, id: &str, data: TagData) {
         self.entries.add_tag(id, data);
     }
+    pub fn into_entries(self) -> TagList {
+        self.entries
+    }
     pub fn as_bytes(&self, little_endian: bool) -> Vec<u8> {
         let mut w = NbtWriter::new(little_endian);
         self.entries.as_bytes(&mut w);
         w.bytes
     }
-    pub fn from_bytes(little_endian: bool, bytes: Vec<u8>) -> NbtTree {
+    pub fn from_bytes(little_endian: bool, bytes: Vec<u8>) -> Result<NbtTree, Error> {
         let mut r = NbtReader::new(little_endian, bytes);
         let mut tree = NbtTree::new(vec![]);
         loop {
-            tree.entries.read_next_tag(&mut r);
+            tree.entries.read_next_tag(&mut r)?;
             if r.read_all() {
-                break tree;
+                break Ok(tree);
             }
         }
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum TagData {
     End,
     Byte(i8),
@@ -264,7 +316,7 @@ impl TagData {
                 println!("\x1b[36mTAG_Double\x1b[0m = \x1b[33m{}\x1b[0m", double)
             }
             TagData::ByteArray(_size, list) => {
-                if list.len() == 0 {
+                if list.is_empty() {
                     println!("\x1b[36mTAG_ByteArray\x1b[0m = []");
                     return;
                 }
@@ -279,7 +331,7 @@ impl TagData {
                 println!("\x1b[36mTAG_String\x1b[0m = \x1b[32m'{}'\x1b[0m", string)
             }
             TagData::List(_kind, _size, list) => {
-                if list.len() == 0 {
+                if list.is_empty() {
                     println!("\x1b[36mTAG_List\x1b[0m = []");
                     return;
                 }
@@ -300,7 +352,7 @@ impl TagData {
                 println!("{}}}", "  ".repeat(indent));
             }
             TagData::IntArray(_size, list) => {
-                if list.len() == 0 {
+                if list.is_empty() {
                     println!("\x1b[36mTAG_IntArray\x1b[0m = []");
                     return;
                 }
@@ -352,11 +404,51 @@ impl TagData {
             panic!("cannot use 'add_tag' on a non-compound tag");
         }
     }
+    /// Returns `None` on non-compound tags instead of panicking
+    pub fn find_tag(&self, id: &str) -> Option<&TagData> {
+        if let TagData::Compound(tags) = self {
+            tags.find_tag(id)
+        } else {
+            None
+        }
+    }
+    pub fn remove_tag(&mut self, id: &str) -> Option<TagData> {
+        if let TagData::Compound(tags) = self {
+            tags.remove_tag(id)
+        } else {
+            panic!("cannot use 'remove_tag' on a non-compound tag");
+        }
+    }
+    /// Unpacks `count` values of `bits` bits from a long array, lowest bits first, where a value
+    /// may span two longs as in Litematica block states. Returns `None` on non-long-array tags
+    /// and arrays too short to hold `count` values.
+    pub fn unpack_long_array(&self, bits: u32, count: usize) -> Option<Vec<u32>> {
+        let TagData::LongArray(_, longs) = self else {
+            return None;
+        };
+        if !(1..=32).contains(&bits) || longs.len() * 64 < count * bits as usize {
+            return None;
+        }
+        let mask = (1u64 << bits) - 1;
+        let values = (0..count)
+            .map(|i| {
+                let start = i * bits as usize;
+                let (long, offset) = (start / 64, start % 64);
+                let mut value = longs[long] as u64 >> offset;
+                if offset + bits as usize > 64 {
+                    value |= (longs[long + 1] as u64) << (64 - offset);
+                }
+                (value & mask) as u32
+            })
+            .collect();
+        Some(values)
+    }
     pub fn as_bytes(&self, w: &mut NbtWriter) {
         match self {
             TagData::Byte(byte) => w.write_bytes(&i8::to_be_bytes(*byte)),
             TagData::Short(short) => w.i16_write_to_bytes(*short),
             TagData::Int(int) => w.i32_write_to_bytes(*int),
+            TagData::Long(long) => w.i64_write_to_bytes(*long),
             TagData::Float(float) => w.f32_write_to_bytes(*float),
             TagData::Double(double) => w.f64_write_to_bytes(*double),
             TagData::String(string) => {
@@ -378,80 +470,93 @@ impl TagData {
                 compound.as_bytes(w);
                 w.write(0);
             }
+            TagData::ByteArray(size, list) => {
+                w.i32_write_to_bytes(*size);
+                for byte in list {
+                    w.write(*byte as u8);
+                }
+            }
+            TagData::IntArray(size, list) => {
+                w.i32_write_to_bytes(*size);
+                for int in list {
+                    w.i32_write_to_bytes(*int);
+                }
+            }
+            TagData::LongArray(size, list) => {
+                w.i32_write_to_bytes(*size);
+                for long in list {
+                    w.i64_write_to_bytes(*long);
+                }
+            }
             _ => panic!("Unknown tag"),
         }
     }
-    pub fn from_bytes(r: &mut NbtReader, kind: &TagKind) -> TagData {
-        match kind {
-            TagKind::Byte => TagData::Byte(i8::from_le_bytes([r.next_byte()])),
-            TagKind::Short => TagData::Short(r.i16_from_next_bytes()),
-            TagKind::Int => TagData::Int(r.i32_from_next_bytes()),
-            TagKind::Long => TagData::Long(r.i64_from_next_bytes()),
-            TagKind::Float => TagData::Float(r.f32_from_next_bytes()),
-            TagKind::Double => TagData::Double(r.f64_from_next_bytes()),
+    pub fn from_bytes(r: &mut NbtReader, kind: &TagKind) -> Result<TagData, Error> {
+        Ok(match kind {
+            TagKind::Byte => TagData::Byte(i8::from_le_bytes([r.next_byte()?])),
+            TagKind::Short => TagData::Short(r.i16_from_next_bytes()?),
+            TagKind::Int => TagData::Int(r.i32_from_next_bytes()?),
+            TagKind::Long => TagData::Long(r.i64_from_next_bytes()?),
+            TagKind::Float => TagData::Float(r.f32_from_next_bytes()?),
+            TagKind::Double => TagData::Double(r.f64_from_next_bytes()?),
             TagKind::ByteArray => {
-                let size = r.i32_from_next_bytes();
-
-                let mut data = Vec::<i8>::new();
-
-                for _i in 0..size {
-                    data.push(i8::from_le_bytes([r.next_byte()]));
-                }
-
-                TagData::ByteArray(size, data)
+                let size = r.next_len()?;
+                let data = r.take(size)?.iter().map(|byte| *byte as i8).collect();
+                TagData::ByteArray(size as i32, data)
             }
             TagKind::String => {
-                let size = r.u16_from_next_bytes();
-
-                let mut data: Vec<u8> = vec![];
-
-                for _i in 0..size {
-                    data.push(r.next_byte());
-                }
-
-                let string = String::from_utf8(data);
-                match string {
-                    Err(_error) => panic!("Invalid string"),
-                    Ok(string) => TagData::String(string),
-                }
+                let size = r.u16_from_next_bytes()?;
+                let string = String::from_utf8(r.take(size as usize)?.to_vec())
+                    .map_err(|_| invalid("string is not valid UTF-8".to_string()))?;
+                TagData::String(string)
             }
             TagKind::List => {
-                let tag = TagKind::from_u8(r.next_byte());
-                let size = r.u32_from_next_bytes();
+                let tag = TagKind::from_u8(r.next_byte()?)?;
+                let size = r.next_len()?;
 
+                r.enter()?;
                 let mut data = Vec::<TagData>::new();
-
                 for _i in 0..size {
-                    data.push(TagData::from_bytes(r, &tag))
+                    data.push(TagData::from_bytes(r, &tag)?)
                 }
+                r.depth -= 1;
 
-                TagData::List(tag, size, data)
+                TagData::List(tag, size as u32, data)
             }
             TagKind::Compound => {
+                r.enter()?;
                 let mut data = TagList::new();
                 loop {
-                    let kind = TagKind::from_u8(r.peek_byte(0));
+                    let kind = TagKind::from_u8(r.peek_byte(0)?)?;
                     if let TagKind::End = kind {
-                        r.next_byte();
+                        r.next_byte()?;
+                        r.depth -= 1;
                         break TagData::Compound(data);
                     }
-                    data.read_next_tag(r);
+                    data.read_next_tag(r)?;
                 }
             }
             TagKind::IntArray => {
-                let size = r.i32_from_next_bytes();
+                let size = r.next_len()?;
 
                 let mut data = Vec::<i32>::new();
-
                 for _i in 0..size {
-                    data.push(r.i32_from_next_bytes())
+                    data.push(r.i32_from_next_bytes()?)
                 }
 
-                TagData::IntArray(size, data)
+                TagData::IntArray(size as i32, data)
             }
-            _ => {
-                panic!("Unknown tag 0x{:02X}", kind.clone() as u8);
+            TagKind::LongArray => {
+                let size = r.next_len()?;
+
+                let mut data = Vec::<i64>::new();
+                for _i in 0..size {
+                    data.push(r.i64_from_next_bytes()?)
+                }
+
+                TagData::LongArray(size as i32, data)
             }
-        }
+            TagKind::End => return Err(invalid("unexpected end tag".to_string())),
+        })
     }
 }
@@ -0,0 +1,202 @@
+use crate::{
+    error::Error,
+    nbt::TagData,
+    structure::{MCStructure, chest_pair_offset, index_from_vec3, set_chest_pair, vec3_from_index},
+    types::{BlockState, BlockType, Cardinal, Vec3, container_block_entity},
+};
+
+impl MCStructure {
+    /// Places a double chest with its front facing `facing`. The second half goes one block
+    /// clockwise of `facing` from `loc`, e.g. east of `loc` for a north facing chest.
+    pub fn place_double_chest(
+        &mut self,
+        loc: Vec3<i32>,
+        facing: Cardinal,
+        trapped: bool,
+    ) -> Result<(), Error> {
+        let halves = [loc, loc + facing.clockwise().offset()];
+        if let Some(half) = halves.iter().find(|half| !self.contains(**half)) {
+            return Err(Error::OutOfBounds {
+                loc: *half,
+                size: self.size,
+            });
+        }
+        let block = BlockType::new(if trapped {
+            "minecraft:trapped_chest"
+        } else {
+            "minecraft:chest"
+        })
+        .set_state(
+            "minecraft:cardinal_direction",
+            &BlockState::String(facing.name().to_string()),
+        );
+        let palette_block = self.palette_find_or_add(&block)?;
+
+        for (i, half) in halves.iter().enumerate() {
+            let pair = halves[1 - i];
+            let index = index_from_vec3(*half, self.size) as u32;
+            self.setblock_palette(*half, palette_block)?;
+            self.block_position_data.remove(&index);
+            let data =
+                container_block_entity(self, &block, index).expect("chests are always containers");
+            data.set_tag("pairlead", TagData::Byte((i == 0) as i8));
+            set_chest_pair(data, pair);
+        }
+        Ok(())
+    }
+
+    /// Returns the positions of chests that are paired with a block that is not paired back
+    /// by a chest of the same type facing the same way
+    pub fn find_half_paired_chests(&self) -> Vec<Vec3<i32>> {
+        let mut half_paired = vec![];
+        for (index, data) in &self.block_position_data {
+            let Some(chest) = self.chest_at_index(*index as usize) else {
+                continue;
+            };
+            let Some(offset) = chest_pair_offset(data) else {
+                continue;
+            };
+            let loc = vec3_from_index(*index as usize, self.size);
+            // damaged files can pair chests with any position, so the offset is checked first
+            let adjacent = matches!((*offset.x(), *offset.z()), (-1 | 1, 0) | (0, -1 | 1));
+            let paired_back = adjacent
+                && self.contains(loc + offset)
+                && self.chest_at_index(index_from_vec3(loc + offset, self.size)) == Some(chest)
+                && self
+                    .block_entity_at_index(index_from_vec3(loc + offset, self.size) as u32)
+                    .and_then(chest_pair_offset)
+                    == Some(Vec3::_000 - offset);
+            if !paired_back {
+                half_paired.push(loc);
+            }
+        }
+        half_paired
+    }
+
+    /// Type and facing of the chest or trapped chest at `index`
+    fn chest_at_index(&self, index: usize) -> Option<(&str, Option<&BlockState>)> {
+        let block = self.blocks[index];
+        if block < 0 {
+            return None;
+        }
+        let block = &self.palette[block as usize];
+        if !matches!(
+            block.type_id.as_str(),
+            "minecraft:chest" | "minecraft:trapped_chest"
+        ) {
+            return None;
+        }
+        let facing = block
+            .states
+            .iter()
+            .find(|(state, _)| state == "minecraft:cardinal_direction")
+            .map(|(_, facing)| facing);
+        Some((&block.type_id, facing))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chest(facing: &str) -> BlockType {
+        BlockType::new("minecraft:chest").set_state(
+            "minecraft:cardinal_direction",
+            &BlockState::String(facing.to_string()),
+        )
+    }
+
+    fn pair_of(structure: &MCStructure, loc: Vec3<i32>) -> Option<Vec3<i32>> {
+        let index = index_from_vec3(loc, structure.size) as u32;
+        chest_pair_offset(structure.block_entity_at_index(index)?).map(|offset| loc + offset)
+    }
+
+    #[test]
+    fn place_double_chest_pairs_both_halves() {
+        let mut structure = MCStructure::new(Vec3::new(2, 1, 2));
+        structure
+            .place_double_chest(Vec3::new(0, 0, 1), Cardinal::North, true)
+            .unwrap();
+
+        assert_eq!(
+            structure.getblock(Vec3::new(1, 0, 1)).type_id,
+            "minecraft:trapped_chest"
+        );
+        assert_eq!(
+            pair_of(&structure, Vec3::new(0, 0, 1)),
+            Some(Vec3::new(1, 0, 1))
+        );
+        assert_eq!(
+            pair_of(&structure, Vec3::new(1, 0, 1)),
+            Some(Vec3::new(0, 0, 1))
+        );
+        let lead = structure
+            .block_entity_at_index(1)
+            .unwrap()
+            .find_tag("pairlead");
+        assert_eq!(lead, Some(&TagData::Byte(1)));
+        assert!(structure.find_half_paired_chests().is_empty());
+
+        assert_eq!(
+            structure.place_double_chest(Vec3::new(1, 0, 0), Cardinal::North, false),
+            Err(Error::OutOfBounds {
+                loc: Vec3::new(2, 0, 0),
+                size: Vec3::new(2, 1, 2),
+            })
+        );
+    }
+
+    #[test]
+    fn halves_must_be_the_same_chest() {
+        let paired = |second: BlockType| {
+            let mut structure = MCStructure::new(Vec3::new(2, 1, 1));
+            structure
+                .place_double_chest(Vec3::new(0, 0, 0), Cardinal::North, false)
+                .unwrap();
+            let index = index_from_vec3(Vec3::new(1, 0, 0), structure.size);
+            let block = structure.palette_add(second).unwrap();
+            structure.blocks[index] = block;
+            structure.find_half_paired_chests()
+        };
+        // another palette entry for the same chest still pairs
+        assert!(paired(chest("north").set_version(1)).is_empty());
+        assert_eq!(paired(chest("south")).len(), 2);
+        let trapped = BlockType::new("minecraft:trapped_chest").set_state(
+            "minecraft:cardinal_direction",
+            &BlockState::String("north".to_string()),
+        );
+        assert_eq!(paired(trapped).len(), 2);
+        assert_eq!(
+            paired(BlockType::new("minecraft:barrel")),
+            vec![Vec3::new(0, 0, 0)]
+        );
+    }
+
+    #[test]
+    fn finds_damaged_pairs() {
+        let mut structure = MCStructure::new(Vec3::new(3, 1, 1));
+        structure
+            .place_double_chest(Vec3::new(0, 0, 0), Cardinal::North, false)
+            .unwrap();
+        set_chest_pair(
+            structure.block_position_data.get_mut(&0).unwrap(),
+            Vec3::new(i32::MIN, 0, 0),
+        );
+        assert_eq!(
+            structure.find_half_paired_chests(),
+            vec![Vec3::new(0, 0, 0), Vec3::new(1, 0, 0)]
+        );
+
+        // only chests are reported
+        structure
+            .setblock(Vec3::new(0, 0, 0), BlockType::new("minecraft:stone"))
+            .unwrap();
+        structure
+            .block_position_data
+            .insert(0, structure.block_position_data[&1].clone());
+        assert_eq!(
+            structure.find_half_paired_chests(),
+            vec![Vec3::new(1, 0, 0)]
+        );
+    }
+}
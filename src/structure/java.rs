@@ -0,0 +1,758 @@
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+};
+
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+
+use crate::{
+    convert::{
+        ConversionReport, bedrock_block_entity_to_java, bedrock_to_java,
+        java_block_entity_to_bedrock, java_to_bedrock,
+    },
+    error::Error,
+    nbt::{NbtTree, TagData, TagKind, TagList},
+    structure::{
+        MCStructure, chest_pair_offset, index_from_vec3, read::read_vec3, read::take_tag,
+        resize::check_size, set_block_entity_position, set_chest_pair, vec3_from_index,
+    },
+    types::{BlockState, BlockType, Cardinal, Vec3, container_block_entity},
+};
+
+/// `DataVersion` written to Java files, 1.21.4. Block entities are written the way that
+/// version saves them, and newer versions upgrade them on load.
+pub(crate) const JAVA_DATA_VERSION: i32 = 4189;
+
+fn invalid(reason: String) -> Error {
+    Error::InvalidStructure(reason)
+}
+
+/// Reads the root compound of a Java NBT file, which is big-endian and usually gzipped
+pub(crate) fn read_java_nbt(bytes: &[u8]) -> Result<TagData, Error> {
+    let bytes = if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut decompressed = vec![];
+        GzDecoder::new(bytes)
+            .read_to_end(&mut decompressed)
+            .map_err(|error| invalid(format!("invalid gzip data: {}", error)))?;
+        decompressed
+    } else {
+        bytes.to_vec()
+    };
+    match NbtTree::from_bytes(false, bytes)?
+        .into_entries()
+        .into_iter()
+        .next()
+    {
+        Some((_, compound @ TagData::Compound(_))) => Ok(compound),
+        _ => Err(invalid("root tag is not a compound".to_string())),
+    }
+}
+
+/// Writes a gzipped, big-endian Java NBT file with `root` as its root compound
+pub(crate) fn write_java_nbt(name: &str, root: TagData) -> Vec<u8> {
+    let bytes = NbtTree::new(vec![(name.to_string(), root)]).as_bytes(false);
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder
+        .write_all(&bytes)
+        .expect("writing to a Vec does not fail");
+    encoder.finish().expect("writing to a Vec does not fail")
+}
+
+pub(crate) fn int_list(values: &[i32]) -> TagData {
+    TagData::List(
+        TagKind::Int,
+        values.len() as u32,
+        values.iter().map(|value| TagData::Int(*value)).collect(),
+    )
+}
+
+pub(crate) fn double_list(values: &[f64]) -> TagData {
+    TagData::List(
+        TagKind::Double,
+        values.len() as u32,
+        values.iter().map(|value| TagData::Double(*value)).collect(),
+    )
+}
+
+/// Java palette entry, `{Name, Properties}`, as a block with string states
+pub(crate) fn read_java_block(data: &TagData) -> Result<BlockType, Error> {
+    let Some(TagData::String(name)) = data.find_tag("Name") else {
+        return Err(invalid("palette entry has no name".to_string()));
+    };
+    let mut block = BlockType::new(name);
+    if let Some(TagData::Compound(properties)) = data.find_tag("Properties") {
+        for (property, value) in properties.iter() {
+            let TagData::String(value) = value else {
+                return Err(invalid(format!(
+                    "property '{}' of '{}' is not a string",
+                    property, name
+                )));
+            };
+            block = block.set_state(property, &BlockState::String(value.clone()));
+        }
+    }
+    Ok(block)
+}
+
+pub(crate) fn java_block_tag(block: &BlockType) -> TagData {
+    let mut tag = TagList::from(vec![(
+        "Name".to_string(),
+        TagData::String(block.type_id.clone()),
+    )]);
+    if !block.states.is_empty() {
+        let properties = block
+            .states
+            .iter()
+            .map(|(name, state)| {
+                let value = match state {
+                    BlockState::String(value) => value.clone(),
+                    BlockState::Int(value) => value.to_string(),
+                    BlockState::Bool(value) => (*value != 0).to_string(),
+                };
+                (name.clone(), TagData::String(value))
+            })
+            .collect();
+        tag.add_tag("Properties", TagData::Compound(TagList::from(properties)));
+    }
+    TagData::Compound(tag)
+}
+
+/// Java block string, e.g. `minecraft:oak_stairs[facing=north,half=top]`
+pub(crate) fn java_block_string(block: &BlockType) -> String {
+    if block.states.is_empty() {
+        return block.type_id.clone();
+    }
+    let states: Vec<String> = block
+        .states
+        .iter()
+        .map(|(name, state)| match state {
+            BlockState::String(value) => format!("{}={}", name, value),
+            BlockState::Int(value) => format!("{}={}", name, value),
+            BlockState::Bool(value) => format!("{}={}", name, *value != 0),
+        })
+        .collect();
+    format!("{}[{}]", block.type_id, states.join(","))
+}
+
+/// Bedrock counterparts of a Java palette, added to a structure's palette as they are used
+pub(crate) struct ImportPalette {
+    java: Vec<BlockType>,
+    bedrock: Vec<Option<BlockType>>,
+    indices: Vec<Option<i32>>,
+}
+
+impl ImportPalette {
+    pub(crate) fn new(java: Vec<BlockType>) -> Self {
+        ImportPalette {
+            bedrock: java.iter().map(java_to_bedrock).collect(),
+            indices: vec![None; java.len()],
+            java,
+        }
+    }
+    pub(crate) fn java(&self, state: usize) -> Option<&BlockType> {
+        self.java.get(state)
+    }
+    /// Index in the palette of `structure` of Java palette entry `state`. Blocks without a
+    /// mapping keep their Java id and are counted in the report each time they are used.
+    pub(crate) fn palette_index(
+        &mut self,
+        state: usize,
+        structure: &mut MCStructure,
+        report: &mut ConversionReport,
+    ) -> Option<i32> {
+        let block = match self.bedrock.get(state)? {
+            Some(bedrock) => bedrock,
+            None => {
+                report.add_block(&self.java[state].type_id);
+                &self.java[state]
+            }
+        };
+        Some(*self.indices[state].get_or_insert_with(|| {
+            structure
+                .palette_find_or_add(block)
+                .expect("imported structures are not strict")
+        }))
+    }
+}
+
+/// Java counterparts of a structure's palette
+pub(crate) struct ExportPalette<'a> {
+    bedrock: &'a [BlockType],
+    java: Vec<Option<BlockType>>,
+}
+
+impl<'a> ExportPalette<'a> {
+    pub(crate) fn new(bedrock: &'a [BlockType]) -> Self {
+        ExportPalette {
+            bedrock,
+            java: bedrock.iter().map(bedrock_to_java).collect(),
+        }
+    }
+    /// Java block of Bedrock palette entry `block`. Blocks without a mapping keep their
+    /// Bedrock id and are counted in the report each time they are used.
+    pub(crate) fn java(&self, block: usize, report: &mut ConversionReport) -> BlockType {
+        match &self.java[block] {
+            Some(java) => java.clone(),
+            None => {
+                report.add_block(&self.bedrock[block].type_id);
+                self.bedrock[block].clone()
+            }
+        }
+    }
+}
+
+/// Bedrock block entity for a Java one, with its position set to `loc`
+pub(crate) fn import_java_block_entity(
+    data: &TagData,
+    loc: Vec3<i32>,
+    report: &mut ConversionReport,
+) -> Option<TagData> {
+    let Some(mut bedrock) = java_block_entity_to_bedrock(data) else {
+        report.add_block_entity(data);
+        return None;
+    };
+    set_block_entity_position(&mut bedrock, loc);
+    Some(bedrock)
+}
+
+/// Java block entity for a Bedrock one, without a position as Java keeps that next to it
+pub(crate) fn export_block_entity(
+    data: &TagData,
+    report: &mut ConversionReport,
+) -> Option<TagData> {
+    let Some(mut java) = bedrock_block_entity_to_java(data) else {
+        report.add_block_entity(data);
+        return None;
+    };
+    for axis in ["x", "y", "z"] {
+        java.remove_tag(axis);
+    }
+    Some(java)
+}
+
+/// Offset to the partner of a double chest half and whether it is the left half
+pub(crate) type ChestPartner = (Vec3<i32>, bool);
+
+/// Offset to the partner of a Java chest that is half of a double chest, and whether it is
+/// the left half, whose partner is clockwise of its facing
+pub(crate) fn java_chest_partner(java: &BlockType) -> Option<ChestPartner> {
+    if !matches!(
+        java.type_id.as_str(),
+        "minecraft:chest" | "minecraft:trapped_chest"
+    ) {
+        return None;
+    }
+    let state = |name: &str| {
+        java.states.iter().find_map(|(state, value)| match value {
+            BlockState::String(value) if state == name => Some(value.as_str()),
+            _ => None,
+        })
+    };
+    let facing = Cardinal::from_name(state("facing")?)?;
+    match state("type")? {
+        "left" => Some((facing.clockwise().offset(), true)),
+        "right" => Some((facing.clockwise().opposite().offset(), false)),
+        _ => None,
+    }
+}
+
+/// Pairs the Bedrock block entities of Java double chests, see `java_chest_partner`
+pub(crate) fn pair_java_chests(
+    structure: &mut MCStructure,
+    chests: Vec<(Vec3<i32>, ChestPartner)>,
+) {
+    for (loc, (offset, left)) in chests {
+        let index = index_from_vec3(loc, structure.size) as u32;
+        let block = structure.palette[structure.blocks[index as usize] as usize].clone();
+        if let Ok(data) = container_block_entity(structure, &block, index) {
+            data.set_tag("pairlead", TagData::Byte(left as i8));
+            set_chest_pair(data, loc + offset);
+        }
+    }
+}
+
+/// Java `type` state of a Bedrock chest, from the partner its block entity is paired with
+pub(crate) fn java_chest_type(java: &BlockType, data: Option<&TagData>) -> BlockType {
+    let Some(offset) = data.and_then(chest_pair_offset) else {
+        return java.clone();
+    };
+    let Some(facing) = java.states.iter().find_map(|(state, value)| match value {
+        BlockState::String(value) if state == "facing" => Cardinal::from_name(value),
+        _ => None,
+    }) else {
+        return java.clone();
+    };
+    let chest_type = if offset == facing.clockwise().offset() {
+        "left"
+    } else if offset == facing.clockwise().opposite().offset() {
+        "right"
+    } else {
+        return java.clone();
+    };
+    java.clone()
+        .set_state("type", &BlockState::String(chest_type.to_string()))
+}
+
+fn read_pos(data: &TagData) -> Option<Vec3<i32>> {
+    match data.find_tag("pos")? {
+        TagData::List(_, _, pos) => match pos[..] {
+            [TagData::Int(x), TagData::Int(y), TagData::Int(z)] => Some(Vec3::new(x, y, z)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn read_doubles(data: &TagData) -> Option<[f64; 3]> {
+    match data {
+        TagData::List(_, _, pos) => match pos[..] {
+            [TagData::Double(x), TagData::Double(y), TagData::Double(z)] => Some([x, y, z]),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Bedrock entity of type `id` at `pos`, keeping the Java `Rotation` if it has one
+pub(crate) fn bedrock_entity(id: &str, pos: [f64; 3], rotation: Option<&TagData>) -> TagData {
+    let mut bedrock = TagList::from(vec![
+        ("identifier".to_string(), TagData::String(id.to_string())),
+        (
+            "Pos".to_string(),
+            TagData::List(
+                TagKind::Float,
+                3,
+                pos.iter()
+                    .map(|value| TagData::Float(*value as f32))
+                    .collect(),
+            ),
+        ),
+    ]);
+    if let Some(rotation @ TagData::List(TagKind::Float, 2, _)) = rotation {
+        bedrock.add_tag("Rotation", rotation.clone());
+    }
+    TagData::Compound(bedrock)
+}
+
+/// Type and position of a Bedrock entity, with the Java `nbt` that keeps them
+pub(crate) fn java_entity_nbt(entity: &TagData) -> Option<([f64; 3], TagList)> {
+    let Some(TagData::String(id)) = entity.find_tag("identifier") else {
+        return None;
+    };
+    let pos = match entity.find_tag("Pos")? {
+        TagData::List(_, _, pos) => match pos[..] {
+            [TagData::Float(x), TagData::Float(y), TagData::Float(z)] => {
+                [x as f64, y as f64, z as f64]
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let mut nbt = TagList::from(vec![
+        ("id".to_string(), TagData::String(id.clone())),
+        ("Pos".to_string(), double_list(&pos)),
+    ]);
+    if let Some(rotation @ TagData::List(TagKind::Float, 2, _)) = entity.find_tag("Rotation") {
+        nbt.add_tag("Rotation", rotation.clone());
+    }
+    Some((pos, nbt))
+}
+
+/// Bedrock entity with the type, position and rotation of a Java one
+fn import_java_entity(entity: &TagData) -> Option<TagData> {
+    let nbt = entity.find_tag("nbt")?;
+    let Some(TagData::String(id)) = nbt.find_tag("id") else {
+        return None;
+    };
+    let pos = read_doubles(entity.find_tag("pos").or(nbt.find_tag("Pos"))?)?;
+    Some(bedrock_entity(id, pos, nbt.find_tag("Rotation")))
+}
+
+/// Java entity, `{pos, blockPos, nbt}`, with the type, position and rotation of a Bedrock one
+fn export_entity(entity: &TagData) -> Option<TagData> {
+    let (pos, nbt) = java_entity_nbt(entity)?;
+    Some(TagData::Compound(TagList::from(vec![
+        (
+            "blockPos".to_string(),
+            int_list(&pos.map(|value| value.floor() as i32)),
+        ),
+        ("nbt".to_string(), TagData::Compound(nbt)),
+        ("pos".to_string(), double_list(&pos)),
+    ])))
+}
+
+impl MCStructure {
+    /// Reads a Java Edition structure block file (`.nbt`). Blocks and block entities are
+    /// converted with the `convert` module; blocks without a Bedrock counterpart keep their
+    /// Java id, block entities without one are left out, and both are listed in the report.
+    /// Entities keep their type, position and rotation.
+    pub fn from_java_structure(bytes: &[u8]) -> Result<(MCStructure, ConversionReport), Error> {
+        let mut root = read_java_nbt(bytes)?;
+        let size = read_vec3(take_tag(&mut root, "size")?, "size")?;
+        check_size(size)?;
+        let mut structure = MCStructure::new(size);
+        let mut report = ConversionReport::default();
+
+        // structures with random variants, like shipwrecks, have several palettes
+        let palette = match (root.remove_tag("palette"), root.remove_tag("palettes")) {
+            (Some(TagData::List(_, _, palette)), _) => palette,
+            (_, Some(TagData::List(_, _, palettes))) => match palettes.into_iter().next() {
+                Some(TagData::List(_, _, palette)) => palette,
+                _ => vec![],
+            },
+            _ => return Err(invalid("missing 'palette'".to_string())),
+        };
+        let mut palette = ImportPalette::new(
+            palette
+                .iter()
+                .map(read_java_block)
+                .collect::<Result<Vec<BlockType>, Error>>()?,
+        );
+
+        let TagData::List(_, _, blocks) = take_tag(&mut root, "blocks")? else {
+            return Err(invalid("'blocks' is not a list".to_string()));
+        };
+        let mut chests = vec![];
+        for block in &blocks {
+            let (Some(loc), Some(TagData::Int(state))) = (read_pos(block), block.find_tag("state"))
+            else {
+                return Err(invalid("block without 'pos' or 'state'".to_string()));
+            };
+            if !structure.contains(loc) {
+                return Err(invalid(format!(
+                    "block position ({}, {}, {}) is out of bounds",
+                    loc.x(),
+                    loc.y(),
+                    loc.z()
+                )));
+            }
+            let state = *state as usize;
+            let Some(palette_block) = palette.palette_index(state, &mut structure, &mut report)
+            else {
+                return Err(invalid(format!(
+                    "block state {} is not in the palette",
+                    state
+                )));
+            };
+            let index = index_from_vec3(loc, size);
+            structure.blocks[index] = palette_block;
+            if let Some(nbt) = block.find_tag("nbt")
+                && let Some(data) = import_java_block_entity(nbt, loc, &mut report)
+            {
+                structure.block_position_data.insert(index as u32, data);
+            }
+            if let Some(partner) = palette.java(state).and_then(java_chest_partner) {
+                chests.push((loc, partner));
+            }
+        }
+        pair_java_chests(&mut structure, chests);
+
+        if let Some(TagData::List(_, _, entities)) = root.remove_tag("entities") {
+            structure.entities = entities.iter().filter_map(import_java_entity).collect();
+        }
+        Ok((structure, report))
+    }
+
+    /// Writes the structure as a Java Edition structure block file (`.nbt`), the reverse of
+    /// `from_java_structure`. Structure void is left out, as Java does.
+    pub fn to_java_structure(&self) -> (Vec<u8>, ConversionReport) {
+        let mut report = ConversionReport::default();
+        let export_palette = ExportPalette::new(&self.palette);
+        let mut palette: Vec<BlockType> = vec![];
+        let mut states: HashMap<String, usize> = HashMap::new();
+        let mut blocks = vec![];
+        for (index, block) in self.blocks.iter().enumerate() {
+            if *block < 0 {
+                continue;
+            }
+            let data = self.block_position_data.get(&(index as u32));
+            let java = java_chest_type(&export_palette.java(*block as usize, &mut report), data);
+            let state = *states.entry(java.to_string()).or_insert_with(|| {
+                palette.push(java);
+                palette.len() - 1
+            });
+            let loc = vec3_from_index(index, self.size);
+            let mut tag = TagList::from(vec![
+                ("pos".to_string(), int_list(&[*loc.x(), *loc.y(), *loc.z()])),
+                ("state".to_string(), TagData::Int(state as i32)),
+            ]);
+            if let Some(nbt) = data.and_then(|data| export_block_entity(data, &mut report)) {
+                tag.add_tag("nbt", nbt);
+            }
+            blocks.push(TagData::Compound(tag));
+        }
+
+        let entities: Vec<TagData> = self.entities.iter().filter_map(export_entity).collect();
+        let root = TagData::Compound(TagList::from(vec![
+            ("DataVersion".to_string(), TagData::Int(JAVA_DATA_VERSION)),
+            (
+                "size".to_string(),
+                int_list(&[*self.size.x(), *self.size.y(), *self.size.z()]),
+            ),
+            (
+                "palette".to_string(),
+                TagData::List(
+                    TagKind::Compound,
+                    palette.len() as u32,
+                    palette.iter().map(java_block_tag).collect(),
+                ),
+            ),
+            (
+                "blocks".to_string(),
+                TagData::List(TagKind::Compound, blocks.len() as u32, blocks),
+            ),
+            (
+                "entities".to_string(),
+                TagData::List(TagKind::Compound, entities.len() as u32, entities),
+            ),
+        ]));
+        (write_java_nbt("", root), report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(input: &str) -> BlockType {
+        input.parse().unwrap()
+    }
+
+    fn java_block(x: i32, state: i32, nbt: Option<TagData>) -> TagData {
+        let mut tag = TagList::from(vec![
+            ("pos".to_string(), int_list(&[x, 0, 0])),
+            ("state".to_string(), TagData::Int(state)),
+        ]);
+        if let Some(nbt) = nbt {
+            tag.add_tag("nbt", nbt);
+        }
+        TagData::Compound(tag)
+    }
+
+    /// A Java structure file one block deep and tall
+    fn java_file(width: i32, palette: &[&str], blocks: Vec<TagData>) -> TagList {
+        TagList::from(vec![
+            ("DataVersion".to_string(), TagData::Int(JAVA_DATA_VERSION)),
+            ("size".to_string(), int_list(&[width, 1, 1])),
+            (
+                "palette".to_string(),
+                TagData::List(
+                    TagKind::Compound,
+                    palette.len() as u32,
+                    palette
+                        .iter()
+                        .map(|java| java_block_tag(&block(java)))
+                        .collect(),
+                ),
+            ),
+            (
+                "blocks".to_string(),
+                TagData::List(TagKind::Compound, blocks.len() as u32, blocks),
+            ),
+        ])
+    }
+
+    fn type_id(structure: &MCStructure, x: i32) -> &str {
+        let block = structure.blocks[index_from_vec3(Vec3::new(x, 0, 0), structure.size)];
+        &structure.palette[block as usize].type_id
+    }
+
+    #[test]
+    fn from_java_structure() {
+        let mut file = java_file(
+            4,
+            &[
+                "minecraft:stone",
+                "minecraft:oak_stairs[facing=north,half=top]",
+                "minecraft:not_a_block",
+            ],
+            vec![
+                java_block(0, 0, None),
+                java_block(1, 1, None),
+                java_block(2, 2, None),
+            ],
+        );
+        let pig = TagList::from(vec![
+            (
+                "id".to_string(),
+                TagData::String("minecraft:pig".to_string()),
+            ),
+            ("Pos".to_string(), double_list(&[10.5, 64.0, 10.5])),
+        ]);
+        file.add_tag(
+            "entities",
+            TagData::List(
+                TagKind::Compound,
+                1,
+                vec![TagData::Compound(TagList::from(vec![
+                    ("pos".to_string(), double_list(&[1.5, 0.0, 0.5])),
+                    ("nbt".to_string(), TagData::Compound(pig)),
+                ]))],
+            ),
+        );
+        let bytes = write_java_nbt("", TagData::Compound(file));
+        let (structure, report) = MCStructure::from_java_structure(&bytes).unwrap();
+
+        assert_eq!(structure.size, Vec3::new(4, 1, 1));
+        assert_eq!(type_id(&structure, 0), "minecraft:stone");
+        assert_eq!(
+            structure.palette[structure.blocks[1] as usize],
+            block(r#"oak_stairs["weirdo_direction"=3,"upside_down_bit"=true]"#)
+        );
+        assert_eq!(type_id(&structure, 2), "minecraft:not_a_block");
+        assert_eq!(structure.blocks[3], -1);
+        assert_eq!(
+            report.unmapped_blocks,
+            [("minecraft:not_a_block".to_string(), 1)].into()
+        );
+        assert_eq!(
+            structure.entities,
+            vec![bedrock_entity("minecraft:pig", [1.5, 0.0, 0.5], None)]
+        );
+    }
+
+    #[test]
+    fn uncompressed_files_and_palettes() {
+        let mut file = java_file(1, &[], vec![java_block(0, 0, None)]);
+        file.remove_tag("palette");
+        file.add_tag(
+            "palettes",
+            TagData::List(
+                TagKind::List,
+                2,
+                ["minecraft:oak_planks", "minecraft:spruce_planks"]
+                    .iter()
+                    .map(|java| {
+                        TagData::List(TagKind::Compound, 1, vec![java_block_tag(&block(java))])
+                    })
+                    .collect(),
+            ),
+        );
+        let bytes = NbtTree::new(vec![(String::new(), TagData::Compound(file))]).as_bytes(false);
+        let (structure, report) = MCStructure::from_java_structure(&bytes).unwrap();
+        assert_eq!(type_id(&structure, 0), "minecraft:oak_planks");
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn double_chests_are_paired() {
+        let chest = TagData::Compound(TagList::from(vec![(
+            "id".to_string(),
+            TagData::String("minecraft:chest".to_string()),
+        )]));
+        let file = java_file(
+            2,
+            &[
+                "minecraft:chest[facing=north,type=left,waterlogged=false]",
+                "minecraft:chest[facing=north,type=right,waterlogged=false]",
+            ],
+            vec![
+                java_block(0, 0, Some(chest.clone())),
+                java_block(1, 1, Some(chest)),
+            ],
+        );
+        let bytes = write_java_nbt("", TagData::Compound(file));
+        let (structure, _) = MCStructure::from_java_structure(&bytes).unwrap();
+        let left = &structure.block_position_data[&0];
+        assert_eq!(chest_pair_offset(left), Some(Vec3::new(1, 0, 0)));
+        assert_eq!(left.find_tag("pairlead"), Some(&TagData::Byte(1)));
+        let right = &structure.block_position_data[&1];
+        assert_eq!(chest_pair_offset(right), Some(Vec3::new(-1, 0, 0)));
+        assert_eq!(right.find_tag("pairlead"), Some(&TagData::Byte(0)));
+
+        let (bytes, report) = structure.to_java_structure();
+        assert!(report.is_empty());
+        let root = read_java_nbt(&bytes).unwrap();
+        let Some(TagData::List(_, _, palette)) = root.find_tag("palette") else {
+            panic!("no palette");
+        };
+        let types: Vec<_> = palette
+            .iter()
+            .map(|entry| {
+                let java = read_java_block(entry).unwrap();
+                java.states
+                    .iter()
+                    .find(|(state, _)| state == "type")
+                    .map(|(_, value)| value.clone())
+            })
+            .collect();
+        assert_eq!(
+            types,
+            [
+                Some(BlockState::String("left".to_string())),
+                Some(BlockState::String("right".to_string()))
+            ]
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut structure = MCStructure::new(Vec3::new(2, 2, 1));
+        let stairs = block(r#"oak_stairs["weirdo_direction"=1,"upside_down_bit"=false]"#);
+        structure
+            .setblock(Vec3::new(0, 0, 0), block(r#"oak_log["pillar_axis"="x"]"#))
+            .unwrap();
+        structure
+            .setblock(Vec3::new(1, 1, 0), stairs.clone())
+            .unwrap();
+        structure
+            .entities
+            .push(bedrock_entity("minecraft:cow", [0.5, 1.0, 0.5], None));
+
+        let (bytes, report) = structure.to_java_structure();
+        assert!(report.is_empty());
+        let (read, report) = MCStructure::from_java_structure(&bytes).unwrap();
+        assert!(report.is_empty());
+        assert_eq!(read.size, structure.size);
+        for (index, block) in structure.blocks.iter().enumerate() {
+            let read_block = read.blocks[index];
+            if *block < 0 {
+                assert_eq!(read_block, -1);
+            } else {
+                assert_eq!(
+                    read.palette[read_block as usize],
+                    structure.palette[*block as usize]
+                );
+            }
+        }
+        assert_eq!(read.entities, structure.entities);
+    }
+
+    #[test]
+    fn invalid_files() {
+        let error = |file: TagList| {
+            MCStructure::from_java_structure(&write_java_nbt("", TagData::Compound(file))).err()
+        };
+        let mut file = java_file(1, &["minecraft:stone"], vec![]);
+        file.remove_tag("palette");
+        assert_eq!(
+            error(file),
+            Some(Error::InvalidStructure("missing 'palette'".to_string()))
+        );
+        assert_eq!(
+            error(java_file(
+                1,
+                &["minecraft:stone"],
+                vec![java_block(1, 0, None)]
+            )),
+            Some(Error::InvalidStructure(
+                "block position (1, 0, 0) is out of bounds".to_string()
+            ))
+        );
+        assert_eq!(
+            error(java_file(
+                1,
+                &["minecraft:stone"],
+                vec![java_block(0, 1, None)]
+            )),
+            Some(Error::InvalidStructure(
+                "block state 1 is not in the palette".to_string()
+            ))
+        );
+        assert_eq!(
+            error(java_file(-1, &[], vec![])),
+            Some(Error::InvalidSize(Vec3::new(-1, 1, 1)))
+        );
+        assert!(MCStructure::from_java_structure(&[0x1f, 0x8b, 0, 1, 2]).is_err());
+    }
+}
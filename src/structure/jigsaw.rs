@@ -0,0 +1,499 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{
+    error::Error,
+    nbt::TagData,
+    structure::{MCStructure, PasteOptions, index_from_vec3, vec3_from_index},
+    types::{BlockState, BlockType, Facing, JigsawBlock, JigsawJoint, Rotation, Vec3},
+};
+
+/// Pool name that never places anything, used by jigsaw blocks that lead nowhere
+const EMPTY_POOL: &str = "minecraft:empty";
+
+/// A named set of pieces for `JigsawAssembler`, each picked with a chance proportional to its
+/// weight
+pub struct StructurePool {
+    name: String,
+    elements: Vec<(MCStructure, u32)>,
+    fallback: Option<String>,
+}
+
+impl StructurePool {
+    pub fn new(name: &str) -> Self {
+        StructurePool {
+            name: name.to_string(),
+            elements: vec![],
+            fallback: None,
+        }
+    }
+    pub fn add_element(mut self, piece: MCStructure, weight: u32) -> Self {
+        self.elements.push((piece, weight));
+        self
+    }
+    /// Pool used when no piece of this pool fits. Pieces from the fallback pool do not grow
+    /// more pieces.
+    pub fn set_fallback(mut self, pool: &str) -> Self {
+        self.fallback = Some(pool.to_string());
+        self
+    }
+}
+
+/// splitmix64, so the same seed gives the same layout everywhere
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// A jigsaw block inside a piece
+#[derive(Clone)]
+struct Connector {
+    loc: Vec3<i32>,
+    facing: Facing,
+    /// `rotation` state, only meaningful for jigsaw blocks facing up or down
+    rotation: i32,
+    jigsaw: JigsawBlock,
+}
+
+/// A piece turned one of four ways, with its jigsaw blocks
+struct Variant {
+    structure: MCStructure,
+    connectors: Vec<Connector>,
+}
+
+impl Variant {
+    fn new(piece: &MCStructure, rotation: Option<Rotation>) -> Variant {
+        let mut structure = piece.clone();
+        if let Some(rotation) = rotation {
+            structure.rotate(rotation);
+        }
+        let mut connectors = vec![];
+        for (index, data) in &structure.block_position_data {
+            let block = structure.blocks[*index as usize];
+            if block < 0 || data.find_tag("id") != Some(&TagData::String("JigsawBlock".to_string()))
+            {
+                continue;
+            }
+            let Some(jigsaw) = JigsawBlock::from_tag(data) else {
+                continue;
+            };
+            let block = &structure.palette[block as usize];
+            let state = |name: &str| {
+                block.states.iter().find_map(|(state, value)| match value {
+                    BlockState::Int(value) if state == name => Some(*value),
+                    _ => None,
+                })
+            };
+            let Some(facing) = state("facing_direction").and_then(Facing::from_facing_direction)
+            else {
+                continue;
+            };
+            connectors.push(Connector {
+                loc: vec3_from_index(*index as usize, structure.size),
+                facing,
+                rotation: state("rotation").unwrap_or(0),
+                jigsaw,
+            });
+        }
+        // jigsaw blocks with a higher selection priority are tried first
+        connectors.sort_by_key(|connector| -connector.jigsaw.selection_priority);
+        Variant {
+            structure,
+            connectors,
+        }
+    }
+}
+
+/// A piece placed in the assembled structure
+struct Placed<'a> {
+    variant: &'a Variant,
+    offset: Vec3<i32>,
+}
+
+impl Placed<'_> {
+    fn intersects(&self, variant: &Variant, offset: Vec3<i32>) -> bool {
+        let overlaps = |min_a: i32, size_a: i32, min_b: i32, size_b: i32| {
+            min_a < min_b + size_b && min_b < min_a + size_a
+        };
+        let (size_a, size_b) = (self.variant.structure.size, variant.structure.size);
+        overlaps(*self.offset.x(), *size_a.x(), *offset.x(), *size_b.x())
+            && overlaps(*self.offset.y(), *size_a.y(), *offset.y(), *size_b.y())
+            && overlaps(*self.offset.z(), *size_a.z(), *offset.z(), *size_b.z())
+    }
+}
+
+/// Builds a structure out of pieces by joining their jigsaw blocks, like the game generates
+/// villages. A jigsaw block is joined to a jigsaw block of a piece from its `pool` whose `name`
+/// matches its `target` and which faces the opposite way. Pieces are turned as needed and
+/// never overlap. Jigsaw blocks turn into their `final_state` in the result.
+pub struct JigsawAssembler {
+    pools: HashMap<String, StructurePool>,
+    seed: u64,
+    max_depth: u32,
+}
+
+impl JigsawAssembler {
+    pub fn new(seed: u64) -> Self {
+        JigsawAssembler {
+            pools: HashMap::new(),
+            seed,
+            max_depth: 7,
+        }
+    }
+    pub fn add_pool(mut self, pool: StructurePool) -> Self {
+        self.pools.insert(pool.name.clone(), pool);
+        self
+    }
+    /// How many pieces away from the start piece the structure may grow. Pieces at this depth
+    /// do not grow more pieces.
+    pub fn set_max_depth(mut self, max_depth: u32) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Assembles a structure starting with a piece from `start_pool`. Fails with
+    /// `Error::UnknownPool` when a pool a jigsaw block leads to is not registered.
+    pub fn assemble(&self, start_pool: &str) -> Result<MCStructure, Error> {
+        let variants: HashMap<&str, Vec<[Variant; 4]>> = self
+            .pools
+            .iter()
+            .map(|(name, pool)| {
+                let variants = pool
+                    .elements
+                    .iter()
+                    .map(|(piece, _)| {
+                        [
+                            None,
+                            Some(Rotation::Cw90),
+                            Some(Rotation::Cw180),
+                            Some(Rotation::Cw270),
+                        ]
+                        .map(|rotation| Variant::new(piece, rotation))
+                    })
+                    .collect();
+                (name.as_str(), variants)
+            })
+            .collect();
+        let mut random = Random(self.seed);
+
+        let start = self
+            .pick_elements(start_pool, &mut random)
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::UnknownPool(start_pool.to_string()))?;
+        let start = &variants[start_pool][start][random.below(4)];
+        let mut placed = vec![Placed {
+            variant: start,
+            offset: Vec3::_000,
+        }];
+        let mut open: VecDeque<(Vec3<i32>, Connector, u32)> = start
+            .connectors
+            .iter()
+            .map(|connector| (Vec3::_000, connector.clone(), 0))
+            .collect();
+
+        while let Some((offset, source, depth)) = open.pop_front() {
+            if depth >= self.max_depth {
+                continue;
+            }
+            // pieces from the fallback pool are terminal
+            let mut pools = vec![(source.jigsaw.pool.as_str(), true)];
+            if let Some(fallback) = self
+                .pools
+                .get(&source.jigsaw.pool)
+                .and_then(|pool| pool.fallback.as_deref())
+            {
+                pools.push((fallback, false));
+            }
+            if let Some((pool, _)) = pools
+                .iter()
+                .find(|(pool, _)| *pool != EMPTY_POOL && !self.pools.contains_key(*pool))
+            {
+                return Err(Error::UnknownPool(pool.to_string()));
+            }
+            let target_loc = offset + source.loc + source.facing.offset();
+
+            'pools: for (pool, grows) in pools {
+                for element in self.pick_elements(pool, &mut random) {
+                    let mut rotations = [0, 1, 2, 3];
+                    random.shuffle(&mut rotations);
+                    for rotation in rotations {
+                        let variant = &variants[pool][element][rotation];
+                        let mut connectors: Vec<&Connector> = variant
+                            .connectors
+                            .iter()
+                            .filter(|connector| fits(&source, connector))
+                            .collect();
+                        random.shuffle(&mut connectors);
+                        for connector in connectors {
+                            let piece_offset = target_loc - connector.loc;
+                            if placed
+                                .iter()
+                                .any(|piece| piece.intersects(variant, piece_offset))
+                            {
+                                continue;
+                            }
+                            placed.push(Placed {
+                                variant,
+                                offset: piece_offset,
+                            });
+                            if grows && depth + 1 < self.max_depth {
+                                for other in &variant.connectors {
+                                    if other.loc != connector.loc {
+                                        open.push_back((piece_offset, other.clone(), depth + 1));
+                                    }
+                                }
+                            }
+                            break 'pools;
+                        }
+                    }
+                }
+            }
+        }
+
+        combine(&placed)
+    }
+
+    /// Indices of the pool's elements in the order they should be tried, favouring heavier
+    /// elements
+    fn pick_elements(&self, pool: &str, random: &mut Random) -> Vec<usize> {
+        let Some(pool) = self.pools.get(pool).filter(|_| pool != EMPTY_POOL) else {
+            return vec![];
+        };
+        let mut weighted: Vec<usize> = pool
+            .elements
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (_, weight))| std::iter::repeat_n(i, *weight as usize))
+            .collect();
+        random.shuffle(&mut weighted);
+        let mut order = vec![];
+        for i in weighted {
+            if !order.contains(&i) {
+                order.push(i);
+            }
+        }
+        order
+    }
+}
+
+/// Whether `connector` can be joined to `source`
+fn fits(source: &Connector, connector: &Connector) -> bool {
+    connector.jigsaw.name == source.jigsaw.target
+        && connector.facing == source.facing.opposite()
+        && (matches!(
+            source.facing,
+            Facing::North | Facing::South | Facing::West | Facing::East
+        ) || source.jigsaw.joint == JigsawJoint::Rollable
+            || connector.rotation == source.rotation)
+}
+
+/// Pastes the placed pieces into one structure, turning jigsaw blocks into their final state
+fn combine(placed: &[Placed]) -> Result<MCStructure, Error> {
+    let min = |a: i32, b: i32| a.min(b);
+    let max = |a: i32, b: i32| a.max(b);
+    let (mut low, mut high) = (placed[0].offset, placed[0].offset);
+    for piece in placed {
+        let end = piece.offset + piece.variant.structure.size;
+        low = Vec3::new(
+            min(*low.x(), *piece.offset.x()),
+            min(*low.y(), *piece.offset.y()),
+            min(*low.z(), *piece.offset.z()),
+        );
+        high = Vec3::new(
+            max(*high.x(), *end.x()),
+            max(*high.y(), *end.y()),
+            max(*high.z(), *end.z()),
+        );
+    }
+
+    let mut structure = MCStructure::new(high - low);
+    for piece in placed {
+        structure.paste(
+            &piece.variant.structure,
+            piece.offset - low,
+            PasteOptions::default(),
+        )?;
+    }
+    for piece in placed {
+        for connector in &piece.variant.connectors {
+            let loc = piece.offset - low + connector.loc;
+            let index = index_from_vec3(loc, structure.size);
+            structure.block_position_data.remove(&(index as u32));
+            structure.extra_position_data.remove(&(index as u32));
+            let final_state = &connector.jigsaw.final_state;
+            if final_state == "minecraft:structure_void" {
+                structure.blocks[index] = -1;
+            } else {
+                let block = final_state
+                    .parse::<BlockType>()
+                    .unwrap_or_else(|_| BlockType::new(final_state));
+                let block = structure.palette_find_or_add(&block)?;
+                structure.setblock_palette(loc, block)?;
+            }
+        }
+    }
+    Ok(structure)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn piece(size: Vec3<i32>, jigsaws: &[(Vec3<i32>, Facing, JigsawBlock)]) -> MCStructure {
+        let mut structure = MCStructure::new(size);
+        for (loc, facing, jigsaw) in jigsaws {
+            structure.place_jigsaw(*loc, *facing, 0, jigsaw).unwrap();
+        }
+        structure
+    }
+
+    fn start(pool: &str) -> StructurePool {
+        StructurePool::new("test:start").add_element(
+            piece(
+                Vec3::new(1, 1, 1),
+                &[(
+                    Vec3::new(0, 0, 0),
+                    Facing::East,
+                    JigsawBlock::new("test:out", "test:in", pool, "minecraft:stone"),
+                )],
+            ),
+            1,
+        )
+    }
+
+    /// Two blocks long, joined on one end and growing on the other
+    fn corridor() -> StructurePool {
+        StructurePool::new("test:corridor").add_element(
+            piece(
+                Vec3::new(2, 1, 1),
+                &[
+                    (
+                        Vec3::new(0, 0, 0),
+                        Facing::West,
+                        JigsawBlock::new("test:in", "", EMPTY_POOL, "minecraft:planks"),
+                    ),
+                    (
+                        Vec3::new(1, 0, 0),
+                        Facing::East,
+                        JigsawBlock::new(
+                            "test:out",
+                            "test:in",
+                            "test:corridor",
+                            "minecraft:planks",
+                        ),
+                    ),
+                ],
+            ),
+            1,
+        )
+    }
+
+    fn cap() -> StructurePool {
+        StructurePool::new("test:cap").add_element(
+            piece(
+                Vec3::new(1, 1, 1),
+                &[(
+                    Vec3::new(0, 0, 0),
+                    Facing::West,
+                    JigsawBlock::new("test:in", "", EMPTY_POOL, "minecraft:glass"),
+                )],
+            ),
+            1,
+        )
+    }
+
+    fn volume(structure: &MCStructure) -> i32 {
+        structure.size.x() * structure.size.y() * structure.size.z()
+    }
+
+    #[test]
+    fn grows_up_to_max_depth() {
+        for max_depth in 0..4 {
+            let structure = JigsawAssembler::new(7)
+                .add_pool(start("test:corridor"))
+                .add_pool(corridor())
+                .set_max_depth(max_depth)
+                .assemble("test:start")
+                .unwrap();
+            assert_eq!(volume(&structure), 1 + 2 * max_depth as i32);
+        }
+    }
+
+    #[test]
+    fn jigsaw_blocks_turn_into_their_final_state() {
+        let structure = JigsawAssembler::new(3)
+            .add_pool(start("test:corridor"))
+            .add_pool(corridor())
+            .set_max_depth(1)
+            .assemble("test:start")
+            .unwrap();
+        let mut type_ids: Vec<_> = structure
+            .blocks
+            .iter()
+            .map(|block| structure.palette[*block as usize].type_id.as_str())
+            .collect();
+        type_ids.sort();
+        assert_eq!(
+            type_ids,
+            ["minecraft:planks", "minecraft:planks", "minecraft:stone"]
+        );
+        assert!(structure.block_position_data.is_empty());
+    }
+
+    #[test]
+    fn fallback_stops_at_max_depth() {
+        let assembler = JigsawAssembler::new(1)
+            .add_pool(start("test:dead_end"))
+            .add_pool(StructurePool::new("test:dead_end").set_fallback("test:cap"))
+            .add_pool(cap());
+        let structure = assembler.assemble("test:start").unwrap();
+        assert_eq!(volume(&structure), 2);
+        let assembler = assembler.set_max_depth(0);
+        let structure = assembler.assemble("test:start").unwrap();
+        assert_eq!(volume(&structure), 1);
+    }
+
+    #[test]
+    fn unknown_pools_are_errors() {
+        let assembler = JigsawAssembler::new(1).add_pool(start("test:missing"));
+        assert_eq!(
+            assembler.assemble("test:start").err(),
+            Some(Error::UnknownPool("test:missing".to_string()))
+        );
+        assert_eq!(
+            assembler.assemble("test:nowhere").err(),
+            Some(Error::UnknownPool("test:nowhere".to_string()))
+        );
+        let assembler = JigsawAssembler::new(1)
+            .add_pool(start("test:dead_end"))
+            .add_pool(StructurePool::new("test:dead_end").set_fallback("test:missing"));
+        assert_eq!(
+            assembler.assemble("test:start").err(),
+            Some(Error::UnknownPool("test:missing".to_string()))
+        );
+    }
+
+    #[test]
+    fn empty_pool_leads_nowhere() {
+        let structure = JigsawAssembler::new(1)
+            .add_pool(start(EMPTY_POOL))
+            .assemble("test:start")
+            .unwrap();
+        assert_eq!(volume(&structure), 1);
+    }
+}
@@ -0,0 +1,515 @@
+use crate::{
+    convert::ConversionReport,
+    error::Error,
+    nbt::TagData,
+    structure::{
+        MCStructure, PasteOptions, index_from_vec3,
+        java::{
+            ImportPalette, bedrock_entity, import_java_block_entity, java_chest_partner,
+            pair_java_chests, read_doubles, read_java_block, read_java_nbt,
+        },
+        resize::check_size,
+    },
+    types::{BlockType, Vec3},
+};
+
+fn invalid(reason: String) -> Error {
+    Error::InvalidStructure(reason)
+}
+
+/// `{x, y, z}` compound, as Litematica saves positions and sizes
+fn read_xyz(data: &TagData, id: &str) -> Result<Vec3<i32>, Error> {
+    let xyz = data.find_tag(id);
+    match ["x", "y", "z"].map(|axis| xyz.and_then(|xyz| xyz.find_tag(axis))) {
+        [
+            Some(TagData::Int(x)),
+            Some(TagData::Int(y)),
+            Some(TagData::Int(z)),
+        ] => Ok(Vec3::new(*x, *y, *z)),
+        _ => Err(invalid(format!("'{}' is not a compound of 3 ints", id))),
+    }
+}
+
+/// Reads one region, with its origin set to its lowest corner. Sizes may be negative, in which
+/// case the region reaches from its `Position` towards lower coordinates.
+fn read_region(region: &TagData, report: &mut ConversionReport) -> Result<MCStructure, Error> {
+    let position = read_xyz(region, "Position")?;
+    let signed_size = read_xyz(region, "Size")?;
+    let corner = |position: i32, size: i32| {
+        if size < 0 {
+            position.checked_add(size + 1)
+        } else {
+            Some(position)
+        }
+    };
+    let (Some(x), Some(y), Some(z)) = (
+        corner(*position.x(), *signed_size.x()),
+        corner(*position.y(), *signed_size.y()),
+        corner(*position.z(), *signed_size.z()),
+    ) else {
+        return Err(invalid(format!(
+            "region at ({}, {}, {}) of size ({}, {}, {}) is out of range",
+            position.x(),
+            position.y(),
+            position.z(),
+            signed_size.x(),
+            signed_size.y(),
+            signed_size.z()
+        )));
+    };
+    let min = Vec3::new(x, y, z);
+    let (Some(x), Some(y), Some(z)) = (
+        signed_size.x().checked_abs(),
+        signed_size.y().checked_abs(),
+        signed_size.z().checked_abs(),
+    ) else {
+        return Err(Error::InvalidSize(signed_size));
+    };
+    let size = Vec3::new(x, y, z);
+    check_size(size)?;
+
+    let Some(TagData::List(_, _, palette)) = region.find_tag("BlockStatePalette") else {
+        return Err(invalid("missing 'BlockStatePalette'".to_string()));
+    };
+    let palette = palette
+        .iter()
+        .map(read_java_block)
+        .collect::<Result<Vec<BlockType>, Error>>()?;
+    // every value takes as many bits as the largest palette index needs, at least 2
+    let bits = (usize::BITS - palette.len().saturating_sub(1).leading_zeros()).max(2);
+    let mut palette = ImportPalette::new(palette);
+    // the block states are checked against the size before it is allocated
+    let count = (size.x() * size.y() * size.z()) as usize;
+    let Some(states) = region
+        .find_tag("BlockStates")
+        .and_then(|states| states.unpack_long_array(bits, count))
+    else {
+        return Err(invalid(format!(
+            "'BlockStates' does not hold {} blocks of {} bits",
+            count, bits
+        )));
+    };
+
+    let mut structure = MCStructure::new(size);
+    structure.origin = min;
+
+    let (width, length) = (*size.x() as usize, *size.z() as usize);
+    let mut chests = vec![];
+    for (i, state) in states.into_iter().enumerate() {
+        let state = state as usize;
+        // regions are ordered by y, then z, then x
+        let loc = Vec3::new(
+            (i % width) as i32,
+            (i / (width * length)) as i32,
+            (i / width % length) as i32,
+        );
+        let Some(palette_block) = palette.palette_index(state, &mut structure, report) else {
+            return Err(invalid(format!(
+                "block state {} is not in the palette",
+                state
+            )));
+        };
+        structure.blocks[index_from_vec3(loc, size)] = palette_block;
+        if let Some(partner) = palette.java(state).and_then(java_chest_partner) {
+            chests.push((loc, partner));
+        }
+    }
+
+    // block entities are relative to the lowest corner
+    if let Some(TagData::List(_, _, tile_entities)) = region.find_tag("TileEntities") {
+        for tile_entity in tile_entities {
+            let (Some(TagData::Int(x)), Some(TagData::Int(y)), Some(TagData::Int(z))) = (
+                tile_entity.find_tag("x"),
+                tile_entity.find_tag("y"),
+                tile_entity.find_tag("z"),
+            ) else {
+                return Err(invalid("tile entity without a position".to_string()));
+            };
+            let loc = Vec3::new(*x, *y, *z);
+            if !structure.contains(loc) {
+                return Err(invalid(format!(
+                    "tile entity position ({}, {}, {}) is out of bounds",
+                    loc.x(),
+                    loc.y(),
+                    loc.z()
+                )));
+            }
+            if let Some(data) = import_java_block_entity(tile_entity, loc, report) {
+                let index = index_from_vec3(loc, size) as u32;
+                structure.block_position_data.insert(index, data);
+            }
+        }
+    }
+    pair_java_chests(&mut structure, chests);
+
+    // entities are relative to `Position`
+    if let Some(TagData::List(_, _, entities)) = region.find_tag("Entities") {
+        let offset = position - min;
+        for entity in entities {
+            let (Some(TagData::String(id)), Some(pos)) = (
+                entity.find_tag("id"),
+                entity.find_tag("Pos").and_then(read_doubles),
+            ) else {
+                continue;
+            };
+            let pos = [
+                pos[0] + *offset.x() as f64,
+                pos[1] + *offset.y() as f64,
+                pos[2] + *offset.z() as f64,
+            ];
+            structure
+                .entities
+                .push(bedrock_entity(id, pos, entity.find_tag("Rotation")));
+        }
+    }
+    Ok(structure)
+}
+
+impl MCStructure {
+    /// Reads a Litematica schematic (`.litematic`) as one structure per region, in the order
+    /// they are saved, with each region's origin set to its lowest corner relative to the
+    /// schematic. Blocks, block entities and entities are converted like in
+    /// `from_java_structure`, with one report for all regions.
+    pub fn from_litematic(
+        bytes: &[u8],
+    ) -> Result<(Vec<(String, MCStructure)>, ConversionReport), Error> {
+        let root = read_java_nbt(bytes)?;
+        let Some(TagData::Compound(regions)) = root.find_tag("Regions") else {
+            return Err(invalid("missing 'Regions'".to_string()));
+        };
+        let mut report = ConversionReport::default();
+        let regions = regions
+            .iter()
+            .map(|(name, region)| Ok((name.clone(), read_region(region, &mut report)?)))
+            .collect::<Result<Vec<(String, MCStructure)>, Error>>()?;
+        Ok((regions, report))
+    }
+
+    /// Reads a Litematica schematic like `from_litematic`, pasting all regions into one
+    /// structure that encloses them. Where regions overlap, later ones win.
+    pub fn from_litematic_merged(bytes: &[u8]) -> Result<(MCStructure, ConversionReport), Error> {
+        let (regions, report) = MCStructure::from_litematic(bytes)?;
+        let Some(min) = regions
+            .iter()
+            .map(|(_, region)| region.origin)
+            .reduce(|a, b| Vec3::new(*a.x().min(b.x()), *a.y().min(b.y()), *a.z().min(b.z())))
+        else {
+            return Ok((MCStructure::new(Vec3::_000), report));
+        };
+        // regions far apart may not fit in one structure
+        let too_far = || invalid("regions are too far apart to merge".to_string());
+        let mut size = Vec3::_000;
+        for (_, region) in &regions {
+            let end = |origin: i32, size: i32, min: i32| origin.checked_add(size)?.checked_sub(min);
+            let (Some(x), Some(y), Some(z)) = (
+                end(*region.origin.x(), *region.size.x(), *min.x()),
+                end(*region.origin.y(), *region.size.y(), *min.y()),
+                end(*region.origin.z(), *region.size.z(), *min.z()),
+            ) else {
+                return Err(too_far());
+            };
+            size = Vec3::new(*size.x().max(&x), *size.y().max(&y), *size.z().max(&z));
+        }
+        check_size(size).map_err(|_| too_far())?;
+        let mut structure = MCStructure::new(size);
+        structure.origin = min;
+        for (_, region) in &regions {
+            structure.paste(region, region.origin - min, PasteOptions::default())?;
+        }
+        Ok((structure, report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nbt::{TagKind, TagList},
+        structure::java::{double_list, java_block_tag, write_java_nbt},
+    };
+
+    fn block(input: &str) -> BlockType {
+        input.parse().unwrap()
+    }
+
+    fn xyz(loc: Vec3<i32>) -> TagData {
+        TagData::Compound(TagList::from(vec![
+            ("x".to_string(), TagData::Int(*loc.x())),
+            ("y".to_string(), TagData::Int(*loc.y())),
+            ("z".to_string(), TagData::Int(*loc.z())),
+        ]))
+    }
+
+    /// Packs values the way Litematica does, spanning longs where needed
+    fn pack(values: &[u64], bits: usize) -> TagData {
+        let mut longs = vec![0u64; (values.len() * bits).div_ceil(64)];
+        for (i, value) in values.iter().enumerate() {
+            let (long, offset) = (i * bits / 64, i * bits % 64);
+            longs[long] |= value << offset;
+            if offset + bits > 64 {
+                longs[long + 1] |= value >> (64 - offset);
+            }
+        }
+        TagData::LongArray(
+            longs.len() as i32,
+            longs.into_iter().map(|long| long as i64).collect(),
+        )
+    }
+
+    fn region(
+        position: Vec3<i32>,
+        size: Vec3<i32>,
+        palette: &[&str],
+        states: &[u64],
+        entities: Vec<TagData>,
+    ) -> TagData {
+        let bits = (usize::BITS - (palette.len() - 1).leading_zeros()).max(2) as usize;
+        TagData::Compound(TagList::from(vec![
+            ("Position".to_string(), xyz(position)),
+            ("Size".to_string(), xyz(size)),
+            (
+                "BlockStatePalette".to_string(),
+                TagData::List(
+                    TagKind::Compound,
+                    palette.len() as u32,
+                    palette
+                        .iter()
+                        .map(|java| java_block_tag(&block(java)))
+                        .collect(),
+                ),
+            ),
+            ("BlockStates".to_string(), pack(states, bits)),
+            (
+                "TileEntities".to_string(),
+                TagData::List(TagKind::Compound, 0, vec![]),
+            ),
+            (
+                "Entities".to_string(),
+                TagData::List(TagKind::Compound, entities.len() as u32, entities),
+            ),
+        ]))
+    }
+
+    fn entity(id: &str, pos: [f64; 3]) -> TagData {
+        TagData::Compound(TagList::from(vec![
+            ("id".to_string(), TagData::String(id.to_string())),
+            ("Pos".to_string(), double_list(&pos)),
+        ]))
+    }
+
+    fn litematic(regions: Vec<(&str, TagData)>) -> Vec<u8> {
+        let regions = regions
+            .into_iter()
+            .map(|(name, region)| (name.to_string(), region))
+            .collect();
+        write_java_nbt(
+            "",
+            TagData::Compound(TagList::from(vec![
+                ("Version".to_string(), TagData::Int(6)),
+                (
+                    "Regions".to_string(),
+                    TagData::Compound(TagList::from(regions)),
+                ),
+            ])),
+        )
+    }
+
+    /// A stone and stairs region, and a glass region with a negative size right of it
+    fn two_regions() -> Vec<u8> {
+        litematic(vec![
+            (
+                "main",
+                region(
+                    Vec3::new(0, 0, 0),
+                    Vec3::new(2, 1, 1),
+                    &[
+                        "minecraft:air",
+                        "minecraft:stone",
+                        "minecraft:oak_stairs[facing=north,half=top]",
+                    ],
+                    &[1, 2],
+                    vec![entity("minecraft:pig", [0.5, 0.0, 0.5])],
+                ),
+            ),
+            (
+                "negative",
+                region(
+                    Vec3::new(3, 0, 0),
+                    Vec3::new(-2, 1, 1),
+                    &["minecraft:air", "minecraft:glass"],
+                    &[1, 0],
+                    vec![entity("minecraft:cow", [0.5, 0.0, 0.5])],
+                ),
+            ),
+        ])
+    }
+
+    fn type_id(structure: &MCStructure, x: i32) -> &str {
+        let block = structure.blocks[index_from_vec3(Vec3::new(x, 0, 0), structure.size)];
+        &structure.palette[block as usize].type_id
+    }
+
+    #[test]
+    fn from_litematic() {
+        let (regions, report) = MCStructure::from_litematic(&two_regions()).unwrap();
+        assert!(report.is_empty());
+        let names: Vec<_> = regions.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["main", "negative"]);
+
+        let main = &regions[0].1;
+        assert_eq!(main.origin, Vec3::new(0, 0, 0));
+        assert_eq!(type_id(main, 0), "minecraft:stone");
+        assert_eq!(
+            main.getblock(Vec3::new(1, 0, 0)),
+            block(r#"oak_stairs["weirdo_direction"=3,"upside_down_bit"=true]"#)
+        );
+        assert_eq!(
+            main.entities,
+            vec![bedrock_entity("minecraft:pig", [0.5, 0.0, 0.5], None)]
+        );
+
+        let negative = &regions[1].1;
+        assert_eq!(negative.origin, Vec3::new(2, 0, 0));
+        assert_eq!(negative.size, Vec3::new(2, 1, 1));
+        assert_eq!(type_id(negative, 0), "minecraft:glass");
+        assert_eq!(type_id(negative, 1), "minecraft:air");
+        // entities are relative to `Position`, the far corner of a negative region
+        assert_eq!(
+            negative.entities,
+            vec![bedrock_entity("minecraft:cow", [1.5, 0.0, 0.5], None)]
+        );
+    }
+
+    #[test]
+    fn from_litematic_merged() {
+        let (structure, _) = MCStructure::from_litematic_merged(&two_regions()).unwrap();
+        assert_eq!(structure.origin, Vec3::new(0, 0, 0));
+        assert_eq!(structure.size, Vec3::new(4, 1, 1));
+        let type_ids: Vec<_> = (0..4).map(|x| type_id(&structure, x)).collect();
+        assert_eq!(
+            type_ids,
+            [
+                "minecraft:stone",
+                "minecraft:oak_stairs",
+                "minecraft:glass",
+                "minecraft:air"
+            ]
+        );
+        assert_eq!(
+            structure.entities,
+            vec![
+                bedrock_entity("minecraft:pig", [0.5, 0.0, 0.5], None),
+                bedrock_entity("minecraft:cow", [3.5, 0.0, 0.5], None),
+            ]
+        );
+
+        let (empty, _) = MCStructure::from_litematic_merged(&litematic(vec![])).unwrap();
+        assert_eq!(empty.size, Vec3::_000);
+    }
+
+    #[test]
+    fn values_spanning_two_longs() {
+        let palette = [
+            "minecraft:air",
+            "minecraft:stone",
+            "minecraft:dirt",
+            "minecraft:glass",
+            "minecraft:cobblestone",
+        ];
+        let states: Vec<u64> = (0..22).map(|i| i % 5).collect();
+        let file = litematic(vec![(
+            "wide",
+            region(
+                Vec3::new(0, 0, 0),
+                Vec3::new(22, 1, 1),
+                &palette,
+                &states,
+                vec![],
+            ),
+        )]);
+        let (regions, _) = MCStructure::from_litematic(&file).unwrap();
+        for (x, state) in states.iter().enumerate() {
+            assert_eq!(type_id(&regions[0].1, x as i32), palette[*state as usize]);
+        }
+    }
+
+    #[test]
+    fn invalid_files() {
+        let error = |regions| MCStructure::from_litematic(&litematic(regions)).err();
+        assert_eq!(
+            MCStructure::from_litematic(&write_java_nbt("", TagData::Compound(TagList::new())))
+                .err(),
+            Some(invalid("missing 'Regions'".to_string()))
+        );
+        assert_eq!(
+            error(vec![(
+                "short",
+                region(
+                    Vec3::new(0, 0, 0),
+                    Vec3::new(40, 1, 1),
+                    &["minecraft:air", "minecraft:stone"],
+                    &[1; 20],
+                    vec![],
+                ),
+            )]),
+            Some(invalid(
+                "'BlockStates' does not hold 40 blocks of 2 bits".to_string()
+            ))
+        );
+        assert_eq!(
+            error(vec![(
+                "state",
+                region(
+                    Vec3::new(0, 0, 0),
+                    Vec3::new(1, 1, 1),
+                    &["minecraft:air", "minecraft:stone"],
+                    &[3],
+                    vec![],
+                ),
+            )]),
+            Some(invalid("block state 3 is not in the palette".to_string()))
+        );
+        assert_eq!(
+            error(vec![(
+                "far",
+                region(
+                    Vec3::new(i32::MIN, 0, 0),
+                    Vec3::new(-2, 1, 1),
+                    &["minecraft:air"],
+                    &[0, 0],
+                    vec![],
+                ),
+            )]),
+            Some(invalid(format!(
+                "region at ({}, 0, 0) of size (-2, 1, 1) is out of range",
+                i32::MIN
+            )))
+        );
+        assert_eq!(
+            MCStructure::from_litematic_merged(&litematic(vec![
+                (
+                    "low",
+                    region(
+                        Vec3::new(i32::MIN, 0, 0),
+                        Vec3::new(1, 1, 1),
+                        &["minecraft:air"],
+                        &[0],
+                        vec![],
+                    ),
+                ),
+                (
+                    "high",
+                    region(
+                        Vec3::new(i32::MAX - 1, 0, 0),
+                        Vec3::new(1, 1, 1),
+                        &["minecraft:air"],
+                        &[0],
+                        vec![],
+                    ),
+                ),
+            ]))
+            .err(),
+            Some(invalid("regions are too far apart to merge".to_string()))
+        );
+    }
+}
@@ -1,8 +1,27 @@
 use core::panic;
+use std::collections::BTreeMap;
+
+mod chest;
+mod java;
+mod jigsaw;
+mod litematic;
+mod paste;
+mod read;
+mod resize;
+mod schem;
+mod schematic;
+mod transform;
+mod vox;
+
+pub use jigsaw::{JigsawAssembler, StructurePool};
+pub use paste::PasteOptions;
+pub use resize::Anchor;
+pub use schem::SchemVersion;
 
 use crate::{
+    error::Error,
     nbt::{NbtTree, TagData, TagKind, TagList},
-    types::{Block, BlockState, BlockType, Vec3},
+    types::{Block, BlockType, DEFAULT_BLOCK_VERSION, Vec3},
 };
 
 pub struct MCStructureIterator<'a> {
@@ -15,13 +34,14 @@ pub struct MCStructureIterator<'a> {
 impl<'a> Iterator for MCStructureIterator<'a> {
     type Item = (Vec3<i32>, BlockType);
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current >= self.blocks.len() {
-            return None;
+        // structure void has no palette entry, so it is skipped
+        while self.blocks.get(self.current)? < &0 {
+            self.current += 1;
         }
         self.current += 1;
         Some((
             vec3_from_index(self.current - 1, self.size),
-            self.palette[self.blocks[self.current] as usize].clone(),
+            self.palette[self.blocks[self.current - 1] as usize].clone(),
         ))
     }
 }
@@ -49,12 +69,97 @@ pub fn index_from_vec3(loc: Vec3<i32>, size: Vec3<i32>) -> usize {
     (size.z() * size.y() * loc.x() + size.z() * loc.y() + loc.z()) as usize
 }
 
-#[derive(Debug)]
+/// Offset from a paired chest to its partner, wrapping for the positions of damaged files
+pub(crate) fn chest_pair_offset(data: &TagData) -> Option<Vec3<i32>> {
+    match (
+        data.find_tag("x"),
+        data.find_tag("z"),
+        data.find_tag("pairx"),
+        data.find_tag("pairz"),
+    ) {
+        (
+            Some(TagData::Int(x)),
+            Some(TagData::Int(z)),
+            Some(TagData::Int(pairx)),
+            Some(TagData::Int(pairz)),
+        ) => Some(Vec3::new(pairx.wrapping_sub(*x), 0, pairz.wrapping_sub(*z))),
+        _ => None,
+    }
+}
+
+pub(crate) fn set_chest_pair(data: &mut TagData, pair: Vec3<i32>) {
+    data.set_tag("pairx", TagData::Int(*pair.x()));
+    data.set_tag("pairz", TagData::Int(*pair.z()));
+}
+
+/// Updates the position stored in a block entity, moving the chest pair along with it
+pub(crate) fn set_block_entity_position(data: &mut TagData, loc: Vec3<i32>) {
+    if let TagData::Compound(_) = data {
+        let pair_offset = chest_pair_offset(data);
+        data.set_tag("x", TagData::Int(*loc.x()));
+        data.set_tag("y", TagData::Int(*loc.y()));
+        data.set_tag("z", TagData::Int(*loc.z()));
+        if let Some(offset) = pair_offset {
+            set_chest_pair(data, loc.wrapping_add(offset));
+        }
+    }
+}
+
+/// Moves the position stored in a block entity by `offset`, along with its chest pair
+pub(crate) fn translate_block_entity(data: &mut TagData, offset: Vec3<i32>) {
+    for (id, offset) in [
+        ("x", offset.x()),
+        ("y", offset.y()),
+        ("z", offset.z()),
+        ("pairx", offset.x()),
+        ("pairz", offset.z()),
+    ] {
+        // positions of damaged files may be anywhere, so they wrap instead of overflowing
+        if let TagData::Compound(_) = data
+            && let Some(TagData::Int(value)) = data.get_tag(id)
+        {
+            *value = value.wrapping_add(*offset);
+        }
+    }
+}
+
+/// Moves an entity's `Pos` by `offset`, returning the new position
+pub(crate) fn translate_entity(entity: &mut TagData, offset: Vec3<i32>) -> Option<Vec3<f32>> {
+    if let TagData::Compound(_) = entity
+        && let Some(TagData::List(TagKind::Float, 3, pos)) = entity.get_tag("Pos")
+        && let [TagData::Float(x), TagData::Float(y), TagData::Float(z)] = &mut pos[..]
+    {
+        *x += *offset.x() as f32;
+        *y += *offset.y() as f32;
+        *z += *offset.z() as f32;
+        return Some(Vec3::new(*x, *y, *z));
+    }
+    None
+}
+
+#[derive(Clone, Debug)]
 pub struct MCStructure {
     pub(crate) size: Vec3<i32>,
-    blocks: Vec<i32>,
+    pub(crate) blocks: Vec<i32>,
+    /// second layer of `block_indices`, mostly water in waterlogged blocks
+    pub(crate) blocks_layer2: Vec<i32>,
     pub(crate) palette: Vec<BlockType>,
-    pub(crate) block_position_data: Vec<(u32, TagData)>,
+    /// block entities keyed by block index. Their positions, like those of entities, are
+    /// relative to the structure; files store them relative to the world, adding `origin`.
+    pub(crate) block_position_data: BTreeMap<u32, TagData>,
+    pub(crate) entities: Vec<TagData>,
+    /// `block_position_data` tags besides `block_entity_data`, e.g. `tick_queue_data`
+    pub(crate) extra_position_data: BTreeMap<u32, TagList>,
+    origin: Vec3<i32>,
+    format_version: i32,
+    default_block_version: i32,
+    /// check blocks against the block registry in `setblock`
+    strict: bool,
+    /// top-level and `structure` tags that are not otherwise understood, kept for round-trips
+    pub(crate) extra_tags: TagList,
+    pub(crate) extra_structure_tags: TagList,
+    /// palettes besides `default`
+    pub(crate) extra_palettes: TagList,
 }
 
 impl MCStructure {
@@ -73,36 +178,192 @@ impl MCStructure {
         }
         MCStructure {
             size,
+            blocks_layer2: blocks.clone(),
             blocks,
             palette: vec![],
-            block_position_data: vec![],
+            block_position_data: BTreeMap::new(),
+            entities: vec![],
+            extra_position_data: BTreeMap::new(),
+            origin: Vec3::_000,
+            format_version: 1,
+            default_block_version: DEFAULT_BLOCK_VERSION,
+            strict: false,
+            extra_tags: TagList::new(),
+            extra_structure_tags: TagList::new(),
+            extra_palettes: TagList::new(),
         }
     }
+    pub fn size(&self) -> Vec3<i32> {
+        self.size
+    }
+    pub fn origin(&self) -> Vec3<i32> {
+        self.origin
+    }
+    /// Sets `structure_world_origin`, the world position the structure was saved from
+    pub fn set_origin(&mut self, origin: Vec3<i32>) {
+        self.origin = origin;
+    }
+    pub fn format_version(&self) -> i32 {
+        self.format_version
+    }
+    pub fn set_format_version(&mut self, format_version: i32) {
+        self.format_version = format_version;
+    }
+    pub fn default_block_version(&self) -> i32 {
+        self.default_block_version
+    }
+    /// Sets the palette `version` written for blocks that do not specify their own
+    pub fn set_default_block_version(&mut self, version: i32) {
+        self.default_block_version = version;
+    }
+    pub fn contains(&self, loc: Vec3<i32>) -> bool {
+        (0..*self.size.x()).contains(loc.x())
+            && (0..*self.size.y()).contains(loc.y())
+            && (0..*self.size.z()).contains(loc.z())
+    }
+    pub(crate) fn contains_point(&self, loc: Vec3<f32>) -> bool {
+        (0.0..*self.size.x() as f32).contains(loc.x())
+            && (0.0..*self.size.y() as f32).contains(loc.y())
+            && (0.0..*self.size.z() as f32).contains(loc.z())
+    }
     pub fn getblock(&self, loc: Vec3<i32>) -> BlockType {
         let index = index_from_vec3(loc, self.size);
         self.palette[self.blocks[index] as usize].clone()
     }
-    pub fn setblock(&mut self, loc: Vec3<i32>, block: BlockType) -> Block<'_> {
+    /// In strict mode, rejects blocks and block states that are not in the block registry,
+    /// see `BlockType::validate`
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+    /// Rejects locations outside of the structure
+    pub(crate) fn check_bounds(&self, loc: Vec3<i32>) -> Result<(), Error> {
+        if !self.contains(loc) {
+            return Err(Error::OutOfBounds {
+                loc,
+                size: self.size,
+            });
+        }
+        Ok(())
+    }
+    /// Checks the block against the block registry in strict mode
+    fn check_block(&self, block: &BlockType) -> Result<(), Error> {
+        if self.strict {
+            block.validate()?;
+        }
+        Ok(())
+    }
+    /// Places a block. Fails if `loc` is outside of the structure, or in strict mode if the
+    /// block is invalid.
+    pub fn setblock(&mut self, loc: Vec3<i32>, block: BlockType) -> Result<Block<'_>, Error> {
+        self.check_bounds(loc)?;
+        self.check_block(&block)?;
         let index = index_from_vec3(loc, self.size);
-        self.blocks[index] = self.palette.len() as i32;
+        self.replace_block(index, self.palette.len() as i32, &block.type_id);
         self.palette.push(block.clone());
-        Block::new(block, index as u32, self)
+        Ok(Block::new(block, index as u32, self))
     }
-    pub fn palette_add(&mut self, block: BlockType) -> i32 {
+    /// Adds a block to the palette, checking it first in strict mode
+    pub fn palette_add(&mut self, block: BlockType) -> Result<i32, Error> {
+        self.check_block(&block)?;
         self.palette.push(block);
-        return (self.palette.len() - 1) as i32;
+        Ok((self.palette.len() - 1) as i32)
     }
-    pub fn setblock_palette(&mut self, loc: Vec3<i32>, palette_block: i32) -> Block<'_> {
+    /// Returns the palette index of an identical block, adding it to the palette if there is none
+    pub fn palette_find_or_add(&mut self, block: &BlockType) -> Result<i32, Error> {
+        match self.palette.iter().position(|entry| entry == block) {
+            Some(idx) => Ok(idx as i32),
+            None => self.palette_add(block.clone()),
+        }
+    }
+    /// Places the block at `palette_block` in the palette. In strict mode the block is checked,
+    /// as the palette may come from a file.
+    pub fn setblock_palette(
+        &mut self,
+        loc: Vec3<i32>,
+        palette_block: i32,
+    ) -> Result<Block<'_>, Error> {
+        self.check_bounds(loc)?;
+        let block = usize::try_from(palette_block)
+            .ok()
+            .and_then(|idx| self.palette.get(idx))
+            .ok_or(Error::UnknownPaletteIndex(palette_block))?
+            .clone();
+        self.check_block(&block)?;
         let index = index_from_vec3(loc, self.size);
+        self.replace_block(index, palette_block, &block.type_id);
+        Ok(Block::new(block, index as u32, self))
+    }
+    /// Sets the block at `index`, dropping the block entity of the old block if the block type
+    /// changes
+    pub(super) fn replace_block(&mut self, index: usize, palette_block: i32, type_id: &str) {
+        let old = self.blocks[index];
+        if old < 0 || self.palette[old as usize].type_id != type_id {
+            self.block_position_data.remove(&(index as u32));
+        }
         self.blocks[index] = palette_block;
-        Block::new(self.palette[palette_block as usize].clone(), index as u32, self)
+    }
+    pub(crate) fn block_entity_at_index(&self, index: u32) -> Option<&TagData> {
+        self.block_position_data.get(&index)
+    }
+    /// Returns the loot table path and seed of the container at `loc`
+    pub fn loot_table(&self, loc: Vec3<i32>) -> Option<(String, i32)> {
+        if !self.contains(loc) {
+            return None;
+        }
+        self.loot_table_at_index(index_from_vec3(loc, self.size) as u32)
+    }
+    pub(crate) fn loot_table_at_index(&self, index: u32) -> Option<(String, i32)> {
+        let data = self.block_entity_at_index(index)?;
+        let Some(TagData::String(path)) = data.find_tag("LootTable") else {
+            return None;
+        };
+        let seed = match data.find_tag("LootTableSeed") {
+            Some(TagData::Int(seed)) => *seed,
+            _ => 0,
+        };
+        Some((path.clone(), seed))
+    }
+    /// Adds an entity, with its `Pos` relative to the structure
+    pub fn add_entity(&mut self, entity: TagData) {
+        self.entities.push(entity);
+    }
+    pub fn entities(&self) -> &[TagData] {
+        &self.entities
+    }
+
+    /// `block_position_data` as written to files, with block entities at their world position
+    fn block_position_data_tags(&self) -> TagList {
+        let mut position_data = BTreeMap::<u32, TagList>::new();
+        for (index, data) in &self.block_position_data {
+            let mut data = data.clone();
+            translate_block_entity(&mut data, self.origin);
+            position_data
+                .entry(*index)
+                .or_default()
+                .add_tag("block_entity_data", data);
+        }
+        for (index, extra) in &self.extra_position_data {
+            let tags = position_data.entry(*index).or_default();
+            for (id, data) in extra.iter() {
+                tags.add_tag(id, data.clone());
+            }
+        }
+        TagList::from(
+            position_data
+                .into_iter()
+                .map(|(index, tags)| (index.to_string(), TagData::Compound(tags)))
+                .collect(),
+        )
     }
 
     pub fn as_bytes(&self) -> Vec<u8> {
         let mut compound = TagData::Compound(TagList::new());
 
         // format_version
-        compound.add_tag("format_version", TagData::Int(1));
+        compound.add_tag("format_version", TagData::Int(self.format_version));
 
         // size
         compound.add_tag(
@@ -138,15 +399,29 @@ impl MCStructure {
                             ),
                             TagData::List(
                                 TagKind::Int,
-                                self.blocks.len() as u32,
-                                vec![TagData::Int(-1); self.blocks.len()],
+                                self.blocks_layer2.len() as u32,
+                                self.blocks_layer2
+                                    .iter()
+                                    .map(|value| TagData::Int(*value))
+                                    .collect(),
                             ),
                         ],
                     ),
                 ),
                 (
                     "entities".to_string(),
-                    TagData::List(TagKind::Compound, 0, vec![]),
+                    TagData::List(
+                        TagKind::Compound,
+                        self.entities.len() as u32,
+                        self.entities
+                            .iter()
+                            .map(|entity| {
+                                let mut entity = entity.clone();
+                                translate_entity(&mut entity, self.origin);
+                                entity
+                            })
+                            .collect(),
+                    ),
                 ),
                 (
                     "palette".to_string(),
@@ -160,60 +435,13 @@ impl MCStructure {
                                     self.palette.len() as u32,
                                     self.palette
                                         .iter()
-                                        .map(|value| {
-                                            TagData::Compound(TagList::from(vec![
-                                                (
-                                                    "name".to_string(),
-                                                    TagData::String(value.type_id.clone()),
-                                                ),
-                                                (
-                                                    "states".to_string(),
-                                                    TagData::Compound(TagList::from(
-                                                        value
-                                                            .states
-                                                            .iter()
-                                                            .map(|state| {
-                                                                (
-                                                                    state.0.clone(),
-                                                                    match &state.1 {
-                                                                        BlockState::String(
-                                                                            string,
-                                                                        ) => TagData::String(
-                                                                            string.to_string(),
-                                                                        ),
-                                                                        BlockState::Int(int) => {
-                                                                            TagData::Int(*int)
-                                                                        }
-                                                                        BlockState::Bool(b) => {
-                                                                            TagData::Byte(*b as i8)
-                                                                        }
-                                                                    },
-                                                                )
-                                                            })
-                                                            .collect(),
-                                                    )),
-                                                ),
-                                            ]))
-                                        })
+                                        .map(|value| value.as_tag(self.default_block_version))
                                         .collect(),
                                 ),
                             ),
                             (
                                 "block_position_data".to_string(),
-                                TagData::Compound(TagList::from(
-                                    self.block_position_data
-                                        .iter()
-                                        .map(|value| {
-                                            (
-                                                value.0.to_string(),
-                                                TagData::Compound(TagList::from(vec![(
-                                                    "block_entity_data".to_string(),
-                                                    value.1.clone(),
-                                                )])),
-                                            )
-                                        })
-                                        .collect(),
-                                )),
+                                TagData::Compound(self.block_position_data_tags()),
                             ),
                         ])),
                     )])),
@@ -224,9 +452,31 @@ impl MCStructure {
         // structure_world_origin
         compound.add_tag(
             "structure_world_origin",
-            TagData::List(TagKind::Int, 3, vec![TagData::Int(0); 3]),
+            TagData::List(
+                TagKind::Int,
+                3,
+                vec![
+                    TagData::Int(*self.origin.x()),
+                    TagData::Int(*self.origin.y()),
+                    TagData::Int(*self.origin.z()),
+                ],
+            ),
         );
 
+        for (id, data) in self.extra_tags.iter() {
+            compound.add_tag(id, data.clone());
+        }
+        if let Some(structure) = compound.get_tag("structure") {
+            if let Some(palette) = structure.get_tag("palette") {
+                for (id, data) in self.extra_palettes.iter() {
+                    palette.add_tag(id, data.clone());
+                }
+            }
+            for (id, data) in self.extra_structure_tags.iter() {
+                structure.add_tag(id, data.clone());
+            }
+        }
+
         let nbt = NbtTree::new(vec![("".to_string(), compound)]);
 
         nbt.as_bytes(true)
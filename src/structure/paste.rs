@@ -0,0 +1,364 @@
+use crate::{
+    error::Error,
+    structure::{
+        MCStructure, index_from_vec3, resize::check_size, set_block_entity_position,
+        translate_entity, vec3_from_index,
+    },
+    types::Vec3,
+};
+
+#[derive(Debug, Copy, Clone)]
+pub struct PasteOptions {
+    /// Leave the destination untouched where the source has `minecraft:air`
+    pub skip_air: bool,
+    /// Leave the destination untouched where the source has no block (structure void)
+    pub skip_void: bool,
+    /// Replace block entities already present in the destination. Otherwise they are kept
+    /// where the destination block keeps its type, the same as with `setblock`.
+    pub overwrite_block_entities: bool,
+    pub include_entities: bool,
+}
+
+impl Default for PasteOptions {
+    fn default() -> Self {
+        PasteOptions {
+            skip_air: false,
+            skip_void: true,
+            overwrite_block_entities: true,
+            include_entities: true,
+        }
+    }
+}
+
+/// Rejects regions with `min` past `max` on some axis
+pub(super) fn check_region(min: Vec3<i32>, max: Vec3<i32>) -> Result<(), Error> {
+    if min.x() > max.x() || min.y() > max.y() || min.z() > max.z() {
+        return Err(Error::InvalidRegion { min, max });
+    }
+    Ok(())
+}
+
+impl MCStructure {
+    /// Copies `other` into this structure with its lowest corner at `offset`, relative to this
+    /// structure whatever the `origin` of either is. Parts of `other` that fall outside of this
+    /// structure are clipped, except for entities without a `Pos`, which are kept the same as
+    /// with `resize`. In strict mode nothing is pasted if a block of `other` is invalid.
+    pub fn paste(
+        &mut self,
+        other: &MCStructure,
+        offset: Vec3<i32>,
+        options: PasteOptions,
+    ) -> Result<(), Error> {
+        if self.strict {
+            let mut used = vec![false; other.palette.len()];
+            for block in other.blocks.iter().chain(&other.blocks_layer2) {
+                if *block >= 0 {
+                    used[*block as usize] = true;
+                }
+            }
+            for (block, _) in other.palette.iter().zip(used).filter(|(_, used)| *used) {
+                self.check_block(block)?;
+            }
+        }
+        let mut remap = vec![None::<i32>; other.palette.len()];
+        let mut add = |structure: &mut MCStructure, block: i32| {
+            *remap[block as usize].get_or_insert_with(|| {
+                structure
+                    .palette_find_or_add(&other.palette[block as usize])
+                    .expect("the blocks were checked above")
+            })
+        };
+
+        for (index, block) in other.blocks.iter().enumerate() {
+            let loc = vec3_from_index(index, other.size) + offset;
+            if !self.contains(loc) {
+                continue;
+            }
+            let (new_block, type_id) = if *block < 0 {
+                if options.skip_void {
+                    continue;
+                }
+                (-1, "")
+            } else {
+                let block_type = &other.palette[*block as usize];
+                if options.skip_air && block_type.type_id == "minecraft:air" {
+                    continue;
+                }
+                (add(self, *block), block_type.type_id.as_str())
+            };
+            let block_layer2 = other.blocks_layer2[index];
+            let new_block_layer2 = if block_layer2 < 0 {
+                -1
+            } else {
+                add(self, block_layer2)
+            };
+            let dest_index = index_from_vec3(loc, self.size);
+            self.replace_block(dest_index, new_block, type_id);
+            self.blocks_layer2[dest_index] = new_block_layer2;
+
+            let dest_index = dest_index as u32;
+            match other.extra_position_data.get(&(index as u32)) {
+                Some(extra) => self.extra_position_data.insert(dest_index, extra.clone()),
+                None => self.extra_position_data.remove(&dest_index),
+            };
+            let exists = self.block_position_data.contains_key(&dest_index);
+            match other.block_position_data.get(&(index as u32)) {
+                Some(data) if options.overwrite_block_entities || !exists => {
+                    let mut data = data.clone();
+                    set_block_entity_position(&mut data, loc);
+                    self.block_position_data.insert(dest_index, data);
+                }
+                None if options.overwrite_block_entities => {
+                    self.block_position_data.remove(&dest_index);
+                }
+                _ => {}
+            }
+        }
+
+        if options.include_entities {
+            for entity in &other.entities {
+                let mut entity = entity.clone();
+                match translate_entity(&mut entity, offset) {
+                    Some(pos) if !self.contains_point(pos) => {}
+                    _ => self.entities.push(entity),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Copies the region between `min` and `max` (inclusive) into a new structure, with its
+    /// origin moved along so it stays in the same place in the world
+    pub fn extract(&self, min: Vec3<i32>, max: Vec3<i32>) -> Result<MCStructure, Error> {
+        check_region(min, max)?;
+        let size = max - min + Vec3::new(1, 1, 1);
+        check_size(size)?;
+        let mut structure = MCStructure::new(size);
+        structure.origin = self.origin + min;
+        structure.strict = self.strict;
+        structure.paste(self, Vec3::_000 - min, PasteOptions::default())?;
+        Ok(structure)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nbt::{TagData, TagKind, TagList},
+        types::BlockType,
+    };
+
+    fn block(type_id: &str) -> BlockType {
+        BlockType::new(type_id)
+    }
+
+    fn with_chest(structure: &mut MCStructure, loc: Vec3<i32>, name: &str) {
+        structure.setblock(loc, block("minecraft:chest")).unwrap();
+        let mut data = TagData::Compound(TagList::from(vec![
+            ("id".to_string(), TagData::String("Chest".to_string())),
+            ("CustomName".to_string(), TagData::String(name.to_string())),
+        ]));
+        set_block_entity_position(&mut data, loc);
+        structure
+            .block_position_data
+            .insert(index_from_vec3(loc, structure.size) as u32, data);
+    }
+
+    fn chest_name(structure: &MCStructure, loc: Vec3<i32>) -> Option<String> {
+        let index = index_from_vec3(loc, structure.size) as u32;
+        match structure
+            .block_entity_at_index(index)?
+            .find_tag("CustomName")
+        {
+            Some(TagData::String(name)) => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    fn entity(pos: Option<[f32; 3]>) -> TagData {
+        let mut tags = vec![(
+            "identifier".to_string(),
+            TagData::String("minecraft:pig".to_string()),
+        )];
+        if let Some(pos) = pos {
+            tags.push((
+                "Pos".to_string(),
+                TagData::List(TagKind::Float, 3, pos.map(TagData::Float).to_vec()),
+            ));
+        }
+        TagData::Compound(TagList::from(tags))
+    }
+
+    #[test]
+    fn paste_clips_to_the_destination() {
+        let mut source = MCStructure::new(Vec3::new(2, 1, 1));
+        source
+            .setblock(Vec3::new(0, 0, 0), block("minecraft:stone"))
+            .unwrap();
+        source
+            .setblock(Vec3::new(1, 0, 0), block("minecraft:dirt"))
+            .unwrap();
+        let mut dest = MCStructure::new(Vec3::new(2, 1, 1));
+        dest.paste(&source, Vec3::new(1, 0, 0), PasteOptions::default())
+            .unwrap();
+
+        assert_eq!(dest.blocks[0], -1);
+        assert_eq!(dest.getblock(Vec3::new(1, 0, 0)), block("minecraft:stone"));
+    }
+
+    #[test]
+    fn paste_skips_air_and_void() {
+        let mut source = MCStructure::new(Vec3::new(2, 1, 1));
+        source
+            .setblock(Vec3::new(0, 0, 0), block("minecraft:air"))
+            .unwrap();
+        let mut dest = MCStructure::new(Vec3::new(2, 1, 1));
+        dest.setblock(Vec3::new(0, 0, 0), block("minecraft:stone"))
+            .unwrap();
+        dest.setblock(Vec3::new(1, 0, 0), block("minecraft:stone"))
+            .unwrap();
+
+        let options = PasteOptions {
+            skip_air: true,
+            ..PasteOptions::default()
+        };
+        dest.paste(&source, Vec3::_000, options).unwrap();
+        assert_eq!(dest.getblock(Vec3::new(0, 0, 0)), block("minecraft:stone"));
+        assert_eq!(dest.getblock(Vec3::new(1, 0, 0)), block("minecraft:stone"));
+
+        let options = PasteOptions {
+            skip_void: false,
+            ..PasteOptions::default()
+        };
+        dest.paste(&source, Vec3::_000, options).unwrap();
+        assert_eq!(dest.getblock(Vec3::new(0, 0, 0)), block("minecraft:air"));
+        assert_eq!(dest.blocks[1], -1);
+    }
+
+    #[test]
+    fn paste_keeps_block_entities_only_where_the_type_is_kept() {
+        let mut source = MCStructure::new(Vec3::new(3, 1, 1));
+        with_chest(&mut source, Vec3::new(0, 0, 0), "source");
+        source
+            .setblock(Vec3::new(1, 0, 0), block("minecraft:chest"))
+            .unwrap();
+        source
+            .setblock(Vec3::new(2, 0, 0), block("minecraft:stone"))
+            .unwrap();
+        let mut dest = MCStructure::new(Vec3::new(3, 1, 1));
+        for x in 0..3 {
+            with_chest(&mut dest, Vec3::new(x, 0, 0), "dest");
+        }
+        let options = PasteOptions {
+            overwrite_block_entities: false,
+            ..PasteOptions::default()
+        };
+        dest.paste(&source, Vec3::_000, options).unwrap();
+
+        assert_eq!(
+            chest_name(&dest, Vec3::new(0, 0, 0)),
+            Some("dest".to_string())
+        );
+        assert_eq!(
+            chest_name(&dest, Vec3::new(1, 0, 0)),
+            Some("dest".to_string())
+        );
+        assert_eq!(chest_name(&dest, Vec3::new(2, 0, 0)), None);
+
+        let mut dest = MCStructure::new(Vec3::new(1, 1, 1));
+        dest.setblock(Vec3::new(0, 0, 0), block("minecraft:barrel"))
+            .unwrap();
+        dest.block_position_data
+            .insert(0, TagData::Compound(TagList::from(vec![])));
+        dest.paste(&source, Vec3::_000, options).unwrap();
+        assert_eq!(
+            chest_name(&dest, Vec3::new(0, 0, 0)),
+            Some("source".to_string())
+        );
+
+        let mut dest = MCStructure::new(Vec3::new(3, 1, 1));
+        for x in 0..3 {
+            with_chest(&mut dest, Vec3::new(x, 0, 0), "dest");
+        }
+        dest.paste(&source, Vec3::_000, PasteOptions::default())
+            .unwrap();
+        assert_eq!(
+            chest_name(&dest, Vec3::new(0, 0, 0)),
+            Some("source".to_string())
+        );
+        assert!(dest.block_entity_at_index(1).is_none());
+    }
+
+    #[test]
+    fn paste_moves_block_entities() {
+        let mut source = MCStructure::new(Vec3::new(1, 1, 1));
+        with_chest(&mut source, Vec3::_000, "source");
+        let mut dest = MCStructure::new(Vec3::new(3, 1, 3));
+        dest.paste(&source, Vec3::new(2, 0, 1), PasteOptions::default())
+            .unwrap();
+
+        let data = dest
+            .block_entity_at_index(index_from_vec3(Vec3::new(2, 0, 1), dest.size) as u32)
+            .unwrap();
+        assert_eq!(data.find_tag("x"), Some(&TagData::Int(2)));
+        assert_eq!(data.find_tag("z"), Some(&TagData::Int(1)));
+    }
+
+    #[test]
+    fn paste_clips_entities_and_keeps_those_without_pos() {
+        let mut source = MCStructure::new(Vec3::new(2, 1, 1));
+        source.add_entity(entity(Some([0.5, 0.0, 0.5])));
+        source.add_entity(entity(Some([1.5, 0.0, 0.5])));
+        source.add_entity(entity(None));
+        let mut dest = MCStructure::new(Vec3::new(2, 1, 1));
+        dest.paste(&source, Vec3::new(1, 0, 0), PasteOptions::default())
+            .unwrap();
+
+        assert_eq!(dest.entities().len(), 2);
+        assert_eq!(
+            dest.entities()[0].find_tag("Pos"),
+            Some(&TagData::List(
+                TagKind::Float,
+                3,
+                vec![
+                    TagData::Float(1.5),
+                    TagData::Float(0.0),
+                    TagData::Float(0.5)
+                ]
+            ))
+        );
+        assert_eq!(dest.entities()[1], entity(None));
+
+        let mut dest = MCStructure::new(Vec3::new(2, 1, 1));
+        let options = PasteOptions {
+            include_entities: false,
+            ..PasteOptions::default()
+        };
+        dest.paste(&source, Vec3::_000, options).unwrap();
+        assert!(dest.entities().is_empty());
+    }
+
+    #[test]
+    fn extract_copies_a_region() {
+        let mut structure = MCStructure::new(Vec3::new(3, 2, 3));
+        structure.set_origin(Vec3::new(10, 64, -5));
+        structure
+            .setblock(Vec3::new(1, 1, 2), block("minecraft:stone"))
+            .unwrap();
+        let region = structure
+            .extract(Vec3::new(1, 0, 1), Vec3::new(2, 1, 2))
+            .unwrap();
+
+        assert_eq!(region.size(), Vec3::new(2, 2, 2));
+        assert_eq!(region.origin(), Vec3::new(11, 64, -4));
+        assert_eq!(
+            region.getblock(Vec3::new(0, 1, 1)),
+            block("minecraft:stone")
+        );
+        assert!(matches!(
+            structure.extract(Vec3::new(2, 0, 0), Vec3::new(1, 0, 0)),
+            Err(Error::InvalidRegion { .. })
+        ));
+    }
+}
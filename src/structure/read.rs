@@ -0,0 +1,403 @@
+use crate::{
+    error::Error,
+    nbt::{NbtTree, TagData},
+    structure::{MCStructure, translate_block_entity, translate_entity},
+    types::{BlockState, BlockType, Vec3},
+};
+
+fn invalid(reason: String) -> Error {
+    Error::InvalidStructure(reason)
+}
+
+pub(super) fn take_tag(compound: &mut TagData, id: &str) -> Result<TagData, Error> {
+    compound
+        .remove_tag(id)
+        .ok_or_else(|| invalid(format!("missing '{}'", id)))
+}
+
+pub(super) fn read_vec3(data: TagData, id: &str) -> Result<Vec3<i32>, Error> {
+    if let TagData::List(_, _, list) = &data
+        && let [TagData::Int(x), TagData::Int(y), TagData::Int(z)] = list[..]
+    {
+        return Ok(Vec3::new(x, y, z));
+    }
+    Err(invalid(format!("'{}' is not a list of 3 ints", id)))
+}
+
+fn read_int_list(data: TagData, id: &str) -> Result<Vec<i32>, Error> {
+    let TagData::List(_, _, list) = data else {
+        return Err(invalid(format!("'{}' is not a list", id)));
+    };
+    list.into_iter()
+        .map(|value| match value {
+            TagData::Int(int) => Ok(int),
+            _ => Err(invalid(format!("'{}' contains a non-int value", id))),
+        })
+        .collect()
+}
+
+fn read_layer(data: TagData, len: usize) -> Result<Vec<i32>, Error> {
+    let blocks = read_int_list(data, "block_indices")?;
+    if blocks.len() != len {
+        return Err(invalid(format!(
+            "expected {} block indices, found {}",
+            len,
+            blocks.len()
+        )));
+    }
+    Ok(blocks)
+}
+
+fn read_block_type(data: TagData) -> Result<BlockType, Error> {
+    let TagData::Compound(mut compound) = data else {
+        return Err(invalid("palette entry is not a compound".to_string()));
+    };
+    let Some(TagData::String(type_id)) = compound.remove_tag("name") else {
+        return Err(invalid("palette entry has no name".to_string()));
+    };
+    let mut block = BlockType::new(&type_id);
+    if let Some(TagData::Compound(states)) = compound.remove_tag("states") {
+        for (name, state) in states {
+            let state = match state {
+                TagData::Byte(byte) => BlockState::Bool(byte as u8),
+                TagData::Int(int) => BlockState::Int(int),
+                TagData::String(string) => BlockState::String(string),
+                _ => {
+                    return Err(invalid(format!(
+                        "state '{}' of '{}' has an unsupported type",
+                        name, type_id
+                    )));
+                }
+            };
+            block.states.push((name, state));
+        }
+    }
+    if let Some(TagData::Int(version)) = compound.remove_tag("version") {
+        block.version = Some(version);
+    }
+    Ok(block)
+}
+
+impl MCStructure {
+    /// Reads a structure from the contents of a `.mcstructure` file
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<MCStructure, Error> {
+        let mut root = match NbtTree::from_bytes(true, bytes)?
+            .into_entries()
+            .into_iter()
+            .next()
+        {
+            Some((_, compound @ TagData::Compound(_))) => compound,
+            _ => return Err(invalid("root tag is not a compound".to_string())),
+        };
+
+        let size = read_vec3(take_tag(&mut root, "size")?, "size")?;
+        if *size.x() < 0 || *size.y() < 0 || *size.z() < 0 {
+            return Err(invalid("negative size".to_string()));
+        }
+        let volume = size
+            .x()
+            .checked_mul(*size.y())
+            .and_then(|area| area.checked_mul(*size.z()))
+            .ok_or_else(|| invalid("size is too large".to_string()))? as usize;
+
+        let mut structure_tag = take_tag(&mut root, "structure")?;
+        if !matches!(structure_tag, TagData::Compound(_)) {
+            return Err(invalid("'structure' is not a compound".to_string()));
+        }
+
+        // the block lists are checked against the size before it is allocated
+        let TagData::List(_, _, layers) = take_tag(&mut structure_tag, "block_indices")? else {
+            return Err(invalid("'block_indices' is not a list".to_string()));
+        };
+        let mut layers = layers.into_iter();
+        let Some(layer) = layers.next() else {
+            return Err(invalid("'block_indices' has no layers".to_string()));
+        };
+        let blocks = read_layer(layer, volume)?;
+        let blocks_layer2 = layers
+            .next()
+            .map(|layer| read_layer(layer, volume))
+            .transpose()?;
+
+        let mut structure = MCStructure::new(size);
+        structure.blocks = blocks;
+        if let Some(blocks_layer2) = blocks_layer2 {
+            structure.blocks_layer2 = blocks_layer2;
+        }
+
+        if let Some(TagData::Int(format_version)) = root.remove_tag("format_version") {
+            structure.format_version = format_version;
+        }
+        if let Some(origin) = root.remove_tag("structure_world_origin") {
+            structure.origin = read_vec3(origin, "structure_world_origin")?;
+        }
+
+        // positions are kept relative to the structure
+        let origin = structure.origin;
+        let offset = Vec3::new(
+            origin.x().wrapping_neg(),
+            origin.y().wrapping_neg(),
+            origin.z().wrapping_neg(),
+        );
+        if let Some(TagData::List(_, _, entities)) = structure_tag.remove_tag("entities") {
+            structure.entities = entities;
+            for entity in &mut structure.entities {
+                translate_entity(entity, offset);
+            }
+        }
+
+        let mut palette = take_tag(&mut structure_tag, "palette")?;
+        if let TagData::Compound(_) = palette
+            && let Some(mut default) = palette.remove_tag("default")
+            && let TagData::Compound(_) = default
+        {
+            if let Some(TagData::List(_, _, block_palette)) = default.remove_tag("block_palette") {
+                for entry in block_palette {
+                    structure.palette.push(read_block_type(entry)?);
+                }
+            }
+            if let Some(TagData::Compound(position_data)) =
+                default.remove_tag("block_position_data")
+            {
+                for (index, mut data) in position_data {
+                    let index = index
+                        .parse::<u32>()
+                        .map_err(|_| invalid(format!("invalid block index '{}'", index)))?;
+                    if index as usize >= structure.blocks.len() {
+                        return Err(invalid(format!("block index {} is out of bounds", index)));
+                    }
+                    if let TagData::Compound(_) = data
+                        && let Some(mut block_entity) = data.remove_tag("block_entity_data")
+                    {
+                        translate_block_entity(&mut block_entity, offset);
+                        structure.block_position_data.insert(index, block_entity);
+                    }
+                    if let TagData::Compound(extra) = data
+                        && extra.iter().next().is_some()
+                    {
+                        structure.extra_position_data.insert(index, extra);
+                    }
+                }
+            }
+        }
+
+        if let Some(block) = structure
+            .blocks
+            .iter()
+            .chain(&structure.blocks_layer2)
+            .find(|block| **block >= structure.palette.len() as i32 || **block < -1)
+        {
+            return Err(invalid(format!(
+                "block index {} is not in the palette",
+                block
+            )));
+        }
+
+        if let TagData::Compound(extra) = palette {
+            structure.extra_palettes = extra;
+        }
+        if let TagData::Compound(extra) = structure_tag {
+            structure.extra_structure_tags = extra;
+        }
+        if let TagData::Compound(extra) = root {
+            structure.extra_tags = extra;
+        }
+
+        Ok(structure)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt::{TagKind, TagList};
+
+    fn root(structure: &MCStructure) -> TagData {
+        NbtTree::from_bytes(true, structure.as_bytes())
+            .unwrap()
+            .into_entries()
+            .into_iter()
+            .next()
+            .unwrap()
+            .1
+    }
+
+    fn bytes(root: TagData) -> Vec<u8> {
+        NbtTree::new(vec![(String::new(), root)]).as_bytes(true)
+    }
+
+    fn int_list(values: &[i32]) -> TagData {
+        TagData::List(
+            TagKind::Int,
+            values.len() as u32,
+            values.iter().map(|value| TagData::Int(*value)).collect(),
+        )
+    }
+
+    fn sample() -> MCStructure {
+        let mut structure = MCStructure::new(Vec3::new(2, 1, 2));
+        structure.set_origin(Vec3::new(100, 64, -20));
+        structure.set_format_version(2);
+        structure
+            .setblock(Vec3::new(0, 0, 1), BlockType::new("minecraft:chest"))
+            .unwrap();
+        structure.blocks_layer2[1] = structure
+            .palette_add(BlockType::new("minecraft:water"))
+            .unwrap();
+        structure.block_position_data.insert(
+            1,
+            TagData::Compound(TagList::from(vec![
+                ("id".to_string(), TagData::String("Chest".to_string())),
+                ("x".to_string(), TagData::Int(0)),
+                ("y".to_string(), TagData::Int(0)),
+                ("z".to_string(), TagData::Int(1)),
+            ])),
+        );
+        structure.add_entity(TagData::Compound(TagList::from(vec![(
+            "Pos".to_string(),
+            TagData::List(
+                TagKind::Float,
+                3,
+                vec![
+                    TagData::Float(1.5),
+                    TagData::Float(0.0),
+                    TagData::Float(0.5),
+                ],
+            ),
+        )])));
+        structure
+    }
+
+    #[test]
+    fn round_trip() {
+        let structure = sample();
+        let bytes = structure.as_bytes();
+        let read = MCStructure::from_bytes(bytes.clone()).unwrap();
+
+        assert_eq!(read.size(), Vec3::new(2, 1, 2));
+        assert_eq!(read.origin(), Vec3::new(100, 64, -20));
+        assert_eq!(read.format_version(), 2);
+        assert_eq!(read.blocks, structure.blocks);
+        assert_eq!(read.blocks_layer2, structure.blocks_layer2);
+        assert_eq!(
+            read.palette
+                .iter()
+                .map(|block| &block.type_id)
+                .collect::<Vec<_>>(),
+            vec!["minecraft:chest", "minecraft:water"]
+        );
+        assert_eq!(read.block_position_data, structure.block_position_data);
+        assert_eq!(read.entities, structure.entities);
+        assert_eq!(read.as_bytes(), bytes);
+    }
+
+    #[test]
+    fn positions_are_absolute_in_files() {
+        let root = root(&sample());
+        let block_entity = root
+            .find_tag("structure")
+            .and_then(|structure| structure.find_tag("palette"))
+            .and_then(|palette| palette.find_tag("default"))
+            .and_then(|default| default.find_tag("block_position_data"))
+            .and_then(|data| data.find_tag("1"))
+            .and_then(|data| data.find_tag("block_entity_data"))
+            .unwrap();
+        assert_eq!(block_entity.find_tag("x"), Some(&TagData::Int(100)));
+        assert_eq!(block_entity.find_tag("z"), Some(&TagData::Int(-19)));
+    }
+
+    #[test]
+    fn keeps_unknown_tags() {
+        let mut root = root(&sample());
+        root.add_tag("custom", TagData::String("kept".to_string()));
+        let structure = root.get_tag("structure").unwrap();
+        structure.add_tag("custom", TagData::Int(7));
+        let palette = structure.get_tag("palette").unwrap();
+        palette.add_tag("other", TagData::Compound(TagList::new()));
+        let position_data = palette
+            .get_tag("default")
+            .and_then(|default| default.get_tag("block_position_data"))
+            .unwrap();
+        position_data.add_tag(
+            "2",
+            TagData::Compound(TagList::from(vec![(
+                "tick_queue_data".to_string(),
+                TagData::Int(1),
+            )])),
+        );
+
+        let structure = MCStructure::from_bytes(bytes(root)).unwrap();
+        assert_eq!(
+            structure.extra_tags.find_tag("custom"),
+            Some(&TagData::String("kept".to_string()))
+        );
+        assert_eq!(structure.extra_structure_tags.iter().count(), 1);
+        assert_eq!(structure.extra_palettes.iter().count(), 1);
+        assert!(structure.extra_position_data.contains_key(&2));
+
+        let again = MCStructure::from_bytes(structure.as_bytes()).unwrap();
+        assert_eq!(again.as_bytes(), structure.as_bytes());
+    }
+
+    #[test]
+    fn rejects_invalid_structures() {
+        let invalid = |edit: &dyn Fn(&mut TagData)| {
+            let mut root = root(&sample());
+            edit(&mut root);
+            matches!(
+                MCStructure::from_bytes(bytes(root)),
+                Err(Error::InvalidStructure(_))
+            )
+        };
+        assert!(invalid(&|root| root.set_tag("size", int_list(&[-1, 1, 2]))));
+        assert!(invalid(
+            &|root| root.set_tag("size", int_list(&[i32::MAX, i32::MAX, 2]))
+        ));
+        assert!(invalid(&|root| root.set_tag("size", int_list(&[3, 1, 2]))));
+        assert!(invalid(&|root| {
+            root.remove_tag("structure");
+        }));
+        assert!(invalid(&|root| {
+            let structure = root.get_tag("structure").unwrap();
+            structure.set_tag(
+                "block_indices",
+                TagData::List(TagKind::List, 1, vec![int_list(&[0, 5, -1, -1])]),
+            );
+        }));
+        assert!(invalid(&|root| {
+            let data = root
+                .get_tag("structure")
+                .and_then(|structure| structure.get_tag("palette"))
+                .and_then(|palette| palette.get_tag("default"))
+                .and_then(|default| default.get_tag("block_position_data"))
+                .unwrap();
+            data.add_tag("4", TagData::Compound(TagList::new()));
+        }));
+        assert!(MCStructure::from_bytes(vec![]).is_err());
+    }
+
+    #[test]
+    fn reads_damaged_positions() {
+        let mut root = root(&sample());
+        root.set_tag("structure_world_origin", int_list(&[i32::MIN, 0, i32::MAX]));
+        let data = root
+            .get_tag("structure")
+            .and_then(|structure| structure.get_tag("palette"))
+            .and_then(|palette| palette.get_tag("default"))
+            .and_then(|default| default.get_tag("block_position_data"))
+            .unwrap();
+        data.add_tag(
+            "3",
+            TagData::Compound(TagList::from(vec![(
+                "block_entity_data".to_string(),
+                TagData::Int(0),
+            )])),
+        );
+
+        let structure = MCStructure::from_bytes(bytes(root)).unwrap();
+        assert_eq!(structure.origin(), Vec3::new(i32::MIN, 0, i32::MAX));
+        assert!(structure.block_entity_at_index(1).is_some());
+        // left as it is, so it is still written back
+        assert_eq!(structure.block_entity_at_index(3), Some(&TagData::Int(0)));
+    }
+}
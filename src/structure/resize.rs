@@ -0,0 +1,263 @@
+use crate::{
+    error::Error,
+    structure::{
+        MCStructure, index_from_vec3, paste::check_region, set_block_entity_position,
+        translate_entity, vec3_from_index,
+    },
+    types::Vec3,
+};
+
+/// Where the existing blocks are kept along an axis when resizing
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Anchor {
+    Min,
+    Center,
+    Max,
+}
+
+impl Anchor {
+    fn offset(self, old: i32, new: i32) -> i32 {
+        match self {
+            Anchor::Min => 0,
+            Anchor::Center => (new - old) / 2,
+            Anchor::Max => new - old,
+        }
+    }
+}
+
+/// Rejects sizes that are negative or too large to index
+pub(super) fn check_size(size: Vec3<i32>) -> Result<(), Error> {
+    if *size.x() < 0 || *size.y() < 0 || *size.z() < 0 {
+        return Err(Error::InvalidSize(size));
+    }
+    size.x()
+        .checked_mul(*size.y())
+        .and_then(|area| area.checked_mul(*size.z()))
+        .ok_or(Error::InvalidSize(size))?;
+    Ok(())
+}
+
+impl MCStructure {
+    /// Changes the size of the structure, filling new space with structure void and
+    /// discarding anything that no longer fits. The origin moves so that the kept blocks stay
+    /// in the same place in the world.
+    pub fn resize(&mut self, new_size: Vec3<i32>, anchor: Vec3<Anchor>) -> Result<(), Error> {
+        check_size(new_size)?;
+        let offset = Vec3::new(
+            anchor.x().offset(*self.size.x(), *new_size.x()),
+            anchor.y().offset(*self.size.y(), *new_size.y()),
+            anchor.z().offset(*self.size.z(), *new_size.z()),
+        );
+        self.rebuild(new_size, offset);
+        Ok(())
+    }
+    /// Shrinks the structure to the region between `min` and `max` (inclusive), moving the
+    /// origin to `min`
+    pub fn crop(&mut self, min: Vec3<i32>, max: Vec3<i32>) -> Result<(), Error> {
+        check_region(min, max)?;
+        let size = max - min + Vec3::new(1, 1, 1);
+        check_size(size)?;
+        self.rebuild(size, Vec3::_000 - min);
+        Ok(())
+    }
+    /// Shrinks the structure to the bounding box of its blocks, moving the origin like `crop`.
+    /// Does nothing if the structure is empty.
+    pub fn trim(&mut self, ignore_air: bool) {
+        if let Some((min, max)) = self.bounding_box(ignore_air) {
+            self.rebuild(max - min + Vec3::new(1, 1, 1), Vec3::_000 - min);
+        }
+    }
+    /// Returns the inclusive bounds of all non-void blocks, also skipping `minecraft:air`
+    /// if `ignore_air` is set
+    pub fn bounding_box(&self, ignore_air: bool) -> Option<(Vec3<i32>, Vec3<i32>)> {
+        let mut bounds: Option<(Vec3<i32>, Vec3<i32>)> = None;
+        for (index, block) in self.blocks.iter().enumerate() {
+            if *block < 0
+                || (ignore_air && self.palette[*block as usize].type_id == "minecraft:air")
+            {
+                continue;
+            }
+            let loc = vec3_from_index(index, self.size);
+            bounds = Some(match bounds {
+                None => (loc, loc),
+                Some((min, max)) => (
+                    Vec3::new(
+                        *min.x().min(loc.x()),
+                        *min.y().min(loc.y()),
+                        *min.z().min(loc.z()),
+                    ),
+                    Vec3::new(
+                        *max.x().max(loc.x()),
+                        *max.y().max(loc.y()),
+                        *max.z().max(loc.z()),
+                    ),
+                ),
+            });
+        }
+        bounds
+    }
+
+    /// Moves everything by `offset` into a structure of `new_size`
+    fn rebuild(&mut self, new_size: Vec3<i32>, offset: Vec3<i32>) {
+        let old_size = self.size;
+        self.size = new_size;
+        self.origin = self.origin - offset;
+
+        let mut blocks = vec![-1; (new_size.x() * new_size.y() * new_size.z()) as usize];
+        let mut blocks_layer2 = blocks.clone();
+        for (index, (block, block_layer2)) in
+            self.blocks.iter().zip(&self.blocks_layer2).enumerate()
+        {
+            let loc = vec3_from_index(index, old_size) + offset;
+            if self.contains(loc) {
+                blocks[index_from_vec3(loc, new_size)] = *block;
+                blocks_layer2[index_from_vec3(loc, new_size)] = *block_layer2;
+            }
+        }
+        self.blocks = blocks;
+        self.blocks_layer2 = blocks_layer2;
+
+        let extra_position_data = std::mem::take(&mut self.extra_position_data);
+        for (index, extra) in extra_position_data {
+            let loc = vec3_from_index(index as usize, old_size) + offset;
+            if self.contains(loc) {
+                self.extra_position_data
+                    .insert(index_from_vec3(loc, new_size) as u32, extra);
+            }
+        }
+
+        let block_position_data = std::mem::take(&mut self.block_position_data);
+        for (index, mut data) in block_position_data {
+            let loc = vec3_from_index(index as usize, old_size) + offset;
+            if self.contains(loc) {
+                set_block_entity_position(&mut data, loc);
+                self.block_position_data
+                    .insert(index_from_vec3(loc, new_size) as u32, data);
+            }
+        }
+
+        // entities without a `Pos` are kept, as there is nothing to clip them by
+        let entities = std::mem::take(&mut self.entities);
+        for mut entity in entities {
+            match translate_entity(&mut entity, offset) {
+                Some(pos) if !self.contains_point(pos) => {}
+                _ => self.entities.push(entity),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nbt::{TagData, TagKind, TagList},
+        types::BlockType,
+    };
+
+    fn stone() -> BlockType {
+        BlockType::new("minecraft:stone")
+    }
+
+    fn entity_at(x: f32, y: f32, z: f32) -> TagData {
+        TagData::Compound(TagList::from(vec![(
+            "Pos".to_string(),
+            TagData::List(
+                TagKind::Float,
+                3,
+                vec![TagData::Float(x), TagData::Float(y), TagData::Float(z)],
+            ),
+        )]))
+    }
+
+    #[test]
+    fn resize_anchors() {
+        let anchored = |anchor| {
+            let mut structure = MCStructure::new(Vec3::new(2, 1, 1));
+            structure.set_origin(Vec3::new(100, 0, 0));
+            structure.setblock(Vec3::new(0, 0, 0), stone()).unwrap();
+            structure
+                .resize(
+                    Vec3::new(6, 1, 1),
+                    Vec3::new(anchor, Anchor::Min, Anchor::Min),
+                )
+                .unwrap();
+            let x = (0..6).find(|x| structure.blocks[*x as usize] >= 0).unwrap();
+            (x, *structure.origin().x())
+        };
+        assert_eq!(anchored(Anchor::Min), (0, 100));
+        assert_eq!(anchored(Anchor::Center), (2, 98));
+        assert_eq!(anchored(Anchor::Max), (4, 96));
+    }
+
+    #[test]
+    fn resize_discards_what_no_longer_fits() {
+        let mut structure = MCStructure::new(Vec3::new(3, 1, 1));
+        structure.setblock(Vec3::new(2, 0, 0), stone()).unwrap();
+        structure.add_entity(entity_at(2.5, 0.0, 0.5));
+        structure.add_entity(entity_at(0.5, 0.0, 0.5));
+        structure
+            .resize(
+                Vec3::new(2, 1, 1),
+                Vec3::new(Anchor::Min, Anchor::Min, Anchor::Min),
+            )
+            .unwrap();
+
+        assert_eq!(structure.size(), Vec3::new(2, 1, 1));
+        assert_eq!(structure.blocks, vec![-1, -1]);
+        assert_eq!(structure.entities(), &[entity_at(0.5, 0.0, 0.5)]);
+    }
+
+    #[test]
+    fn resize_rejects_invalid_sizes() {
+        let mut structure = MCStructure::new(Vec3::new(1, 1, 1));
+        let anchor = Vec3::new(Anchor::Min, Anchor::Min, Anchor::Min);
+        assert!(matches!(
+            structure.resize(Vec3::new(-1, 1, 1), anchor),
+            Err(Error::InvalidSize(_))
+        ));
+        assert!(matches!(
+            structure.resize(Vec3::new(i32::MAX, i32::MAX, 2), anchor),
+            Err(Error::InvalidSize(_))
+        ));
+        assert_eq!(structure.size(), Vec3::new(1, 1, 1));
+    }
+
+    #[test]
+    fn crop_moves_the_origin() {
+        let mut structure = MCStructure::new(Vec3::new(4, 4, 4));
+        structure.setblock(Vec3::new(2, 3, 1), stone()).unwrap();
+        structure
+            .crop(Vec3::new(1, 2, 1), Vec3::new(2, 3, 3))
+            .unwrap();
+
+        assert_eq!(structure.size(), Vec3::new(2, 2, 3));
+        assert_eq!(structure.origin(), Vec3::new(1, 2, 1));
+        assert_eq!(structure.getblock(Vec3::new(1, 1, 0)), stone());
+        assert!(matches!(
+            structure.crop(Vec3::new(1, 0, 0), Vec3::new(0, 0, 0)),
+            Err(Error::InvalidRegion { .. })
+        ));
+    }
+
+    #[test]
+    fn trim_to_bounding_box() {
+        let mut structure = MCStructure::new(Vec3::new(5, 5, 5));
+        assert_eq!(structure.bounding_box(false), None);
+        structure
+            .setblock(Vec3::new(0, 0, 0), BlockType::new("minecraft:air"))
+            .unwrap();
+        structure.setblock(Vec3::new(1, 2, 3), stone()).unwrap();
+        structure.setblock(Vec3::new(3, 2, 1), stone()).unwrap();
+
+        assert_eq!(
+            structure.bounding_box(false),
+            Some((Vec3::new(0, 0, 0), Vec3::new(3, 2, 3)))
+        );
+        structure.trim(true);
+        assert_eq!(structure.size(), Vec3::new(3, 1, 3));
+        assert_eq!(structure.origin(), Vec3::new(1, 2, 1));
+        assert_eq!(structure.getblock(Vec3::new(0, 0, 2)), stone());
+        assert_eq!(structure.getblock(Vec3::new(2, 0, 0)), stone());
+    }
+}
@@ -0,0 +1,644 @@
+use std::collections::HashMap;
+
+use crate::{
+    convert::ConversionReport,
+    error::Error,
+    nbt::{TagData, TagKind, TagList},
+    structure::{
+        MCStructure, index_from_vec3,
+        java::{
+            ExportPalette, ImportPalette, JAVA_DATA_VERSION, bedrock_entity, double_list,
+            export_block_entity, import_java_block_entity, java_block_string, java_chest_partner,
+            java_chest_type, java_entity_nbt, pair_java_chests, read_doubles, read_java_nbt,
+            write_java_nbt,
+        },
+        resize::check_size,
+    },
+    types::{BlockType, Vec3},
+};
+
+/// Version of the Sponge schematic format written by `MCStructure::to_schem`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SchemVersion {
+    V2,
+    V3,
+}
+
+fn invalid(reason: String) -> Error {
+    Error::InvalidStructure(reason)
+}
+
+fn read_varints(bytes: &[i8], count: usize) -> Result<Vec<usize>, Error> {
+    // every block takes at least one byte
+    if bytes.len() < count {
+        return Err(invalid(format!(
+            "expected {} blocks, found at most {}",
+            count,
+            bytes.len()
+        )));
+    }
+    let mut values = Vec::with_capacity(count);
+    let mut bytes = bytes.iter().map(|byte| *byte as u8);
+    while let Some(mut byte) = bytes.next() {
+        let mut value = 0usize;
+        let mut shift = 0;
+        while byte & 0x80 != 0 {
+            value |= ((byte & 0x7f) as usize) << shift;
+            shift += 7;
+            if shift > 28 {
+                return Err(invalid("block data varint is too long".to_string()));
+            }
+            byte = bytes
+                .next()
+                .ok_or_else(|| invalid("block data ends in the middle of a varint".to_string()))?;
+        }
+        values.push(value | (byte as usize) << shift);
+    }
+    if values.len() != count {
+        return Err(invalid(format!(
+            "expected {} blocks, found {}",
+            count,
+            values.len()
+        )));
+    }
+    Ok(values)
+}
+
+fn write_varint(bytes: &mut Vec<i8>, mut value: usize) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f | 0x80) as i8);
+        value >>= 7;
+    }
+    bytes.push(value as i8);
+}
+
+fn read_dimension(schematic: &TagData, id: &str) -> Result<i32, Error> {
+    match schematic.find_tag(id) {
+        Some(TagData::Short(value)) => Ok(*value as u16 as i32),
+        _ => Err(invalid(format!("missing '{}'", id))),
+    }
+}
+
+fn read_int_array(data: Option<&TagData>) -> Option<Vec3<i32>> {
+    match data? {
+        TagData::IntArray(_, values) => match values[..] {
+            [x, y, z] => Some(Vec3::new(x, y, z)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn int_array(loc: Vec3<i32>) -> TagData {
+    TagData::IntArray(3, vec![*loc.x(), *loc.y(), *loc.z()])
+}
+
+/// Java palette, ordered by id, from a `Palette` of block strings
+fn read_palette(palette: &TagList) -> Result<Vec<BlockType>, Error> {
+    let mut entries = vec![];
+    for (block, id) in palette.iter() {
+        let TagData::Int(id) = id else {
+            return Err(invalid(format!("palette id of '{}' is not an int", block)));
+        };
+        entries.push((*id, block.parse::<BlockType>()?));
+    }
+    entries.sort_by_key(|(id, _)| *id);
+    if let Some((id, _)) = entries
+        .iter()
+        .enumerate()
+        .find(|(index, (id, _))| *id != *index as i32)
+        .map(|(_, entry)| entry)
+    {
+        return Err(invalid(format!("palette id {} is out of order", id)));
+    }
+    Ok(entries.into_iter().map(|(_, block)| block).collect())
+}
+
+/// Java block entity, with `id` and without position, from a schematic one. Version 3 nests
+/// the block entity's tags in `Data`, earlier versions keep them next to `Pos` and `Id`.
+fn java_block_entity(block_entity: &TagData, version: i32) -> Option<TagData> {
+    let Some(TagData::String(id)) = block_entity.find_tag("Id") else {
+        return None;
+    };
+    let mut java = TagList::from(vec![("id".to_string(), TagData::String(id.clone()))]);
+    let tags = if version >= 3 {
+        match block_entity.find_tag("Data") {
+            Some(TagData::Compound(data)) => data.clone(),
+            _ => TagList::new(),
+        }
+    } else {
+        match block_entity {
+            TagData::Compound(data) => data.clone(),
+            _ => TagList::new(),
+        }
+    };
+    for (name, tag) in tags {
+        if !matches!(name.as_str(), "id" | "Id" | "Pos") {
+            java.add_tag(&name, tag);
+        }
+    }
+    Some(TagData::Compound(java))
+}
+
+fn import_entity(entity: &TagData, version: i32) -> Option<TagData> {
+    let Some(TagData::String(id)) = entity.find_tag("Id") else {
+        return None;
+    };
+    let pos = read_doubles(entity.find_tag("Pos")?)?;
+    let rotation = if version >= 3 {
+        entity
+            .find_tag("Data")
+            .and_then(|data| data.find_tag("Rotation"))
+    } else {
+        entity.find_tag("Rotation")
+    };
+    Some(bedrock_entity(id, pos, rotation))
+}
+
+/// Schematic entity or block entity `{Pos, Id}` holding the rest of `tags`
+fn schematic_entry(pos: TagData, mut tags: TagList, version: SchemVersion) -> Option<TagData> {
+    let Some(TagData::String(id)) = tags.remove_tag("id") else {
+        return None;
+    };
+    let mut entry = TagList::from(vec![
+        ("Pos".to_string(), pos),
+        ("Id".to_string(), TagData::String(id)),
+    ]);
+    match version {
+        SchemVersion::V2 => {
+            for (name, tag) in tags {
+                entry.add_tag(&name, tag);
+            }
+        }
+        SchemVersion::V3 => entry.add_tag("Data", TagData::Compound(tags)),
+    }
+    Some(TagData::Compound(entry))
+}
+
+fn compound_list(list: Vec<TagData>) -> TagData {
+    TagData::List(TagKind::Compound, list.len() as u32, list)
+}
+
+impl MCStructure {
+    /// Reads a Sponge schematic (`.schem`), as saved by WorldEdit, of version 1, 2 or 3.
+    /// Blocks, block entities and entities are converted like in `from_java_structure`.
+    /// `Offset` becomes the origin and `Metadata` is kept as an extra tag for `to_schem`.
+    pub fn from_schem(bytes: &[u8]) -> Result<(MCStructure, ConversionReport), Error> {
+        let mut schematic = read_java_nbt(bytes)?;
+        // version 3 wraps the schematic in an unnamed root compound
+        if let Some(inner @ TagData::Compound(_)) = schematic.remove_tag("Schematic") {
+            schematic = inner;
+        }
+        let version = match schematic.find_tag("Version") {
+            Some(TagData::Int(version @ 1..=3)) => *version,
+            Some(TagData::Int(version)) => {
+                return Err(invalid(format!(
+                    "unsupported schematic version {}",
+                    version
+                )));
+            }
+            _ => return Err(invalid("missing 'Version'".to_string())),
+        };
+        let size = Vec3::new(
+            read_dimension(&schematic, "Width")?,
+            read_dimension(&schematic, "Height")?,
+            read_dimension(&schematic, "Length")?,
+        );
+        check_size(size)?;
+
+        let (blocks, block_entities_id) = if version >= 3 {
+            match schematic.find_tag("Blocks") {
+                Some(blocks @ TagData::Compound(_)) => (blocks, "BlockEntities"),
+                _ => return Err(invalid("missing 'Blocks'".to_string())),
+            }
+        } else if schematic.find_tag("BlockEntities").is_some() {
+            (&schematic, "BlockEntities")
+        } else {
+            // version 1 calls them tile entities
+            (&schematic, "TileEntities")
+        };
+        let data_id = if version >= 3 { "Data" } else { "BlockData" };
+        let Some(TagData::Compound(palette)) = blocks.find_tag("Palette") else {
+            return Err(invalid("missing 'Palette'".to_string()));
+        };
+        let mut palette = ImportPalette::new(read_palette(palette)?);
+        let Some(TagData::ByteArray(_, data)) = blocks.find_tag(data_id) else {
+            return Err(invalid(format!("missing '{}'", data_id)));
+        };
+
+        let (width, length) = (*size.x() as usize, *size.z() as usize);
+        // the block data is checked against the size before it is allocated
+        let states = read_varints(data, (size.x() * size.y() * size.z()) as usize)?;
+        let mut structure = MCStructure::new(size);
+        let mut report = ConversionReport::default();
+        if let Some(offset) = read_int_array(schematic.find_tag("Offset")) {
+            structure.origin = offset;
+        }
+        if let Some(metadata @ TagData::Compound(_)) = schematic.find_tag("Metadata") {
+            structure.extra_tags.set_tag("Metadata", metadata.clone());
+        }
+        let mut chests = vec![];
+        for (i, state) in states.into_iter().enumerate() {
+            // schematics are ordered by y, then z, then x
+            let loc = Vec3::new(
+                (i % width) as i32,
+                (i / (width * length)) as i32,
+                (i / width % length) as i32,
+            );
+            let Some(palette_block) = palette.palette_index(state, &mut structure, &mut report)
+            else {
+                return Err(invalid(format!(
+                    "block state {} is not in the palette",
+                    state
+                )));
+            };
+            structure.blocks[index_from_vec3(loc, size)] = palette_block;
+            if let Some(partner) = palette.java(state).and_then(java_chest_partner) {
+                chests.push((loc, partner));
+            }
+        }
+
+        if let Some(TagData::List(_, _, block_entities)) = blocks.find_tag(block_entities_id) {
+            for block_entity in block_entities {
+                let Some(loc) = read_int_array(block_entity.find_tag("Pos")) else {
+                    return Err(invalid("block entity without 'Pos'".to_string()));
+                };
+                if !structure.contains(loc) {
+                    return Err(invalid(format!(
+                        "block entity position ({}, {}, {}) is out of bounds",
+                        loc.x(),
+                        loc.y(),
+                        loc.z()
+                    )));
+                }
+                if let Some(java) = java_block_entity(block_entity, version)
+                    && let Some(data) = import_java_block_entity(&java, loc, &mut report)
+                {
+                    let index = index_from_vec3(loc, size) as u32;
+                    structure.block_position_data.insert(index, data);
+                }
+            }
+        }
+        pair_java_chests(&mut structure, chests);
+
+        if let Some(TagData::List(_, _, entities)) = schematic.find_tag("Entities") {
+            structure.entities = entities
+                .iter()
+                .filter_map(|entity| import_entity(entity, version))
+                .collect();
+        }
+        Ok((structure, report))
+    }
+
+    /// Writes the structure as a Sponge schematic (`.schem`) of the given version, the
+    /// reverse of `from_schem`. Schematics have no structure void, so it is written as air.
+    pub fn to_schem(&self, version: SchemVersion) -> (Vec<u8>, ConversionReport) {
+        let mut report = ConversionReport::default();
+        let export_palette = ExportPalette::new(&self.palette);
+        let mut palette: HashMap<String, usize> = HashMap::from([("minecraft:air".to_string(), 0)]);
+        let mut data = vec![];
+        let mut block_entities = vec![];
+        let (width, height, length) = (*self.size.x(), *self.size.y(), *self.size.z());
+        for y in 0..height {
+            for z in 0..length {
+                for x in 0..width {
+                    let loc = Vec3::new(x, y, z);
+                    let index = index_from_vec3(loc, self.size);
+                    let block = self.blocks[index];
+                    if block < 0 {
+                        write_varint(&mut data, 0);
+                        continue;
+                    }
+                    let block_entity = self.block_position_data.get(&(index as u32));
+                    let java = java_chest_type(
+                        &export_palette.java(block as usize, &mut report),
+                        block_entity,
+                    );
+                    let next = palette.len();
+                    write_varint(
+                        &mut data,
+                        *palette.entry(java_block_string(&java)).or_insert(next),
+                    );
+                    if let Some(TagData::Compound(java)) =
+                        block_entity.and_then(|data| export_block_entity(data, &mut report))
+                        && let Some(entry) = schematic_entry(int_array(loc), java, version)
+                    {
+                        block_entities.push(entry);
+                    }
+                }
+            }
+        }
+        let entities = self
+            .entities
+            .iter()
+            .filter_map(|entity| {
+                let (pos, nbt) = java_entity_nbt(entity)?;
+                schematic_entry(double_list(&pos), nbt, version)
+            })
+            .collect();
+
+        let mut palette: Vec<(String, usize)> = palette.into_iter().collect();
+        palette.sort_by_key(|(_, id)| *id);
+        let palette_max = palette.len() as i32;
+        let palette = TagData::Compound(TagList::from(
+            palette
+                .into_iter()
+                .map(|(block, id)| (block, TagData::Int(id as i32)))
+                .collect(),
+        ));
+        let data = TagData::ByteArray(data.len() as i32, data);
+
+        let mut schematic = TagList::from(vec![
+            (
+                "Version".to_string(),
+                TagData::Int(match version {
+                    SchemVersion::V2 => 2,
+                    SchemVersion::V3 => 3,
+                }),
+            ),
+            ("DataVersion".to_string(), TagData::Int(JAVA_DATA_VERSION)),
+            ("Width".to_string(), TagData::Short(width as i16)),
+            ("Height".to_string(), TagData::Short(height as i16)),
+            ("Length".to_string(), TagData::Short(length as i16)),
+            ("Offset".to_string(), int_array(self.origin)),
+        ]);
+        if let Some(metadata @ TagData::Compound(_)) = self.extra_tags.find_tag("Metadata") {
+            schematic.add_tag("Metadata", metadata.clone());
+        }
+        match version {
+            SchemVersion::V2 => {
+                schematic.add_tag("PaletteMax", TagData::Int(palette_max));
+                schematic.add_tag("Palette", palette);
+                schematic.add_tag("BlockData", data);
+                schematic.add_tag("BlockEntities", compound_list(block_entities));
+                schematic.add_tag("Entities", compound_list(entities));
+                (
+                    write_java_nbt("Schematic", TagData::Compound(schematic)),
+                    report,
+                )
+            }
+            SchemVersion::V3 => {
+                schematic.add_tag(
+                    "Blocks",
+                    TagData::Compound(TagList::from(vec![
+                        ("Palette".to_string(), palette),
+                        ("Data".to_string(), data),
+                        ("BlockEntities".to_string(), compound_list(block_entities)),
+                    ])),
+                );
+                schematic.add_tag("Entities", compound_list(entities));
+                let root = TagList::from(vec![(
+                    "Schematic".to_string(),
+                    TagData::Compound(schematic),
+                )]);
+                (write_java_nbt("", TagData::Compound(root)), report)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt::NbtTree;
+
+    fn block(input: &str) -> BlockType {
+        input.parse().unwrap()
+    }
+
+    fn float_list(values: &[f32]) -> TagData {
+        TagData::List(
+            TagKind::Float,
+            values.len() as u32,
+            values.iter().map(|value| TagData::Float(*value)).collect(),
+        )
+    }
+
+    fn metadata() -> TagData {
+        TagData::Compound(TagList::from(vec![(
+            "Author".to_string(),
+            TagData::String("someone".to_string()),
+        )]))
+    }
+
+    /// A schematic 3 wide and 2 long, with a chest and a pig
+    fn schem_file(version: i32) -> Vec<u8> {
+        let palette = TagData::Compound(TagList::from(vec![
+            ("minecraft:air".to_string(), TagData::Int(0)),
+            ("minecraft:stone".to_string(), TagData::Int(1)),
+            (
+                "minecraft:oak_stairs[facing=north,half=top]".to_string(),
+                TagData::Int(2),
+            ),
+            (
+                "minecraft:chest[facing=west,type=single,waterlogged=false]".to_string(),
+                TagData::Int(3),
+            ),
+        ]));
+        let data = TagData::ByteArray(6, vec![1, 0, 2, 3, 1, 0]);
+        let items = ("Items".to_string(), compound_list(vec![]));
+        let mut chest = TagList::from(vec![
+            ("Pos".to_string(), int_array(Vec3::new(0, 0, 1))),
+            (
+                "Id".to_string(),
+                TagData::String("minecraft:chest".to_string()),
+            ),
+        ]);
+        let rotation = ("Rotation".to_string(), float_list(&[90.0, 0.0]));
+        let mut pig = TagList::from(vec![
+            ("Pos".to_string(), double_list(&[0.5, 1.0, 1.5])),
+            (
+                "Id".to_string(),
+                TagData::String("minecraft:pig".to_string()),
+            ),
+        ]);
+        let mut schematic = TagList::from(vec![
+            ("Version".to_string(), TagData::Int(version)),
+            ("DataVersion".to_string(), TagData::Int(JAVA_DATA_VERSION)),
+            ("Width".to_string(), TagData::Short(3)),
+            ("Height".to_string(), TagData::Short(1)),
+            ("Length".to_string(), TagData::Short(2)),
+            ("Offset".to_string(), int_array(Vec3::new(1, 2, 3))),
+            ("Metadata".to_string(), metadata()),
+        ]);
+        if version >= 3 {
+            chest.add_tag("Data", TagData::Compound(TagList::from(vec![items])));
+            pig.add_tag("Data", TagData::Compound(TagList::from(vec![rotation])));
+            schematic.add_tag(
+                "Blocks",
+                TagData::Compound(TagList::from(vec![
+                    ("Palette".to_string(), palette),
+                    ("Data".to_string(), data),
+                    (
+                        "BlockEntities".to_string(),
+                        compound_list(vec![TagData::Compound(chest)]),
+                    ),
+                ])),
+            );
+        } else {
+            chest.add_tag(&items.0, items.1);
+            pig.add_tag(&rotation.0, rotation.1);
+            schematic.add_tag("PaletteMax", TagData::Int(4));
+            schematic.add_tag("Palette", palette);
+            schematic.add_tag("BlockData", data);
+            schematic.add_tag(
+                "BlockEntities",
+                compound_list(vec![TagData::Compound(chest)]),
+            );
+        }
+        schematic.add_tag("Entities", compound_list(vec![TagData::Compound(pig)]));
+        if version >= 3 {
+            schematic = TagList::from(vec![(
+                "Schematic".to_string(),
+                TagData::Compound(schematic),
+            )]);
+        }
+        write_java_nbt("", TagData::Compound(schematic))
+    }
+
+    fn type_id(structure: &MCStructure, loc: Vec3<i32>) -> &str {
+        let block = structure.blocks[index_from_vec3(loc, structure.size)];
+        &structure.palette[block as usize].type_id
+    }
+
+    #[test]
+    fn varints() {
+        let values = [0, 1, 127, 128, 300, 16383, 16384, 1 << 21];
+        let mut bytes = vec![];
+        for value in values {
+            write_varint(&mut bytes, value);
+        }
+        assert_eq!(bytes.len(), 1 + 1 + 1 + 2 + 2 + 2 + 3 + 4);
+        assert_eq!(read_varints(&bytes, values.len()), Ok(values.to_vec()));
+        assert!(read_varints(&bytes, values.len() + 1).is_err());
+        assert!(read_varints(&bytes[..bytes.len() - 1], values.len()).is_err());
+        assert_eq!(
+            read_varints(&[-1, -1, -1, -1, -1, 1], 1),
+            Err(invalid("block data varint is too long".to_string()))
+        );
+    }
+
+    #[test]
+    fn from_schem() {
+        for version in [2, 3] {
+            let (structure, report) = MCStructure::from_schem(&schem_file(version)).unwrap();
+            assert!(report.is_empty(), "{}", report);
+            assert_eq!(structure.size, Vec3::new(3, 1, 2));
+            assert_eq!(structure.origin, Vec3::new(1, 2, 3));
+            assert_eq!(structure.extra_tags.find_tag("Metadata"), Some(&metadata()));
+            assert_eq!(type_id(&structure, Vec3::new(0, 0, 0)), "minecraft:stone");
+            assert_eq!(type_id(&structure, Vec3::new(1, 0, 0)), "minecraft:air");
+            assert_eq!(
+                structure.getblock(Vec3::new(2, 0, 0)),
+                block(r#"oak_stairs["weirdo_direction"=3,"upside_down_bit"=true]"#)
+            );
+            assert_eq!(
+                structure.getblock(Vec3::new(0, 0, 1)),
+                block(r#"chest["minecraft:cardinal_direction"="west"]"#)
+            );
+            assert_eq!(type_id(&structure, Vec3::new(1, 0, 1)), "minecraft:stone");
+
+            let chest = &structure.block_position_data
+                [&(index_from_vec3(Vec3::new(0, 0, 1), structure.size) as u32)];
+            assert_eq!(
+                chest.find_tag("id"),
+                Some(&TagData::String("Chest".to_string()))
+            );
+            assert_eq!(chest.find_tag("z"), Some(&TagData::Int(1)));
+            assert_eq!(
+                structure.entities,
+                vec![bedrock_entity(
+                    "minecraft:pig",
+                    [0.5, 1.0, 1.5],
+                    Some(&float_list(&[90.0, 0.0]))
+                )]
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let (structure, _) = MCStructure::from_schem(&schem_file(3)).unwrap();
+        for version in [SchemVersion::V2, SchemVersion::V3] {
+            let (bytes, report) = structure.to_schem(version);
+            assert!(report.is_empty());
+            let (read, report) = MCStructure::from_schem(&bytes).unwrap();
+            assert!(report.is_empty());
+            assert_eq!(read.size, structure.size);
+            assert_eq!(read.origin, structure.origin);
+            assert_eq!(read.extra_tags, structure.extra_tags);
+            for x in 0..3 {
+                for z in 0..2 {
+                    let loc = Vec3::new(x, 0, z);
+                    assert_eq!(read.getblock(loc), structure.getblock(loc));
+                }
+            }
+            assert_eq!(read.block_position_data, structure.block_position_data);
+            assert_eq!(read.entities, structure.entities);
+        }
+    }
+
+    #[test]
+    fn structure_void_is_written_as_air() {
+        let mut structure = MCStructure::new(Vec3::new(2, 1, 1));
+        structure
+            .setblock(Vec3::new(1, 0, 0), block("minecraft:stone"))
+            .unwrap();
+        let (bytes, _) = structure.to_schem(SchemVersion::V2);
+        let root = read_java_nbt(&bytes).unwrap();
+        assert_eq!(
+            root.find_tag("BlockData"),
+            Some(&TagData::ByteArray(2, vec![0, 1]))
+        );
+        let (read, _) = MCStructure::from_schem(&bytes).unwrap();
+        assert_eq!(type_id(&read, Vec3::new(0, 0, 0)), "minecraft:air");
+    }
+
+    #[test]
+    fn invalid_schematics() {
+        let error = |edit: &dyn Fn(&mut TagData)| {
+            let bytes = schem_file(2);
+            let mut root = read_java_nbt(&bytes).unwrap();
+            edit(&mut root);
+            let bytes = NbtTree::new(vec![(String::new(), root)]).as_bytes(false);
+            MCStructure::from_schem(&bytes).err()
+        };
+        assert_eq!(
+            error(&|root| root.set_tag("Version", TagData::Int(4))),
+            Some(invalid("unsupported schematic version 4".to_string()))
+        );
+        assert_eq!(
+            error(&|root| {
+                root.remove_tag("Width");
+            }),
+            Some(invalid("missing 'Width'".to_string()))
+        );
+        assert_eq!(
+            error(&|root| root.set_tag("BlockData", TagData::ByteArray(5, vec![0; 5]))),
+            Some(invalid("expected 6 blocks, found at most 5".to_string()))
+        );
+        assert_eq!(
+            error(&|root| root.set_tag("BlockData", TagData::ByteArray(6, vec![4; 6]))),
+            Some(invalid("block state 4 is not in the palette".to_string()))
+        );
+        assert_eq!(
+            error(&|root| root
+                .get_tag("Palette")
+                .unwrap()
+                .set_tag("minecraft:stone", TagData::Int(7))),
+            Some(invalid("palette id 2 is out of order".to_string()))
+        );
+        assert_eq!(
+            error(&|root| {
+                let Some(TagData::List(_, _, block_entities)) = root.get_tag("BlockEntities")
+                else {
+                    unreachable!()
+                };
+                block_entities[0].set_tag("Pos", int_array(Vec3::new(0, 0, 2)));
+            }),
+            Some(invalid(
+                "block entity position (0, 0, 2) is out of bounds".to_string()
+            ))
+        );
+    }
+}
@@ -0,0 +1,378 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::{
+    convert::{ConversionReport, legacy_block_entity_id, legacy_to_java},
+    error::Error,
+    nbt::TagData,
+    structure::{
+        MCStructure, index_from_vec3,
+        java::{ImportPalette, import_java_block_entity, pair_java_chests, read_java_nbt},
+        resize::check_size,
+    },
+    types::{BlockState, BlockType, Cardinal, Vec3},
+};
+
+fn invalid(reason: String) -> Error {
+    Error::InvalidStructure(reason)
+}
+
+fn read_dimension(schematic: &TagData, id: &str) -> Result<i32, Error> {
+    match schematic.find_tag(id) {
+        Some(TagData::Short(value)) => Ok(*value as u16 as i32),
+        _ => Err(invalid(format!("missing '{}'", id))),
+    }
+}
+
+fn read_bytes<'a>(schematic: &'a TagData, id: &str, len: usize) -> Result<&'a [i8], Error> {
+    match schematic.find_tag(id) {
+        Some(TagData::ByteArray(_, bytes)) if bytes.len() >= len => Ok(bytes),
+        Some(TagData::ByteArray(_, bytes)) => Err(invalid(format!(
+            "expected {} bytes in '{}', found {}",
+            len,
+            id,
+            bytes.len()
+        ))),
+        _ => Err(invalid(format!("missing '{}'", id))),
+    }
+}
+
+/// Facing of a legacy chest, which decides which neighbours it can pair with
+fn chest_facing(java: &BlockType) -> Option<Cardinal> {
+    if !matches!(
+        java.type_id.as_str(),
+        "minecraft:chest" | "minecraft:trapped_chest"
+    ) {
+        return None;
+    }
+    java.states.iter().find_map(|(state, value)| match value {
+        BlockState::String(value) if state == "facing" => Cardinal::from_name(value),
+        _ => None,
+    })
+}
+
+impl MCStructure {
+    /// Reads an MCEdit schematic (`.schematic`), the format of WorldEdit and MCEdit before
+    /// 1.13. Blocks are converted with `legacy_to_java` and then like in
+    /// `from_java_structure`; blocks without a Java counterpart are left as structure void
+    /// and reported as `id:data`. Chests next to a chest of the same kind and facing are
+    /// paired, as they were in Java. Entities are not imported.
+    pub fn from_schematic(bytes: &[u8]) -> Result<(MCStructure, ConversionReport), Error> {
+        let schematic = read_java_nbt(bytes)?;
+        if let Some(TagData::String(materials)) = schematic.find_tag("Materials")
+            && materials != "Alpha"
+        {
+            return Err(invalid(format!(
+                "unsupported schematic materials '{}'",
+                materials
+            )));
+        }
+        let size = Vec3::new(
+            read_dimension(&schematic, "Width")?,
+            read_dimension(&schematic, "Height")?,
+            read_dimension(&schematic, "Length")?,
+        );
+        check_size(size)?;
+        // the block arrays are checked against the size before it is allocated
+        let count = (size.x() * size.y() * size.z()) as usize;
+        let blocks = read_bytes(&schematic, "Blocks", count)?;
+        let data = read_bytes(&schematic, "Data", count)?;
+        // ids above 255 keep their upper four bits in `AddBlocks`, two blocks to a byte
+        let add_blocks = match schematic.find_tag("AddBlocks") {
+            Some(_) => Some(read_bytes(&schematic, "AddBlocks", count.div_ceil(2))?),
+            None => None,
+        };
+        let mut structure = MCStructure::new(size);
+        let mut report = ConversionReport::default();
+        if let (Some(TagData::Int(x)), Some(TagData::Int(y)), Some(TagData::Int(z))) = (
+            schematic.find_tag("WEOriginX"),
+            schematic.find_tag("WEOriginY"),
+            schematic.find_tag("WEOriginZ"),
+        ) {
+            structure.origin = Vec3::new(*x, *y, *z);
+        }
+
+        let legacy_block = |i: usize| {
+            let add = add_blocks.map_or(0, |add| {
+                let add = add[i / 2] as u8;
+                if i.is_multiple_of(2) {
+                    add >> 4
+                } else {
+                    add & 15
+                }
+            });
+            (
+                (add as u16) << 8 | blocks[i] as u8 as u16,
+                data[i] as u8 & 15,
+            )
+        };
+        let (width, length) = (*size.x() as usize, *size.z() as usize);
+        let mut java = vec![];
+        let mut java_states: HashMap<(u16, u8), Option<usize>> = HashMap::new();
+        let mut states = Vec::with_capacity(count);
+        for i in 0..count {
+            let (id, mut data) = legacy_block(i);
+            // the upper half of a tall plant takes its kind from the lower half
+            if id == 175 && data & 8 != 0 && i >= width * length {
+                let (below, below_data) = legacy_block(i - width * length);
+                if below == 175 {
+                    data = 8 | below_data & 7;
+                }
+            }
+            let state = *java_states.entry((id, data)).or_insert_with(|| {
+                legacy_to_java(id, data).map(|block| {
+                    java.push(block);
+                    java.len() - 1
+                })
+            });
+            if state.is_none() {
+                report.add_legacy_block(id, data);
+            }
+            states.push(state);
+        }
+
+        let mut palette = ImportPalette::new(java);
+        let mut chests = BTreeMap::new();
+        for (i, state) in states.into_iter().enumerate() {
+            let Some(state) = state else {
+                continue;
+            };
+            // schematics are ordered by y, then z, then x
+            let loc = Vec3::new(
+                (i % width) as i32,
+                (i / (width * length)) as i32,
+                (i / width % length) as i32,
+            );
+            if let Some(palette_block) = palette.palette_index(state, &mut structure, &mut report) {
+                structure.blocks[index_from_vec3(loc, size)] = palette_block;
+            }
+            if let Some(chest) = palette.java(state)
+                && let Some(facing) = chest_facing(chest)
+            {
+                chests.insert(index_from_vec3(loc, size), (loc, state, facing));
+            }
+        }
+
+        // a chest between two others pairs with the first one found
+        let mut pairs = vec![];
+        let mut paired = HashSet::new();
+        for (index, (loc, state, facing)) in &chests {
+            let left = facing.clockwise().offset();
+            if structure.contains(*loc + left)
+                && let partner = index_from_vec3(*loc + left, size)
+                && let Some((_, partner_state, _)) = chests.get(&partner)
+                && partner_state == state
+                && !paired.contains(index)
+                && !paired.contains(&partner)
+            {
+                paired.extend([*index, partner]);
+                pairs.push((*loc, (left, true)));
+                pairs.push((*loc + left, (facing.clockwise().opposite().offset(), false)));
+            }
+        }
+
+        if let Some(TagData::List(_, _, tile_entities)) = schematic.find_tag("TileEntities") {
+            for tile_entity in tile_entities {
+                let (Some(TagData::Int(x)), Some(TagData::Int(y)), Some(TagData::Int(z))) = (
+                    tile_entity.find_tag("x"),
+                    tile_entity.find_tag("y"),
+                    tile_entity.find_tag("z"),
+                ) else {
+                    return Err(invalid("tile entity without a position".to_string()));
+                };
+                let loc = Vec3::new(*x, *y, *z);
+                if !structure.contains(loc) {
+                    return Err(invalid(format!(
+                        "tile entity position ({}, {}, {}) is out of bounds",
+                        loc.x(),
+                        loc.y(),
+                        loc.z()
+                    )));
+                }
+                let mut java = tile_entity.clone();
+                if let Some(TagData::String(id)) = tile_entity.find_tag("id") {
+                    java.set_tag("id", TagData::String(legacy_block_entity_id(id)));
+                }
+                if let Some(data) = import_java_block_entity(&java, loc, &mut report) {
+                    let index = index_from_vec3(loc, size) as u32;
+                    structure.block_position_data.insert(index, data);
+                }
+            }
+        }
+        pair_java_chests(&mut structure, pairs);
+        Ok((structure, report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nbt::{TagKind, TagList},
+        structure::{chest_pair_offset, java::write_java_nbt},
+    };
+
+    fn chest(x: i32) -> TagData {
+        TagData::Compound(TagList::from(vec![
+            ("id".to_string(), TagData::String("Chest".to_string())),
+            ("x".to_string(), TagData::Int(x)),
+            ("y".to_string(), TagData::Int(0)),
+            ("z".to_string(), TagData::Int(0)),
+            (
+                "Items".to_string(),
+                TagData::List(TagKind::Compound, 0, vec![]),
+            ),
+        ]))
+    }
+
+    /// A schematic 3 wide, 2 tall and 1 long: a rose bush, a double chest facing north and
+    /// block 300, which does not exist
+    fn schematic() -> TagList {
+        TagList::from(vec![
+            ("Width".to_string(), TagData::Short(3)),
+            ("Height".to_string(), TagData::Short(2)),
+            ("Length".to_string(), TagData::Short(1)),
+            (
+                "Materials".to_string(),
+                TagData::String("Alpha".to_string()),
+            ),
+            (
+                "Blocks".to_string(),
+                TagData::ByteArray(6, vec![-81, 54, 54, -81, 0, 44]),
+            ),
+            (
+                "Data".to_string(),
+                TagData::ByteArray(6, vec![4, 2, 2, 8, 0, 0]),
+            ),
+            (
+                "AddBlocks".to_string(),
+                TagData::ByteArray(3, vec![0, 0, 1]),
+            ),
+            ("WEOriginX".to_string(), TagData::Int(5)),
+            ("WEOriginY".to_string(), TagData::Int(6)),
+            ("WEOriginZ".to_string(), TagData::Int(7)),
+            (
+                "TileEntities".to_string(),
+                TagData::List(TagKind::Compound, 2, vec![chest(1), chest(2)]),
+            ),
+            (
+                "Entities".to_string(),
+                TagData::List(TagKind::Compound, 0, vec![]),
+            ),
+        ])
+    }
+
+    fn read(schematic: TagList) -> Result<(MCStructure, ConversionReport), Error> {
+        MCStructure::from_schematic(&write_java_nbt("Schematic", TagData::Compound(schematic)))
+    }
+
+    fn block(input: &str) -> BlockType {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn from_schematic() {
+        let (structure, report) = read(schematic()).unwrap();
+        assert_eq!(structure.size, Vec3::new(3, 2, 1));
+        assert_eq!(structure.origin, Vec3::new(5, 6, 7));
+        assert_eq!(
+            structure.getblock(Vec3::new(0, 0, 0)),
+            block(r#"rose_bush["upper_block_bit"=false]"#)
+        );
+        assert_eq!(
+            structure.getblock(Vec3::new(0, 1, 0)),
+            block(r#"rose_bush["upper_block_bit"=true]"#)
+        );
+        assert_eq!(
+            structure.getblock(Vec3::new(1, 0, 0)),
+            block(r#"chest["minecraft:cardinal_direction"="north"]"#)
+        );
+        assert_eq!(
+            structure.getblock(Vec3::new(1, 1, 0)).type_id,
+            "minecraft:air"
+        );
+        assert_eq!(
+            structure.blocks[index_from_vec3(Vec3::new(2, 1, 0), structure.size)],
+            -1
+        );
+        assert_eq!(report.unmapped_blocks, [("300:0".to_string(), 1)].into());
+        assert!(report.unmapped_block_entities.is_empty());
+    }
+
+    #[test]
+    fn neighbouring_chests_are_paired() {
+        let (structure, _) = read(schematic()).unwrap();
+        let chest = |x: i32| {
+            &structure.block_position_data
+                [&(index_from_vec3(Vec3::new(x, 0, 0), structure.size) as u32)]
+        };
+        assert_eq!(
+            chest(1).find_tag("id"),
+            Some(&TagData::String("Chest".to_string()))
+        );
+        assert_eq!(chest_pair_offset(chest(1)), Some(Vec3::new(1, 0, 0)));
+        assert_eq!(chest(1).find_tag("pairlead"), Some(&TagData::Byte(1)));
+        assert_eq!(chest_pair_offset(chest(2)), Some(Vec3::new(-1, 0, 0)));
+        assert_eq!(chest(2).find_tag("pairlead"), Some(&TagData::Byte(0)));
+
+        // chests facing other ways stay single
+        let mut schematic = schematic();
+        schematic.set_tag("Data", TagData::ByteArray(6, vec![4, 2, 3, 8, 0, 0]));
+        let (structure, _) = read(schematic).unwrap();
+        for data in structure.block_position_data.values() {
+            assert_eq!(chest_pair_offset(data), None);
+        }
+    }
+
+    #[test]
+    fn upper_plant_halves_follow_the_lower_half() {
+        let mut schematic = schematic();
+        schematic.set_tag("Data", TagData::ByteArray(6, vec![1, 2, 2, 10, 0, 0]));
+        let (structure, _) = read(schematic).unwrap();
+        assert_eq!(
+            structure.getblock(Vec3::new(0, 1, 0)),
+            block(r#"lilac["upper_block_bit"=true]"#)
+        );
+    }
+
+    #[test]
+    fn invalid_schematics() {
+        let error = |edit: &dyn Fn(&mut TagList)| {
+            let mut schematic = schematic();
+            edit(&mut schematic);
+            read(schematic).err()
+        };
+        assert_eq!(
+            error(
+                &|schematic| schematic.set_tag("Materials", TagData::String("Pocket".to_string()))
+            ),
+            Some(invalid(
+                "unsupported schematic materials 'Pocket'".to_string()
+            ))
+        );
+        assert_eq!(
+            error(&|schematic| {
+                schematic.remove_tag("Height");
+            }),
+            Some(invalid("missing 'Height'".to_string()))
+        );
+        assert_eq!(
+            error(&|schematic| schematic.set_tag("Data", TagData::ByteArray(5, vec![0; 5]))),
+            Some(invalid("expected 6 bytes in 'Data', found 5".to_string()))
+        );
+        assert_eq!(
+            error(&|schematic| schematic.set_tag("AddBlocks", TagData::ByteArray(2, vec![0; 2]))),
+            Some(invalid(
+                "expected 3 bytes in 'AddBlocks', found 2".to_string()
+            ))
+        );
+        assert_eq!(
+            error(&|schematic| schematic.set_tag(
+                "TileEntities",
+                TagData::List(TagKind::Compound, 1, vec![chest(3)])
+            )),
+            Some(invalid(
+                "tile entity position (3, 0, 0) is out of bounds".to_string()
+            ))
+        );
+    }
+}
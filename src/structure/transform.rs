@@ -0,0 +1,197 @@
+use crate::{
+    nbt::{TagData, TagKind},
+    structure::{
+        MCStructure, chest_pair_offset, index_from_vec3, set_block_entity_position, set_chest_pair,
+        vec3_from_index,
+    },
+    types::{Axis, Rotation, Transform, Vec3},
+};
+
+impl MCStructure {
+    pub fn rotate(&mut self, rotation: Rotation) {
+        self.transform(Transform::Rotate(rotation));
+    }
+    pub fn mirror(&mut self, axis: Axis) {
+        self.transform(Transform::Mirror(axis));
+    }
+
+    fn transform(&mut self, transform: Transform) {
+        let old_size = self.size;
+        let new_size = transform.size(old_size);
+
+        let new_index = |index: usize| {
+            let loc = transform.block_position(vec3_from_index(index, old_size), old_size);
+            index_from_vec3(loc, new_size)
+        };
+        let mut blocks = vec![-1; self.blocks.len()];
+        let mut blocks_layer2 = vec![-1; self.blocks.len()];
+        for (index, (block, block_layer2)) in
+            self.blocks.iter().zip(&self.blocks_layer2).enumerate()
+        {
+            blocks[new_index(index)] = *block;
+            blocks_layer2[new_index(index)] = *block_layer2;
+        }
+        self.blocks = blocks;
+        self.blocks_layer2 = blocks_layer2;
+        self.extra_position_data = std::mem::take(&mut self.extra_position_data)
+            .into_iter()
+            .map(|(index, extra)| (new_index(index as usize) as u32, extra))
+            .collect();
+        self.size = new_size;
+
+        for block in &mut self.palette {
+            *block = block.transformed(transform);
+        }
+
+        let block_position_data = std::mem::take(&mut self.block_position_data);
+        for (index, mut data) in block_position_data {
+            let old_loc = vec3_from_index(index as usize, old_size);
+            let loc = transform.block_position(old_loc, old_size);
+            let pair_offset = chest_pair_offset(&data);
+            set_block_entity_position(&mut data, loc);
+            if let Some(offset) = pair_offset {
+                set_chest_pair(&mut data, loc.wrapping_add(transform.offset(offset)));
+            }
+            self.block_position_data
+                .insert(index_from_vec3(loc, new_size) as u32, data);
+        }
+
+        for entity in &mut self.entities {
+            if let Some(TagData::List(TagKind::Float, 3, pos)) = entity.get_tag("Pos")
+                && let [TagData::Float(x), TagData::Float(y), TagData::Float(z)] = &mut pos[..]
+            {
+                let loc = transform.point(Vec3::new(*x, *y, *z), old_size);
+                (*x, *y, *z) = (*loc.x(), *loc.y(), *loc.z());
+            }
+            if let Some(TagData::List(TagKind::Float, 2, rotation)) = entity.get_tag("Rotation")
+                && let [TagData::Float(yaw), TagData::Float(pitch)] = &mut rotation[..]
+            {
+                (*yaw, *pitch) = transform.yaw_pitch(*yaw, *pitch);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nbt::TagList,
+        types::{BlockState, BlockType, Cardinal},
+    };
+
+    fn stairs(direction: i32) -> BlockType {
+        BlockType::new("minecraft:oak_stairs")
+            .set_state("weirdo_direction", &BlockState::Int(direction))
+    }
+
+    fn entity_at(x: f32, y: f32, z: f32, yaw: f32) -> TagData {
+        TagData::Compound(TagList::from(vec![
+            (
+                "Pos".to_string(),
+                TagData::List(
+                    TagKind::Float,
+                    3,
+                    vec![TagData::Float(x), TagData::Float(y), TagData::Float(z)],
+                ),
+            ),
+            (
+                "Rotation".to_string(),
+                TagData::List(
+                    TagKind::Float,
+                    2,
+                    vec![TagData::Float(yaw), TagData::Float(0.0)],
+                ),
+            ),
+        ]))
+    }
+
+    fn pos(entity: &TagData) -> Vec<TagData> {
+        match entity.find_tag("Pos") {
+            Some(TagData::List(_, _, pos)) => pos.clone(),
+            other => panic!("expected a Pos list, found {other:?}"),
+        }
+    }
+
+    #[test]
+    fn rotate_moves_blocks_and_states() {
+        let mut structure = MCStructure::new(Vec3::new(2, 1, 3));
+        structure.setblock(Vec3::new(0, 0, 0), stairs(0)).unwrap();
+        structure.setblock(Vec3::new(1, 0, 2), stairs(3)).unwrap();
+        structure.rotate(Rotation::Cw90);
+
+        assert_eq!(structure.size(), Vec3::new(3, 1, 2));
+        assert_eq!(structure.getblock(Vec3::new(2, 0, 0)), stairs(2));
+        assert_eq!(structure.getblock(Vec3::new(0, 0, 1)), stairs(0));
+        assert_eq!(structure.blocks[0], -1);
+    }
+
+    #[test]
+    fn mirror_moves_blocks_and_states() {
+        let mut structure = MCStructure::new(Vec3::new(3, 1, 1));
+        structure.setblock(Vec3::new(0, 0, 0), stairs(0)).unwrap();
+        structure.mirror(Axis::X);
+
+        assert_eq!(structure.size(), Vec3::new(3, 1, 1));
+        assert_eq!(structure.getblock(Vec3::new(2, 0, 0)), stairs(1));
+    }
+
+    #[test]
+    fn rotate_moves_chest_pairs() {
+        let mut structure = MCStructure::new(Vec3::new(2, 1, 2));
+        structure
+            .place_double_chest(Vec3::new(0, 0, 0), Cardinal::North, false)
+            .unwrap();
+        structure.rotate(Rotation::Cw90);
+
+        // the halves at (0, 0, 0) and (1, 0, 0) end up at (1, 0, 0) and (1, 0, 1)
+        let first = &structure.block_position_data
+            [&(index_from_vec3(Vec3::new(1, 0, 0), structure.size) as u32)];
+        let second = &structure.block_position_data
+            [&(index_from_vec3(Vec3::new(1, 0, 1), structure.size) as u32)];
+        assert_eq!(chest_pair_offset(first), Some(Vec3::new(0, 0, 1)));
+        assert_eq!(chest_pair_offset(second), Some(Vec3::new(0, 0, -1)));
+        assert_eq!(structure.find_half_paired_chests(), vec![]);
+    }
+
+    #[test]
+    fn rotate_keeps_damaged_chest_pairs() {
+        let mut structure = MCStructure::new(Vec3::new(1, 1, 1));
+        let mut data = TagData::Compound(TagList::from(vec![
+            ("id".to_string(), TagData::String("Chest".to_string())),
+            ("pairx".to_string(), TagData::Int(i32::MIN)),
+            ("pairz".to_string(), TagData::Int(0)),
+        ]));
+        set_block_entity_position(&mut data, Vec3::new(0, 0, 0));
+        structure.block_position_data.insert(0, data);
+        structure.rotate(Rotation::Cw90);
+
+        let data = &structure.block_position_data[&0];
+        assert_eq!(chest_pair_offset(data), Some(Vec3::new(0, 0, i32::MIN)));
+    }
+
+    #[test]
+    fn rotate_moves_entities() {
+        let mut structure = MCStructure::new(Vec3::new(2, 1, 4));
+        structure.add_entity(entity_at(0.5, 0.0, 1.5, 0.0));
+        structure.rotate(Rotation::Cw90);
+
+        let entity = &structure.entities()[0];
+        assert_eq!(
+            pos(entity),
+            vec![
+                TagData::Float(2.5),
+                TagData::Float(0.0),
+                TagData::Float(0.5)
+            ]
+        );
+        assert_eq!(
+            entity.find_tag("Rotation"),
+            Some(&TagData::List(
+                TagKind::Float,
+                2,
+                vec![TagData::Float(90.0), TagData::Float(0.0)]
+            ))
+        );
+    }
+}
@@ -0,0 +1,594 @@
+use std::collections::HashMap;
+
+use crate::{
+    convert::nearest_block,
+    error::Error,
+    structure::{MCStructure, resize::check_size},
+    types::Vec3,
+};
+
+fn invalid(reason: String) -> Error {
+    Error::InvalidStructure(reason)
+}
+
+/// Deepest scene graph allowed, which also stops cycles
+const MAX_DEPTH: u32 = 64;
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if len > self.bytes.len() {
+            return Err(invalid("unexpected end of file".to_string()));
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+    fn int(&mut self) -> Result<i32, Error> {
+        let bytes = self.take(4)?;
+        Ok(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+    fn len(&mut self) -> Result<usize, Error> {
+        usize::try_from(self.int()?).map_err(|_| invalid("negative length".to_string()))
+    }
+    fn string(&mut self) -> Result<String, Error> {
+        let len = self.len()?;
+        Ok(String::from_utf8_lossy(self.take(len)?).into_owned())
+    }
+    fn dict(&mut self) -> Result<HashMap<String, String>, Error> {
+        (0..self.len()?)
+            .map(|_| Ok((self.string()?, self.string()?)))
+            .collect()
+    }
+}
+
+/// Rotation of a transform node, a matrix with a single 1 or -1 in each row
+#[derive(Clone, Copy)]
+struct Rotation([[i32; 3]; 3]);
+
+impl Rotation {
+    const IDENTITY: Rotation = Rotation([[1, 0, 0], [0, 1, 0], [0, 0, 1]]);
+
+    /// Reads the `_r` byte: bits 0-1 and 2-3 give the column of the first and second rows,
+    /// bits 4 to 6 whether each row is negative
+    fn from_byte(r: u8) -> Result<Rotation, Error> {
+        let (first, second) = ((r & 3) as usize, (r >> 2 & 3) as usize);
+        if first == second || first > 2 || second > 2 {
+            return Err(invalid(format!("invalid rotation {}", r)));
+        }
+        let mut matrix = [[0; 3]; 3];
+        for (row, column) in [first, second, 3 - first - second].into_iter().enumerate() {
+            matrix[row][column] = if r >> (4 + row) & 1 != 0 { -1 } else { 1 };
+        }
+        Ok(Rotation(matrix))
+    }
+    fn apply(&self, v: [i64; 3]) -> [i64; 3] {
+        self.0
+            .map(|row| row[0] as i64 * v[0] + row[1] as i64 * v[1] + row[2] as i64 * v[2])
+    }
+    fn then(&self, inner: &Rotation) -> Rotation {
+        let mut matrix = [[0; 3]; 3];
+        for (row, values) in matrix.iter_mut().enumerate() {
+            for (column, value) in values.iter_mut().enumerate() {
+                *value = (0..3).map(|i| self.0[row][i] * inner.0[i][column]).sum();
+            }
+        }
+        Rotation(matrix)
+    }
+}
+
+enum Node {
+    Transform {
+        child: i32,
+        translation: [i32; 3],
+        rotation: Rotation,
+    },
+    Group(Vec<i32>),
+    Shape(Vec<usize>),
+}
+
+struct Model {
+    size: [i32; 3],
+    /// `x, y, z, color index` of each voxel
+    voxels: Vec<[u8; 4]>,
+}
+
+struct Vox {
+    models: Vec<Model>,
+    palette: [[u8; 3]; 256],
+    nodes: HashMap<i32, Node>,
+}
+
+/// MagicaVoxel's palette for files without an `RGBA` chunk: a 6x6x6 color cube without black,
+/// then ramps of red, green, blue and gray
+fn default_palette() -> [[u8; 3]; 256] {
+    const CUBE: [u8; 6] = [0xff, 0xcc, 0x99, 0x66, 0x33, 0x00];
+    const RAMP: [u8; 10] = [0xee, 0xdd, 0xbb, 0xaa, 0x88, 0x77, 0x55, 0x44, 0x22, 0x11];
+    let cube = CUBE.into_iter().flat_map(|r| {
+        CUBE.into_iter()
+            .flat_map(move |g| CUBE.into_iter().map(move |b| [r, g, b]))
+    });
+    let ramps = [[1, 0, 0], [0, 1, 0], [0, 0, 1], [1, 1, 1]]
+        .into_iter()
+        .flat_map(|channels: [u8; 3]| RAMP.into_iter().map(move |v| channels.map(|c| c * v)));
+    let mut palette = [[0; 3]; 256];
+    for (entry, color) in palette[1..]
+        .iter_mut()
+        .zip(cube.filter(|color| *color != [0; 3]).chain(ramps))
+    {
+        *entry = color;
+    }
+    palette
+}
+
+fn translation(frame: &HashMap<String, String>) -> Result<[i32; 3], Error> {
+    let Some(t) = frame.get("_t") else {
+        return Ok([0; 3]);
+    };
+    let values: Vec<i32> = t
+        .split_whitespace()
+        .map(|value| value.parse::<i32>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| invalid(format!("invalid translation '{}'", t)))?;
+    match values[..] {
+        [x, y, z] => Ok([x, y, z]),
+        _ => Err(invalid(format!("invalid translation '{}'", t))),
+    }
+}
+
+fn hidden(attributes: &HashMap<String, String>) -> bool {
+    attributes
+        .get("_hidden")
+        .is_some_and(|hidden| hidden == "1")
+}
+
+fn read_vox(bytes: &[u8]) -> Result<Vox, Error> {
+    let mut reader = Reader { bytes };
+    if reader.take(4)? != b"VOX " {
+        return Err(invalid("not a MagicaVoxel file".to_string()));
+    }
+    reader.int()?;
+    if reader.take(4)? != b"MAIN" {
+        return Err(invalid("missing 'MAIN' chunk".to_string()));
+    }
+    let content = reader.len()?;
+    let children = reader.len()?;
+    reader.take(content)?;
+    let mut reader = Reader {
+        bytes: reader.take(children)?,
+    };
+
+    let mut vox = Vox {
+        models: vec![],
+        palette: default_palette(),
+        nodes: HashMap::new(),
+    };
+    let mut size = None;
+    while !reader.bytes.is_empty() {
+        let id = reader.take(4)?;
+        let content = reader.len()?;
+        let children = reader.len()?;
+        let mut chunk = Reader {
+            bytes: reader.take(content)?,
+        };
+        reader.take(children)?;
+        match id {
+            b"SIZE" => size = Some([chunk.int()?, chunk.int()?, chunk.int()?]),
+            b"XYZI" => {
+                let Some(size) = size.take() else {
+                    return Err(invalid("'XYZI' chunk without 'SIZE'".to_string()));
+                };
+                let count = chunk.len()?;
+                let voxels = chunk
+                    .take(count.saturating_mul(4))?
+                    .chunks_exact(4)
+                    .map(|voxel| [voxel[0], voxel[1], voxel[2], voxel[3]])
+                    .collect();
+                vox.models.push(Model { size, voxels });
+            }
+            // color `i` of the file is palette index `i + 1`, index 0 is empty
+            b"RGBA" => {
+                for (index, color) in chunk.take(256 * 4)?.chunks_exact(4).take(255).enumerate() {
+                    vox.palette[index + 1] = [color[0], color[1], color[2]];
+                }
+            }
+            b"nTRN" => {
+                let id = chunk.int()?;
+                let attributes = chunk.dict()?;
+                let child = chunk.int()?;
+                chunk.int()?;
+                chunk.int()?;
+                let frames = chunk.len()?;
+                let frame = if frames > 0 {
+                    chunk.dict()?
+                } else {
+                    HashMap::new()
+                };
+                let rotation = match frame.get("_r") {
+                    Some(r) => Rotation::from_byte(
+                        r.parse()
+                            .map_err(|_| invalid(format!("invalid rotation '{}'", r)))?,
+                    )?,
+                    None => Rotation::IDENTITY,
+                };
+                let node = if hidden(&attributes) {
+                    Node::Group(vec![])
+                } else {
+                    Node::Transform {
+                        child,
+                        translation: translation(&frame)?,
+                        rotation,
+                    }
+                };
+                vox.nodes.insert(id, node);
+            }
+            b"nGRP" => {
+                let id = chunk.int()?;
+                let attributes = chunk.dict()?;
+                let children = (0..chunk.len()?)
+                    .map(|_| chunk.int())
+                    .collect::<Result<Vec<i32>, Error>>()?;
+                let children = if hidden(&attributes) {
+                    vec![]
+                } else {
+                    children
+                };
+                vox.nodes.insert(id, Node::Group(children));
+            }
+            b"nSHP" => {
+                let id = chunk.int()?;
+                chunk.dict()?;
+                let models = (0..chunk.len()?)
+                    .map(|_| {
+                        let model = chunk.len()?;
+                        chunk.dict()?;
+                        Ok(model)
+                    })
+                    .collect::<Result<Vec<usize>, Error>>()?;
+                vox.nodes.insert(id, Node::Shape(models));
+            }
+            _ => {}
+        }
+    }
+    Ok(vox)
+}
+
+impl Vox {
+    /// Voxels of the models placed by `node`, in MagicaVoxel coordinates, with their color
+    /// indices. Coordinates are 64-bit, as the translations of nested nodes add up.
+    fn place(
+        &self,
+        node: i32,
+        rotation: Rotation,
+        translation: [i64; 3],
+        depth: u32,
+        voxels: &mut Vec<([i64; 3], u8)>,
+    ) -> Result<(), Error> {
+        if depth > MAX_DEPTH {
+            return Err(invalid("scene graph is too deep".to_string()));
+        }
+        let Some(node) = self.nodes.get(&node) else {
+            return Err(invalid(format!("missing scene graph node {}", node)));
+        };
+        match node {
+            Node::Transform {
+                child,
+                translation: t,
+                rotation: r,
+            } => {
+                let t = rotation.apply(t.map(i64::from));
+                let translation = [0, 1, 2].map(|i| translation[i] + t[i]);
+                self.place(*child, rotation.then(r), translation, depth + 1, voxels)
+            }
+            Node::Group(children) => children
+                .iter()
+                .try_for_each(|child| self.place(*child, rotation, translation, depth + 1, voxels)),
+            Node::Shape(models) => {
+                for model in models {
+                    self.place_model(*model, rotation, translation, voxels)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Models turn around their center, rounded down
+    fn place_model(
+        &self,
+        model: usize,
+        rotation: Rotation,
+        translation: [i64; 3],
+        voxels: &mut Vec<([i64; 3], u8)>,
+    ) -> Result<(), Error> {
+        let Some(model) = self.models.get(model) else {
+            return Err(invalid(format!("missing model {}", model)));
+        };
+        for [x, y, z, color] in &model.voxels {
+            let local = [*x as i64, *y as i64, *z as i64];
+            let centered = [0, 1, 2].map(|i| local[i] - model.size[i] as i64 / 2);
+            let rotated = rotation.apply(centered);
+            voxels.push(([0, 1, 2].map(|i| rotated[i] + translation[i]), *color));
+        }
+        Ok(())
+    }
+}
+
+impl MCStructure {
+    /// Reads a MagicaVoxel model (`.vox`), placing each model where the scene graph puts it
+    /// and skipping hidden nodes. Every palette color becomes the block of the closest
+    /// average color, see `nearest_block`. MagicaVoxel's Z axis points up, so it becomes Y and
+    /// its Y axis becomes -Z. Empty space is left as structure void.
+    pub fn from_vox(bytes: &[u8]) -> Result<MCStructure, Error> {
+        let vox = read_vox(bytes)?;
+        let mut voxels = vec![];
+        if vox.nodes.contains_key(&0) {
+            vox.place(0, Rotation::IDENTITY, [0; 3], 0, &mut voxels)?;
+        } else {
+            // files from before scene graphs have their models on top of each other
+            for model in 0..vox.models.len() {
+                vox.place_model(model, Rotation::IDENTITY, [0; 3], &mut voxels)?;
+            }
+        }
+
+        let out_of_range = || invalid("voxels are placed too far apart".to_string());
+        let voxels: Vec<(Vec3<i32>, u8)> = voxels
+            .into_iter()
+            .map(|([x, y, z], color)| match [x, z, -y].map(i32::try_from) {
+                [Ok(x), Ok(y), Ok(z)] => Ok((Vec3::new(x, y, z), color)),
+                _ => Err(out_of_range()),
+            })
+            .collect::<Result<_, Error>>()?;
+        let Some(min) = voxels
+            .iter()
+            .map(|(loc, _)| *loc)
+            .reduce(|a, b| Vec3::new(*a.x().min(b.x()), *a.y().min(b.y()), *a.z().min(b.z())))
+        else {
+            return Ok(MCStructure::new(Vec3::_000));
+        };
+        let max = voxels.iter().map(|(loc, _)| *loc).fold(min, |a, b| {
+            Vec3::new(*a.x().max(b.x()), *a.y().max(b.y()), *a.z().max(b.z()))
+        });
+        let extent = |max: i32, min: i32| i32::try_from(max as i64 - min as i64 + 1);
+        let (Ok(x), Ok(y), Ok(z)) = (
+            extent(*max.x(), *min.x()),
+            extent(*max.y(), *min.y()),
+            extent(*max.z(), *min.z()),
+        ) else {
+            return Err(out_of_range());
+        };
+        let size = Vec3::new(x, y, z);
+        check_size(size).map_err(|_| out_of_range())?;
+        let mut structure = MCStructure::new(size);
+        // colors that map to the same block share its palette entry
+        let mut blocks: [Option<i32>; 256] = [None; 256];
+        for (loc, color) in voxels {
+            let block = match blocks[color as usize] {
+                Some(block) => block,
+                None => {
+                    let block = nearest_block(vox.palette[color as usize]);
+                    let block = structure.palette_find_or_add(&block)?;
+                    blocks[color as usize] = Some(block);
+                    block
+                }
+            };
+            structure.setblock_palette(loc - min, block)?;
+        }
+        Ok(structure)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::index_from_vec3;
+
+    fn ints(values: &[i32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    fn dict(entries: &[(&str, &str)]) -> Vec<u8> {
+        let mut bytes = ints(&[entries.len() as i32]);
+        for (key, value) in entries {
+            for string in [key, value] {
+                bytes.extend(ints(&[string.len() as i32]));
+                bytes.extend(string.as_bytes());
+            }
+        }
+        bytes
+    }
+
+    fn chunk(id: &[u8; 4], content: Vec<u8>) -> Vec<u8> {
+        let mut bytes = id.to_vec();
+        bytes.extend(ints(&[content.len() as i32, 0]));
+        bytes.extend(content);
+        bytes
+    }
+
+    fn vox(chunks: Vec<Vec<u8>>) -> Vec<u8> {
+        let children = chunks.concat();
+        let mut bytes = b"VOX ".to_vec();
+        bytes.extend(ints(&[150]));
+        bytes.extend(b"MAIN");
+        bytes.extend(ints(&[0, children.len() as i32]));
+        bytes.extend(children);
+        bytes
+    }
+
+    fn model(size: [i32; 3], voxels: &[[u8; 4]]) -> Vec<Vec<u8>> {
+        let mut xyzi = ints(&[voxels.len() as i32]);
+        xyzi.extend(voxels.concat());
+        vec![chunk(b"SIZE", ints(&size)), chunk(b"XYZI", xyzi)]
+    }
+
+    fn rgba(colors: &[[u8; 3]]) -> Vec<u8> {
+        let mut content = vec![0; 256 * 4];
+        for (i, color) in colors.iter().enumerate() {
+            content[i * 4..i * 4 + 3].copy_from_slice(color);
+            content[i * 4 + 3] = 255;
+        }
+        chunk(b"RGBA", content)
+    }
+
+    fn transform(
+        id: i32,
+        child: i32,
+        attributes: &[(&str, &str)],
+        frame: &[(&str, &str)],
+    ) -> Vec<u8> {
+        let mut content = ints(&[id]);
+        content.extend(dict(attributes));
+        content.extend(ints(&[child, -1, 0, 1]));
+        content.extend(dict(frame));
+        chunk(b"nTRN", content)
+    }
+
+    fn group(id: i32, children: &[i32]) -> Vec<u8> {
+        let mut content = ints(&[id]);
+        content.extend(dict(&[]));
+        content.extend(ints(&[children.len() as i32]));
+        content.extend(ints(children));
+        chunk(b"nGRP", content)
+    }
+
+    fn shape(id: i32, model: i32) -> Vec<u8> {
+        let mut content = ints(&[id]);
+        content.extend(dict(&[]));
+        content.extend(ints(&[1, model]));
+        content.extend(dict(&[]));
+        chunk(b"nSHP", content)
+    }
+
+    fn block_at(structure: &MCStructure, loc: Vec3<i32>) -> i32 {
+        structure.blocks[index_from_vec3(loc, structure.size)]
+    }
+
+    #[test]
+    fn default_palette_colors() {
+        let palette = default_palette();
+        assert_eq!(palette[0], [0; 3]);
+        assert_eq!(palette[1], [0xff; 3]);
+        assert_eq!(palette[215], [0x00, 0x00, 0x33]);
+        assert_eq!(palette[216], [0xee, 0x00, 0x00]);
+        assert_eq!(palette[255], [0x11; 3]);
+    }
+
+    #[test]
+    fn model_without_scene_graph() {
+        let white = [250, 250, 250];
+        let mut chunks = model([2, 3, 1], &[[0, 0, 0, 1], [1, 2, 0, 2], [1, 0, 0, 3]]);
+        chunks.push(rgba(&[white, [30, 30, 30], white]));
+        let structure = MCStructure::from_vox(&vox(chunks)).unwrap();
+
+        // MagicaVoxel's Y becomes -Z
+        assert_eq!(structure.size, Vec3::new(2, 1, 3));
+        let white = block_at(&structure, Vec3::new(0, 0, 2));
+        assert_eq!(
+            structure.palette[white as usize],
+            nearest_block([250, 250, 250])
+        );
+        assert_eq!(block_at(&structure, Vec3::new(1, 0, 2)), white);
+        let dark = block_at(&structure, Vec3::new(1, 0, 0));
+        assert_eq!(
+            structure.palette[dark as usize],
+            nearest_block([30, 30, 30])
+        );
+        assert_eq!(structure.palette.len(), 2);
+        assert_eq!(block_at(&structure, Vec3::new(0, 0, 0)), -1);
+    }
+
+    #[test]
+    fn scene_graph() {
+        let mut chunks = model([1, 1, 1], &[[0, 0, 0, 1]]);
+        chunks.extend([
+            transform(0, 1, &[], &[]),
+            group(1, &[2, 4, 6]),
+            transform(2, 3, &[], &[("_t", "10 0 0")]),
+            shape(3, 0),
+            transform(4, 5, &[("_hidden", "1")], &[("_t", "50 0 0")]),
+            shape(5, 0),
+            transform(6, 7, &[], &[("_t", "0 0 5")]),
+            shape(7, 0),
+        ]);
+        let structure = MCStructure::from_vox(&vox(chunks)).unwrap();
+        // MagicaVoxel's Z points up
+        assert_eq!(structure.size, Vec3::new(11, 6, 1));
+        assert!(block_at(&structure, Vec3::new(10, 0, 0)) >= 0);
+        assert!(block_at(&structure, Vec3::new(0, 5, 0)) >= 0);
+        assert_eq!(
+            structure.blocks.iter().filter(|block| **block >= 0).count(),
+            2
+        );
+    }
+
+    #[test]
+    fn rotated_models() {
+        let line = model([3, 1, 1], &[[0, 0, 0, 1], [1, 0, 0, 1], [2, 0, 0, 1]]);
+        let read = |r: &str| {
+            let mut chunks = line.clone();
+            chunks.extend([transform(0, 1, &[], &[("_r", r)]), shape(1, 0)]);
+            MCStructure::from_vox(&vox(chunks))
+        };
+        assert_eq!(read("4").unwrap().size, Vec3::new(3, 1, 1));
+        // a quarter turn around Z turns the line from X to Y, which becomes Z
+        assert_eq!(read("17").unwrap().size, Vec3::new(1, 1, 3));
+        assert_eq!(
+            read("0").err(),
+            Some(invalid("invalid rotation 0".to_string()))
+        );
+    }
+
+    #[test]
+    fn empty_files() {
+        let structure = MCStructure::from_vox(&vox(vec![])).unwrap();
+        assert_eq!(structure.size, Vec3::_000);
+    }
+
+    #[test]
+    fn invalid_files() {
+        let error = |bytes: &[u8]| MCStructure::from_vox(bytes).err();
+        assert_eq!(
+            error(b"PNG 1234"),
+            Some(invalid("not a MagicaVoxel file".to_string()))
+        );
+        let file = vox(model([1, 1, 1], &[[0, 0, 0, 1]]));
+        assert_eq!(
+            error(&file[..file.len() - 1]),
+            Some(invalid("unexpected end of file".to_string()))
+        );
+        assert_eq!(
+            error(&vox(vec![model([1, 1, 1], &[[0, 0, 0, 1]])[1].clone()])),
+            Some(invalid("'XYZI' chunk without 'SIZE'".to_string()))
+        );
+        assert_eq!(
+            error(&vox(vec![transform(0, 1, &[], &[])])),
+            Some(invalid("missing scene graph node 1".to_string()))
+        );
+        assert_eq!(
+            error(&vox(vec![transform(0, 1, &[], &[]), shape(1, 0)])),
+            Some(invalid("missing model 0".to_string()))
+        );
+        assert_eq!(
+            error(&vox(vec![transform(0, 0, &[], &[])])),
+            Some(invalid("scene graph is too deep".to_string()))
+        );
+        let mut far = model([1, 1, 1], &[[0, 0, 0, 1]]);
+        far.extend([
+            transform(0, 1, &[], &[]),
+            group(1, &[2, 4]),
+            transform(2, 3, &[], &[("_t", "2147483647 0 0")]),
+            shape(3, 0),
+            transform(4, 5, &[], &[("_t", "-2147483648 0 0")]),
+            shape(5, 0),
+        ]);
+        assert_eq!(
+            error(&vox(far)),
+            Some(invalid("voxels are placed too far apart".to_string()))
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use std::{fs::{self, File}, io::{self, Write}};
 
-use mcstructs::{nbt::NbtTree, structure::MCStructure, types::{BlockState, BlockType, Vec3}};
+use mcstructs::{nbt::NbtTree, structure::MCStructure, types::{BlockState, BlockType, ItemStack, Vec3}};
 
 fn main () -> io::Result<()> {
 	let path = "generated.mcstructure";
@@ -12,11 +12,11 @@ fn main () -> io::Result<()> {
 		.set_state("minecraft:cardinal_direction", &BlockState::String("north".to_string()))
 		.set_state("output_lit_bit", &BlockState::Bool(0))
 		.set_state("output_subtract_bit", &BlockState::Bool(1))
-	);
+	).map_err(io::Error::other)?;
 
 	structure.setblock(Vec3::new(0, 1, 0),
 		BlockType::new("minecraft:barrel")
-	).set_item_slot(0, "minecraft:redstone", 32);
+	).map_err(io::Error::other)?.set_item_slot(0, &ItemStack::new("minecraft:redstone", 32).map_err(io::Error::other)?).map_err(io::Error::other)?;
 
 	let mut file = File::create(path)?;
 	let data = structure.as_bytes();
@@ -25,7 +25,7 @@ fn main () -> io::Result<()> {
 	println!("Wrote {} bytes to '{}'", data.len(), path);
 
 	let bytes = fs::read(path)?;
-	let nbt = NbtTree::from_bytes(true, bytes);
+	let nbt = NbtTree::from_bytes(true, bytes).map_err(io::Error::other)?;
 	nbt.print();
 
 	Ok(())
@@ -0,0 +1,412 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Error,
+    nbt::{TagData, TagKind, TagList},
+    structure::MCStructure,
+    types::{
+        Block, BlockState, BlockType, Cardinal, DyeColor, Facing, ItemStack, Vec3,
+        block_entity_or_insert, set_tag_unless_default,
+    },
+};
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum BannerPattern {
+    Base,
+    StripeBottom,
+    StripeTop,
+    StripeLeft,
+    StripeRight,
+    StripeCenter,
+    StripeMiddle,
+    StripeDownRight,
+    StripeDownLeft,
+    SmallStripes,
+    Cross,
+    StraightCross,
+    DiagonalLeft,
+    DiagonalRight,
+    DiagonalUpLeft,
+    DiagonalUpRight,
+    HalfVertical,
+    HalfVerticalRight,
+    HalfHorizontal,
+    HalfHorizontalBottom,
+    SquareBottomLeft,
+    SquareBottomRight,
+    SquareTopLeft,
+    SquareTopRight,
+    TriangleBottom,
+    TriangleTop,
+    TrianglesBottom,
+    TrianglesTop,
+    Circle,
+    Rhombus,
+    Border,
+    CurlyBorder,
+    Bricks,
+    Gradient,
+    GradientUp,
+    Creeper,
+    Skull,
+    Flower,
+    Mojang,
+    Globe,
+    Piglin,
+    Flow,
+    Guster,
+}
+
+impl BannerPattern {
+    pub const ALL: [BannerPattern; 43] = [
+        BannerPattern::Base,
+        BannerPattern::StripeBottom,
+        BannerPattern::StripeTop,
+        BannerPattern::StripeLeft,
+        BannerPattern::StripeRight,
+        BannerPattern::StripeCenter,
+        BannerPattern::StripeMiddle,
+        BannerPattern::StripeDownRight,
+        BannerPattern::StripeDownLeft,
+        BannerPattern::SmallStripes,
+        BannerPattern::Cross,
+        BannerPattern::StraightCross,
+        BannerPattern::DiagonalLeft,
+        BannerPattern::DiagonalRight,
+        BannerPattern::DiagonalUpLeft,
+        BannerPattern::DiagonalUpRight,
+        BannerPattern::HalfVertical,
+        BannerPattern::HalfVerticalRight,
+        BannerPattern::HalfHorizontal,
+        BannerPattern::HalfHorizontalBottom,
+        BannerPattern::SquareBottomLeft,
+        BannerPattern::SquareBottomRight,
+        BannerPattern::SquareTopLeft,
+        BannerPattern::SquareTopRight,
+        BannerPattern::TriangleBottom,
+        BannerPattern::TriangleTop,
+        BannerPattern::TrianglesBottom,
+        BannerPattern::TrianglesTop,
+        BannerPattern::Circle,
+        BannerPattern::Rhombus,
+        BannerPattern::Border,
+        BannerPattern::CurlyBorder,
+        BannerPattern::Bricks,
+        BannerPattern::Gradient,
+        BannerPattern::GradientUp,
+        BannerPattern::Creeper,
+        BannerPattern::Skull,
+        BannerPattern::Flower,
+        BannerPattern::Mojang,
+        BannerPattern::Globe,
+        BannerPattern::Piglin,
+        BannerPattern::Flow,
+        BannerPattern::Guster,
+    ];
+
+    /// Code stored in `Pattern`
+    pub fn code(self) -> &'static str {
+        match self {
+            BannerPattern::Base => "b",
+            BannerPattern::StripeBottom => "bs",
+            BannerPattern::StripeTop => "ts",
+            BannerPattern::StripeLeft => "ls",
+            BannerPattern::StripeRight => "rs",
+            BannerPattern::StripeCenter => "cs",
+            BannerPattern::StripeMiddle => "ms",
+            BannerPattern::StripeDownRight => "drs",
+            BannerPattern::StripeDownLeft => "dls",
+            BannerPattern::SmallStripes => "ss",
+            BannerPattern::Cross => "cr",
+            BannerPattern::StraightCross => "sc",
+            BannerPattern::DiagonalLeft => "ld",
+            BannerPattern::DiagonalRight => "rud",
+            BannerPattern::DiagonalUpLeft => "lud",
+            BannerPattern::DiagonalUpRight => "rd",
+            BannerPattern::HalfVertical => "vh",
+            BannerPattern::HalfVerticalRight => "vhr",
+            BannerPattern::HalfHorizontal => "hh",
+            BannerPattern::HalfHorizontalBottom => "hhb",
+            BannerPattern::SquareBottomLeft => "bl",
+            BannerPattern::SquareBottomRight => "br",
+            BannerPattern::SquareTopLeft => "tl",
+            BannerPattern::SquareTopRight => "tr",
+            BannerPattern::TriangleBottom => "bt",
+            BannerPattern::TriangleTop => "tt",
+            BannerPattern::TrianglesBottom => "bts",
+            BannerPattern::TrianglesTop => "tts",
+            BannerPattern::Circle => "mc",
+            BannerPattern::Rhombus => "mr",
+            BannerPattern::Border => "bo",
+            BannerPattern::CurlyBorder => "cbo",
+            BannerPattern::Bricks => "bri",
+            BannerPattern::Gradient => "gra",
+            BannerPattern::GradientUp => "gru",
+            BannerPattern::Creeper => "cre",
+            BannerPattern::Skull => "sku",
+            BannerPattern::Flower => "flo",
+            BannerPattern::Mojang => "moj",
+            BannerPattern::Globe => "glb",
+            BannerPattern::Piglin => "pig",
+            BannerPattern::Flow => "flw",
+            BannerPattern::Guster => "gus",
+        }
+    }
+    pub fn from_code(code: &str) -> Option<BannerPattern> {
+        BannerPattern::ALL
+            .into_iter()
+            .find(|pattern| pattern.code() == code)
+    }
+}
+
+/// Where a banner block hangs
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BannerPlacement {
+    /// On the ground, turned in 16 steps clockwise starting at south
+    Standing(u8),
+    /// On a wall, with its front facing the given direction
+    Wall(Cardinal),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Banner {
+    pub base: DyeColor,
+    /// Pattern layers, bottom layer first
+    pub patterns: Vec<(BannerPattern, DyeColor)>,
+    /// Illager banners have `Type` 1
+    pub ominous: bool,
+}
+
+impl Banner {
+    pub fn new(base: DyeColor) -> Self {
+        Banner {
+            base,
+            patterns: vec![],
+            ominous: false,
+        }
+    }
+    pub fn add_pattern(mut self, pattern: BannerPattern, color: DyeColor) -> Self {
+        self.patterns.push((pattern, color));
+        self
+    }
+    pub fn set_ominous(mut self, ominous: bool) -> Self {
+        self.ominous = ominous;
+        self
+    }
+
+    pub fn block_type(placement: BannerPlacement) -> BlockType {
+        match placement {
+            BannerPlacement::Standing(rotation) => BlockType::new("minecraft:standing_banner")
+                .set_state(
+                    "ground_sign_direction",
+                    &BlockState::Int((rotation % 16) as i32),
+                ),
+            BannerPlacement::Wall(facing) => BlockType::new("minecraft:wall_banner").set_state(
+                "facing_direction",
+                &BlockState::Int(Facing::from(facing).facing_direction()),
+            ),
+        }
+    }
+    /// Banner item with the same colors and patterns, e.g. to put in a container
+    pub fn item(&self, count: u8) -> Result<ItemStack, Error> {
+        let mut item =
+            ItemStack::new("minecraft:banner", count)?.set_damage(self.base.banner_color() as i16);
+        if !self.patterns.is_empty() {
+            item = item.add_tag("Patterns", self.patterns_tag());
+        }
+        if self.ominous {
+            item = item.add_tag("Type", TagData::Int(1));
+        }
+        Ok(item)
+    }
+
+    fn patterns_tag(&self) -> TagData {
+        TagData::List(
+            TagKind::Compound,
+            self.patterns.len() as u32,
+            self.patterns
+                .iter()
+                .map(|(pattern, color)| {
+                    TagData::Compound(TagList::from(vec![
+                        ("Color".to_string(), TagData::Int(color.banner_color())),
+                        (
+                            "Pattern".to_string(),
+                            TagData::String(pattern.code().to_string()),
+                        ),
+                    ]))
+                })
+                .collect(),
+        )
+    }
+    pub(crate) fn from_tag(data: &TagData) -> Option<Banner> {
+        let Some(TagData::Int(base)) = data.find_tag("Base") else {
+            return None;
+        };
+        let mut patterns = vec![];
+        if let Some(TagData::List(_, _, list)) = data.find_tag("Patterns") {
+            for pattern in list {
+                let (Some(TagData::String(code)), Some(TagData::Int(color))) =
+                    (pattern.find_tag("Pattern"), pattern.find_tag("Color"))
+                else {
+                    return None;
+                };
+                patterns.push((
+                    BannerPattern::from_code(code)?,
+                    DyeColor::from_banner_color(*color)?,
+                ));
+            }
+        }
+        Some(Banner {
+            base: DyeColor::from_banner_color(*base)?,
+            patterns,
+            ominous: data.find_tag("Type") == Some(&TagData::Int(1)),
+        })
+    }
+    pub(crate) fn write_tags(&self, data: &mut TagData) {
+        data.set_tag("Base", TagData::Int(self.base.banner_color()));
+        set_tag_unless_default(
+            data,
+            "Patterns",
+            self.patterns_tag(),
+            TagData::List(TagKind::Compound, 0, vec![]),
+        );
+        set_tag_unless_default(
+            data,
+            "Type",
+            TagData::Int(self.ominous as i32),
+            TagData::Int(0),
+        );
+    }
+}
+
+pub(crate) fn set_banner_of_block(
+    structure: &mut MCStructure,
+    permutation: &BlockType,
+    index: u32,
+    banner: &Banner,
+) -> Result<(), Error> {
+    if !matches!(
+        permutation.type_id.as_str(),
+        "minecraft:standing_banner" | "minecraft:wall_banner"
+    ) {
+        return Err(Error::UnexpectedBlock {
+            type_id: permutation.type_id.clone(),
+            expected: "banner",
+        });
+    }
+    let data = block_entity_or_insert(structure, index, "Banner", vec![])?;
+    banner.write_tags(data);
+    Ok(())
+}
+
+impl<'a> Block<'a> {
+    pub fn set_banner(self, banner: &Banner) -> Result<Self, Error> {
+        set_banner_of_block(self.structure, &self.permutation, self.index, banner)?;
+        Ok(self)
+    }
+}
+
+impl MCStructure {
+    pub fn place_banner(
+        &mut self,
+        loc: Vec3<i32>,
+        placement: BannerPlacement,
+        banner: &Banner,
+    ) -> Result<Block<'_>, Error> {
+        self.setblock(loc, Banner::block_type(placement))?
+            .set_banner(banner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BlockEntity;
+
+    fn banner() -> Banner {
+        Banner::new(DyeColor::White)
+            .add_pattern(BannerPattern::Creeper, DyeColor::Green)
+            .add_pattern(BannerPattern::Border, DyeColor::Black)
+    }
+
+    #[test]
+    fn pattern_codes_round_trip() {
+        for pattern in BannerPattern::ALL {
+            assert_eq!(BannerPattern::from_code(pattern.code()), Some(pattern));
+        }
+        assert_eq!(BannerPattern::from_code("nope"), None);
+    }
+
+    #[test]
+    fn banner_block_states() {
+        assert_eq!(
+            Banner::block_type(BannerPlacement::Standing(20)),
+            BlockType::new("minecraft:standing_banner")
+                .set_state("ground_sign_direction", &BlockState::Int(4))
+        );
+        assert_eq!(
+            Banner::block_type(BannerPlacement::Wall(Cardinal::East)),
+            BlockType::new("minecraft:wall_banner")
+                .set_state("facing_direction", &BlockState::Int(5))
+        );
+    }
+
+    #[test]
+    fn place_banner_round_trips() {
+        let mut structure = MCStructure::new(Vec3::new(1, 1, 1));
+        let banner = banner().set_ominous(true);
+        structure
+            .place_banner(Vec3::new(0, 0, 0), BannerPlacement::Standing(0), &banner)
+            .unwrap();
+
+        let Some(BlockEntity::Banner(read, tags)) = structure.block_entity(Vec3::new(0, 0, 0))
+        else {
+            panic!("expected a banner");
+        };
+        assert_eq!(read, banner);
+        assert_eq!(tags.find_tag("Base"), Some(&TagData::Int(15)));
+        assert_eq!(tags.find_tag("Type"), Some(&TagData::Int(1)));
+    }
+
+    #[test]
+    fn plain_banners_have_no_patterns_tag() {
+        let mut structure = MCStructure::new(Vec3::new(1, 1, 1));
+        structure
+            .place_banner(
+                Vec3::new(0, 0, 0),
+                BannerPlacement::Wall(Cardinal::North),
+                &Banner::new(DyeColor::Black),
+            )
+            .unwrap();
+        let data = structure.block_entity_at_index(0).unwrap();
+        assert_eq!(data.find_tag("Base"), Some(&TagData::Int(0)));
+        assert_eq!(data.find_tag("Patterns"), None);
+        assert_eq!(data.find_tag("Type"), None);
+    }
+
+    #[test]
+    fn banner_items() {
+        let item = banner().item(2).unwrap();
+        assert_eq!(item.damage, 15);
+        assert_eq!(
+            item.extra_tags.find_tag("Patterns"),
+            Some(&banner().patterns_tag())
+        );
+        assert!(matches!(
+            banner().item(17),
+            Err(Error::InvalidItemCount { max: 16, .. })
+        ));
+    }
+
+    #[test]
+    fn only_banners_hold_banner_data() {
+        let mut structure = MCStructure::new(Vec3::new(1, 1, 1));
+        assert!(matches!(
+            structure
+                .setblock(Vec3::new(0, 0, 0), BlockType::new("minecraft:stone"))
+                .unwrap()
+                .set_banner(&banner()),
+            Err(Error::UnexpectedBlock { .. })
+        ));
+    }
+}
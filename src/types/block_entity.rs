@@ -0,0 +1,419 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::{
+    error::Error,
+    nbt::{TagData, TagList},
+    structure::{MCStructure, index_from_vec3, set_block_entity_position, vec3_from_index},
+    types::{
+        Banner, CommandBlock, Container, JigsawBlock, MobSpawner, Sign, SignKind, StructureBlock,
+        Vec3, container::CONTAINER_BLOCK_ENTITY_IDS,
+    },
+};
+
+/// Typed data of a beacon block entity
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Beacon {
+    /// Effect ids, 0 for none
+    pub primary: i32,
+    pub secondary: i32,
+}
+
+impl Beacon {
+    fn from_tag(data: &TagData) -> Option<Beacon> {
+        match (data.find_tag("primary"), data.find_tag("secondary")) {
+            (Some(TagData::Int(primary)), Some(TagData::Int(secondary))) => Some(Beacon {
+                primary: *primary,
+                secondary: *secondary,
+            }),
+            _ => None,
+        }
+    }
+    fn write_tags(&self, data: &mut TagData) {
+        data.set_tag("primary", TagData::Int(self.primary));
+        data.set_tag("secondary", TagData::Int(self.secondary));
+    }
+}
+
+/// Writes `value`, unless it is `default` and the tag is missing anyway
+pub(crate) fn set_tag_unless_default(
+    data: &mut TagData,
+    id: &str,
+    value: TagData,
+    default: TagData,
+) {
+    if value != default || data.find_tag(id).is_some() {
+        data.set_tag(id, value);
+    }
+}
+
+/// A block entity from `block_position_data`. Typed variants keep the compound they were read
+/// from next to the typed data, so tags that are not understood survive; the typed data is
+/// written over that compound. Block entities the typed data cannot reproduce exactly are
+/// `Unknown`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BlockEntity {
+    Container(Container, TagList),
+    Sign(Sign, TagList),
+    CommandBlock(CommandBlock, TagList),
+    Spawner(MobSpawner, TagList),
+    Banner(Banner, TagList),
+    Beacon(Beacon, TagList),
+    Jigsaw(JigsawBlock, TagList),
+    StructureBlock(StructureBlock, TagList),
+    Unknown(TagData),
+}
+
+impl BlockEntity {
+    pub fn from_tag(data: &TagData) -> BlockEntity {
+        let TagData::Compound(tags) = data else {
+            return BlockEntity::Unknown(data.clone());
+        };
+        let Some(TagData::String(id)) = data.find_tag("id") else {
+            return BlockEntity::Unknown(data.clone());
+        };
+        let tags = tags.clone();
+        let typed = match id.as_str() {
+            "Sign" | "HangingSign" => {
+                Sign::from_tag(data).map(|sign| BlockEntity::Sign(sign, tags))
+            }
+            "CommandBlock" => CommandBlock::from_tag(data)
+                .map(|command_block| BlockEntity::CommandBlock(command_block, tags)),
+            "MobSpawner" => {
+                MobSpawner::from_tag(data).map(|spawner| BlockEntity::Spawner(spawner, tags))
+            }
+            "Banner" => Banner::from_tag(data).map(|banner| BlockEntity::Banner(banner, tags)),
+            "Beacon" => Beacon::from_tag(data).map(|beacon| BlockEntity::Beacon(beacon, tags)),
+            "JigsawBlock" => {
+                JigsawBlock::from_tag(data).map(|jigsaw| BlockEntity::Jigsaw(jigsaw, tags))
+            }
+            "StructureBlock" => StructureBlock::from_tag(data)
+                .map(|structure_block| BlockEntity::StructureBlock(structure_block, tags)),
+            id if CONTAINER_BLOCK_ENTITY_IDS.contains(&id) => {
+                Container::from_tag(data).map(|container| BlockEntity::Container(container, tags))
+            }
+            _ => None,
+        };
+        match typed {
+            Some(block_entity) if block_entity.as_tag() == *data => block_entity,
+            _ => BlockEntity::Unknown(data.clone()),
+        }
+    }
+    pub fn as_tag(&self) -> TagData {
+        let with_tags = |tags: &TagList, write: &dyn Fn(&mut TagData)| {
+            let mut data = TagData::Compound(tags.clone());
+            write(&mut data);
+            data
+        };
+        match self {
+            BlockEntity::Container(container, tags) => {
+                with_tags(tags, &|data| container.write_tags(data))
+            }
+            BlockEntity::Sign(sign, tags) => with_tags(tags, &|data| sign.write_tags(data)),
+            BlockEntity::CommandBlock(command_block, tags) => {
+                with_tags(tags, &|data| command_block.write_settings(data))
+            }
+            BlockEntity::Spawner(spawner, tags) => {
+                with_tags(tags, &|data| spawner.write_tags(data))
+            }
+            BlockEntity::Banner(banner, tags) => with_tags(tags, &|data| banner.write_tags(data)),
+            BlockEntity::Beacon(beacon, tags) => with_tags(tags, &|data| beacon.write_tags(data)),
+            BlockEntity::Jigsaw(jigsaw, tags) => with_tags(tags, &|data| jigsaw.write_tags(data)),
+            BlockEntity::StructureBlock(structure_block, tags) => {
+                with_tags(tags, &|data| structure_block.write_tags(data))
+            }
+            BlockEntity::Unknown(data) => data.clone(),
+        }
+    }
+    /// The `id` tag, e.g. `Chest`
+    pub fn id(&self) -> Option<&str> {
+        let id = match self {
+            BlockEntity::Container(_, tags)
+            | BlockEntity::Sign(_, tags)
+            | BlockEntity::CommandBlock(_, tags)
+            | BlockEntity::Spawner(_, tags)
+            | BlockEntity::Banner(_, tags)
+            | BlockEntity::Beacon(_, tags)
+            | BlockEntity::Jigsaw(_, tags)
+            | BlockEntity::StructureBlock(_, tags) => tags.find_tag("id"),
+            BlockEntity::Unknown(data) => data.find_tag("id"),
+        };
+        match id {
+            Some(TagData::String(id)) => Some(id),
+            _ => None,
+        }
+    }
+    /// `id` written for block entities created without one. Containers and signs depend on
+    /// their block.
+    fn default_id(&self) -> Option<&'static str> {
+        match self {
+            BlockEntity::CommandBlock(..) => Some("CommandBlock"),
+            BlockEntity::Spawner(..) => Some("MobSpawner"),
+            BlockEntity::Banner(..) => Some("Banner"),
+            BlockEntity::Beacon(..) => Some("Beacon"),
+            BlockEntity::Jigsaw(..) => Some("JigsawBlock"),
+            BlockEntity::StructureBlock(..) => Some("StructureBlock"),
+            BlockEntity::Container(..) | BlockEntity::Sign(..) | BlockEntity::Unknown(_) => None,
+        }
+    }
+}
+
+/// Mutable access to a block entity, written back to the structure when dropped
+pub struct BlockEntityMut<'a> {
+    structure: &'a mut MCStructure,
+    index: u32,
+    block_entity: BlockEntity,
+}
+
+impl Deref for BlockEntityMut<'_> {
+    type Target = BlockEntity;
+    fn deref(&self) -> &BlockEntity {
+        &self.block_entity
+    }
+}
+
+impl DerefMut for BlockEntityMut<'_> {
+    fn deref_mut(&mut self) -> &mut BlockEntity {
+        &mut self.block_entity
+    }
+}
+
+impl Drop for BlockEntityMut<'_> {
+    fn drop(&mut self) {
+        let index = self.index as usize;
+        let data = match self
+            .structure
+            .complete_block_entity(index, &self.block_entity)
+        {
+            Ok(data) => data,
+            // keep the id of the block entity that was read if no other can be found
+            Err(_) => {
+                let mut data = self.block_entity.as_tag();
+                if let TagData::Compound(_) = data {
+                    if self.block_entity.id().is_none()
+                        && let Some(id) = self
+                            .structure
+                            .block_position_data
+                            .get(&self.index)
+                            .and_then(|old| old.find_tag("id"))
+                    {
+                        data.set_tag("id", id.clone());
+                    }
+                    set_block_entity_position(
+                        &mut data,
+                        vec3_from_index(index, self.structure.size),
+                    );
+                }
+                data
+            }
+        };
+        self.structure.block_position_data.insert(self.index, data);
+    }
+}
+
+impl MCStructure {
+    pub fn block_entity(&self, loc: Vec3<i32>) -> Option<BlockEntity> {
+        if !self.contains(loc) {
+            return None;
+        }
+        self.block_entity_at_index(index_from_vec3(loc, self.size) as u32)
+            .map(BlockEntity::from_tag)
+    }
+    pub fn block_entity_mut(&mut self, loc: Vec3<i32>) -> Option<BlockEntityMut<'_>> {
+        if !self.contains(loc) {
+            return None;
+        }
+        let index = index_from_vec3(loc, self.size) as u32;
+        let block_entity = BlockEntity::from_tag(self.block_entity_at_index(index)?);
+        Some(BlockEntityMut {
+            structure: self,
+            index,
+            block_entity,
+        })
+    }
+    /// Stores a block entity at `loc`, replacing any that is there. Its position is set from
+    /// `loc`, and a missing `id` is filled in from the kind of block entity or the block at
+    /// `loc`.
+    pub fn set_block_entity(
+        &mut self,
+        loc: Vec3<i32>,
+        block_entity: &BlockEntity,
+    ) -> Result<(), Error> {
+        self.check_bounds(loc)?;
+        let index = index_from_vec3(loc, self.size);
+        let data = self.complete_block_entity(index, block_entity)?;
+        self.block_position_data.insert(index as u32, data);
+        Ok(())
+    }
+    /// Tag of a block entity stored at `index`, with its position and any missing `id` filled in
+    fn complete_block_entity(
+        &self,
+        index: usize,
+        block_entity: &BlockEntity,
+    ) -> Result<TagData, Error> {
+        let mut data = block_entity.as_tag();
+        if !matches!(data, TagData::Compound(_)) {
+            return Err(Error::InvalidStructure(
+                "block entity data must be a compound".to_string(),
+            ));
+        }
+        if block_entity.id().is_none() {
+            let block =
+                (self.blocks[index] >= 0).then(|| &self.palette[self.blocks[index] as usize]);
+            let id = match block_entity {
+                BlockEntity::Container(..) => block
+                    .and_then(|block| block.container_info())
+                    .map(|container| container.block_entity_id)
+                    .ok_or_else(|| {
+                        Error::NotAContainer(
+                            block.map_or("structure void".to_string(), |block| {
+                                block.type_id.clone()
+                            }),
+                        )
+                    })?,
+                BlockEntity::Sign(..) => match block.and_then(|block| block.sign_kind()) {
+                    Some(SignKind::Hanging) => "HangingSign",
+                    _ => "Sign",
+                },
+                _ => block_entity
+                    .default_id()
+                    .ok_or_else(|| Error::InvalidStructure("block entity has no id".to_string()))?,
+            };
+            data.set_tag("id", TagData::String(id.to_string()));
+        }
+        if data.find_tag("isMovable").is_none() {
+            data.set_tag("isMovable", TagData::Byte(1));
+        }
+        set_block_entity_position(&mut data, vec3_from_index(index, self.size));
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BlockType;
+
+    fn beacon_tag() -> TagData {
+        TagData::Compound(TagList::from(vec![
+            ("id".to_string(), TagData::String("Beacon".to_string())),
+            ("primary".to_string(), TagData::Int(1)),
+            ("secondary".to_string(), TagData::Int(10)),
+            ("isMovable".to_string(), TagData::Byte(1)),
+            ("x".to_string(), TagData::Int(0)),
+            ("y".to_string(), TagData::Int(0)),
+            ("z".to_string(), TagData::Int(0)),
+        ]))
+    }
+
+    #[test]
+    fn typed_block_entities_round_trip() {
+        let data = beacon_tag();
+        let block_entity = BlockEntity::from_tag(&data);
+        let BlockEntity::Beacon(beacon, _) = &block_entity else {
+            panic!("expected a beacon");
+        };
+        assert_eq!(
+            *beacon,
+            Beacon {
+                primary: 1,
+                secondary: 10,
+            }
+        );
+        assert_eq!(block_entity.as_tag(), data);
+        assert_eq!(block_entity.id(), Some("Beacon"));
+    }
+
+    #[test]
+    fn unknown_block_entities() {
+        let unknown = TagData::Compound(TagList::from(vec![(
+            "id".to_string(),
+            TagData::String("Bell".to_string()),
+        )]));
+        assert_eq!(
+            BlockEntity::from_tag(&unknown),
+            BlockEntity::Unknown(unknown.clone())
+        );
+        assert_eq!(
+            BlockEntity::from_tag(&TagData::Int(3)),
+            BlockEntity::Unknown(TagData::Int(3))
+        );
+        // a command block without its settings cannot be written back the same
+        let partial = TagData::Compound(TagList::from(vec![
+            (
+                "id".to_string(),
+                TagData::String("CommandBlock".to_string()),
+            ),
+            ("Command".to_string(), TagData::String("say".to_string())),
+        ]));
+        assert_eq!(
+            BlockEntity::from_tag(&partial),
+            BlockEntity::Unknown(partial.clone())
+        );
+    }
+
+    #[test]
+    fn block_entity_mut_writes_back() {
+        let mut structure = MCStructure::new(Vec3::new(1, 1, 1));
+        structure
+            .setblock(Vec3::new(0, 0, 0), BlockType::new("minecraft:beacon"))
+            .unwrap();
+        structure.block_position_data.insert(0, beacon_tag());
+        if let BlockEntity::Beacon(beacon, _) =
+            &mut *structure.block_entity_mut(Vec3::_000).unwrap()
+        {
+            beacon.secondary = 0;
+        }
+        assert_eq!(
+            structure
+                .block_entity_at_index(0)
+                .unwrap()
+                .find_tag("secondary"),
+            Some(&TagData::Int(0))
+        );
+    }
+
+    #[test]
+    fn set_block_entity_fills_in_the_id() {
+        let mut structure = MCStructure::new(Vec3::new(2, 1, 1));
+        let container = BlockEntity::Container(Container::default(), TagList::new());
+        structure
+            .setblock(Vec3::new(1, 0, 0), BlockType::new("minecraft:hopper"))
+            .unwrap();
+        structure
+            .set_block_entity(Vec3::new(1, 0, 0), &container)
+            .unwrap();
+
+        let data = structure.block_entity_at_index(1).unwrap();
+        assert_eq!(
+            data.find_tag("id"),
+            Some(&TagData::String("Hopper".to_string()))
+        );
+        assert_eq!(data.find_tag("x"), Some(&TagData::Int(1)));
+        assert_eq!(data.find_tag("isMovable"), Some(&TagData::Byte(1)));
+
+        assert_eq!(
+            structure.set_block_entity(Vec3::new(0, 0, 0), &container),
+            Err(Error::NotAContainer("structure void".to_string()))
+        );
+    }
+
+    #[test]
+    fn out_of_range_positions() {
+        let mut structure = MCStructure::new(Vec3::new(1, 1, 1));
+        structure
+            .setblock(Vec3::new(0, 0, 0), BlockType::new("minecraft:beacon"))
+            .unwrap();
+        structure.block_position_data.insert(0, beacon_tag());
+        assert!(structure.block_entity(Vec3::new(0, 0, 0)).is_some());
+        assert!(structure.block_entity(Vec3::new(1, 0, 0)).is_none());
+        assert!(structure.block_entity(Vec3::new(0, -1, 0)).is_none());
+        assert!(structure.block_entity_mut(Vec3::new(0, 0, 1)).is_none());
+        assert!(matches!(
+            structure.set_block_entity(
+                Vec3::new(0, 2, 0),
+                &BlockEntity::Beacon(Beacon::default(), TagList::new())
+            ),
+            Err(Error::OutOfBounds { .. })
+        ));
+    }
+}
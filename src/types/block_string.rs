@@ -0,0 +1,235 @@
+use std::{fmt, iter::Peekable, str::Chars, str::FromStr};
+
+use crate::{
+    error::Error,
+    types::{BlockState, BlockType},
+};
+
+fn write_quoted(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        if c == '"' || c == '\\' {
+            write!(f, "\\")?;
+        }
+        write!(f, "{}", c)?;
+    }
+    write!(f, "\"")
+}
+
+/// Formats the block in Bedrock command syntax, e.g.
+/// `minecraft:stone_stairs["weirdo_direction"=2,"upside_down_bit"=true]`
+impl fmt::Display for BlockType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.type_id)?;
+        if self.states.is_empty() {
+            return Ok(());
+        }
+        write!(f, "[")?;
+        for (i, (name, state)) in self.states.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write_quoted(f, name)?;
+            write!(f, "=")?;
+            match state {
+                BlockState::String(value) => write_quoted(f, value)?,
+                BlockState::Int(value) => write!(f, "{}", value)?,
+                BlockState::Bool(value) => write!(f, "{}", *value != 0)?,
+            }
+        }
+        write!(f, "]")
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+    fn error(&self, reason: &str) -> Error {
+        Error::InvalidBlockString {
+            input: self.input.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+    /// A quoted string, or a bare word up to the next `stop` character
+    fn word(&mut self, stop: &[char]) -> Result<(String, bool), Error> {
+        self.skip_whitespace();
+        let mut word = String::new();
+        if self.chars.next_if_eq(&'"').is_some() {
+            loop {
+                match self.chars.next() {
+                    Some('"') => return Ok((word, true)),
+                    Some('\\') => match self.chars.next() {
+                        Some(c) => word.push(c),
+                        None => return Err(self.error("unterminated string")),
+                    },
+                    Some(c) => word.push(c),
+                    None => return Err(self.error("unterminated string")),
+                }
+            }
+        }
+        while let Some(c) = self
+            .chars
+            .next_if(|c| !stop.contains(c) && !c.is_whitespace())
+        {
+            word.push(c);
+        }
+        if word.is_empty() {
+            return Err(self.error("expected a name or value"));
+        }
+        Ok((word, false))
+    }
+    fn expect(&mut self, expected: &[char]) -> Result<char, Error> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some(c) if expected.contains(&c) => Ok(c),
+            Some(c) => Err(self.error(&format!("unexpected '{}'", c))),
+            None => Err(self.error("unexpected end")),
+        }
+    }
+}
+
+/// Parses Bedrock command syntax, `minecraft:stone_stairs["weirdo_direction"=2]`, as well as
+/// Java syntax, `minecraft:oak_stairs[facing=north]`. The namespace defaults to `minecraft`.
+/// Quoted values are strings; unquoted values are booleans, integers or strings, whichever fits.
+impl FromStr for BlockType {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<BlockType, Error> {
+        let mut parser = Parser {
+            input,
+            chars: input.chars().peekable(),
+        };
+        let (type_id, _) = parser.word(&['['])?;
+        let type_id = if type_id.contains(':') {
+            type_id
+        } else {
+            format!("minecraft:{}", type_id)
+        };
+        let mut block = BlockType::new(&type_id);
+
+        parser.skip_whitespace();
+        if parser.chars.next_if_eq(&'[').is_some() {
+            parser.skip_whitespace();
+            if parser.chars.next_if_eq(&']').is_none() {
+                loop {
+                    // older Bedrock versions separate names and values with ':', so names
+                    // containing ':' such as `minecraft:cardinal_direction` must be quoted
+                    let (name, _) = parser.word(&['=', ':', ',', ']'])?;
+                    parser.expect(&['=', ':'])?;
+                    let (value, quoted) = parser.word(&[',', ']', '='])?;
+                    let state = match (quoted, value.as_str()) {
+                        (true, _) => BlockState::String(value),
+                        (false, "true") => BlockState::Bool(1),
+                        (false, "false") => BlockState::Bool(0),
+                        (false, _) => match value.parse::<i32>() {
+                            Ok(int) => BlockState::Int(int),
+                            Err(_) => BlockState::String(value),
+                        },
+                    };
+                    block = block.set_state(&name, &state);
+                    if parser.expect(&[',', ']'])? == ']' {
+                        break;
+                    }
+                }
+            }
+        }
+        parser.skip_whitespace();
+        if parser.chars.next().is_some() {
+            return Err(parser.error("unexpected text after the block"));
+        }
+        Ok(block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stairs() -> BlockType {
+        BlockType::new("minecraft:stone_stairs")
+            .set_state("weirdo_direction", &BlockState::Int(2))
+            .set_state("upside_down_bit", &BlockState::Bool(1))
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            stairs().to_string(),
+            r#"minecraft:stone_stairs["weirdo_direction"=2,"upside_down_bit"=true]"#
+        );
+        assert_eq!(BlockType::new("minecraft:air").to_string(), "minecraft:air");
+        let sign = BlockType::new("minecraft:standing_sign")
+            .set_state("odd \"name\\", &BlockState::String("a\"b".to_string()));
+        assert_eq!(
+            sign.to_string(),
+            r#"minecraft:standing_sign["odd \"name\\"="a\"b"]"#
+        );
+    }
+
+    #[test]
+    fn display_parse_round_trip() {
+        let blocks = [
+            BlockType::new("minecraft:air"),
+            stairs(),
+            BlockType::new("minecraft:log")
+                .set_state("pillar_axis", &BlockState::String("y".to_string()))
+                .set_state("old_log_type", &BlockState::String("oak".to_string())),
+            BlockType::new("minecraft:chest").set_state(
+                "minecraft:cardinal_direction",
+                &BlockState::String("north".to_string()),
+            ),
+            BlockType::new("minecraft:wool")
+                .set_state("color", &BlockState::String("true".to_string()))
+                .set_state("value", &BlockState::String("12".to_string())),
+            BlockType::new("mymod:thing")
+                .set_state("odd \"name\\", &BlockState::String("a,b]".to_string()))
+                .set_state("negative", &BlockState::Int(-3))
+                .set_state("off", &BlockState::Bool(0)),
+        ];
+        for block in blocks {
+            assert_eq!(block.to_string().parse::<BlockType>(), Ok(block));
+        }
+    }
+
+    #[test]
+    fn parse_java_and_old_bedrock_syntax() {
+        assert_eq!(
+            "oak_stairs[facing=north, half = top ,waterlogged=false]".parse::<BlockType>(),
+            Ok(BlockType::new("minecraft:oak_stairs")
+                .set_state("facing", &BlockState::String("north".to_string()))
+                .set_state("half", &BlockState::String("top".to_string()))
+                .set_state("waterlogged", &BlockState::Bool(0)))
+        );
+        assert_eq!(
+            r#"stone_stairs["weirdo_direction":2,"upside_down_bit":true]"#.parse::<BlockType>(),
+            Ok(stairs())
+        );
+        assert_eq!(
+            " minecraft:stone [ ] ".parse::<BlockType>(),
+            Ok(BlockType::new("minecraft:stone"))
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let reason = |input: &str| match input.parse::<BlockType>() {
+            Err(Error::InvalidBlockString { input: got, reason }) => {
+                assert_eq!(got, input);
+                reason
+            }
+            other => panic!("{:?} parsed as {:?}", input, other),
+        };
+        assert_eq!(reason(""), "expected a name or value");
+        assert_eq!(reason("stone[facing=north"), "unexpected end");
+        assert_eq!(reason("stone[facing]"), "unexpected ']'");
+        assert_eq!(reason(r#"stone["facing=north]"#), "unterminated string");
+        assert_eq!(reason("stone[facing=]"), "expected a name or value");
+        assert_eq!(reason("stone dirt"), "unexpected text after the block");
+    }
+}
@@ -0,0 +1,451 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Error,
+    nbt::{TagData, TagKind},
+    structure::MCStructure,
+    types::{Block, BlockState, BlockType, Cardinal, Facing, Vec3, block_entity_or_insert},
+};
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum CommandBlockKind {
+    Impulse,
+    Chain,
+    Repeating,
+}
+
+impl CommandBlockKind {
+    pub fn type_id(self) -> &'static str {
+        match self {
+            CommandBlockKind::Impulse => "minecraft:command_block",
+            CommandBlockKind::Chain => "minecraft:chain_command_block",
+            CommandBlockKind::Repeating => "minecraft:repeating_command_block",
+        }
+    }
+    pub fn from_type_id(type_id: &str) -> Option<CommandBlockKind> {
+        [
+            CommandBlockKind::Impulse,
+            CommandBlockKind::Chain,
+            CommandBlockKind::Repeating,
+        ]
+        .into_iter()
+        .find(|kind| kind.type_id() == type_id)
+    }
+    /// Value of `LPCommandMode`
+    fn command_mode(self) -> i32 {
+        match self {
+            CommandBlockKind::Impulse => 0,
+            CommandBlockKind::Repeating => 1,
+            CommandBlockKind::Chain => 2,
+        }
+    }
+    fn from_command_mode(mode: i32) -> Option<CommandBlockKind> {
+        match mode {
+            0 => Some(CommandBlockKind::Impulse),
+            1 => Some(CommandBlockKind::Repeating),
+            2 => Some(CommandBlockKind::Chain),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommandBlock {
+    pub kind: CommandBlockKind,
+    pub command: String,
+    pub custom_name: String,
+    /// "Always Active" instead of "Needs Redstone"
+    pub auto: bool,
+    pub conditional: bool,
+    pub tick_delay: i32,
+    pub execute_on_first_tick: bool,
+    pub track_output: bool,
+    /// Command version the command was written for. The game picks its own if this is `None`.
+    pub version: Option<i32>,
+}
+
+impl CommandBlock {
+    pub fn new(kind: CommandBlockKind, command: &str) -> Self {
+        CommandBlock {
+            kind,
+            command: command.to_string(),
+            custom_name: String::new(),
+            auto: kind == CommandBlockKind::Chain,
+            conditional: false,
+            tick_delay: 0,
+            execute_on_first_tick: false,
+            track_output: true,
+            version: None,
+        }
+    }
+    pub fn set_custom_name(mut self, name: &str) -> Self {
+        self.custom_name = name.to_string();
+        self
+    }
+    pub fn set_auto(mut self, auto: bool) -> Self {
+        self.auto = auto;
+        self
+    }
+    pub fn set_conditional(mut self, conditional: bool) -> Self {
+        self.conditional = conditional;
+        self
+    }
+    pub fn set_tick_delay(mut self, tick_delay: i32) -> Self {
+        self.tick_delay = tick_delay;
+        self
+    }
+    pub fn set_execute_on_first_tick(mut self, execute_on_first_tick: bool) -> Self {
+        self.execute_on_first_tick = execute_on_first_tick;
+        self
+    }
+    pub fn set_track_output(mut self, track_output: bool) -> Self {
+        self.track_output = track_output;
+        self
+    }
+    pub fn set_version(mut self, version: i32) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// The command block with its `facing_direction` and `conditional_bit` states
+    pub fn block_type(&self, facing: Facing) -> BlockType {
+        BlockType::new(self.kind.type_id())
+            .set_state(
+                "facing_direction",
+                &BlockState::Int(facing.facing_direction()),
+            )
+            .set_state("conditional_bit", &BlockState::Bool(self.conditional as u8))
+    }
+
+    /// Reads the settings of a `CommandBlock` block entity. The kind comes from
+    /// `LPCommandMode`, as the block entity does not know which block it belongs to.
+    pub(crate) fn from_tag(data: &TagData) -> Option<CommandBlock> {
+        let byte = |id: &str| match data.find_tag(id) {
+            Some(TagData::Byte(value)) => Some(*value != 0),
+            _ => None,
+        };
+        let (
+            Some(TagData::Int(mode)),
+            Some(TagData::String(command)),
+            Some(TagData::String(custom_name)),
+            Some(TagData::Int(tick_delay)),
+        ) = (
+            data.find_tag("LPCommandMode"),
+            data.find_tag("Command"),
+            data.find_tag("CustomName"),
+            data.find_tag("TickDelay"),
+        )
+        else {
+            return None;
+        };
+        Some(CommandBlock {
+            kind: CommandBlockKind::from_command_mode(*mode)?,
+            command: command.clone(),
+            custom_name: custom_name.clone(),
+            auto: byte("auto")?,
+            conditional: byte("conditionalMode")?,
+            tick_delay: *tick_delay,
+            execute_on_first_tick: byte("ExecuteOnFirstTick")?,
+            track_output: byte("TrackOutput")?,
+            version: match data.find_tag("Version") {
+                Some(TagData::Int(version)) => Some(*version),
+                _ => None,
+            },
+        })
+    }
+
+    /// Writes the settings, keeping the last output and powered state of the block entity
+    pub(crate) fn write_settings(&self, data: &mut TagData) {
+        data.set_tag("Command", TagData::String(self.command.clone()));
+        data.set_tag("CustomName", TagData::String(self.custom_name.clone()));
+        data.set_tag("auto", TagData::Byte(self.auto as i8));
+        data.set_tag("conditionalMode", TagData::Byte(self.conditional as i8));
+        data.set_tag("TickDelay", TagData::Int(self.tick_delay));
+        data.set_tag(
+            "ExecuteOnFirstTick",
+            TagData::Byte(self.execute_on_first_tick as i8),
+        );
+        data.set_tag("TrackOutput", TagData::Byte(self.track_output as i8));
+        data.set_tag("LPCommandMode", TagData::Int(self.kind.command_mode()));
+        match self.version {
+            Some(version) => data.set_tag("Version", TagData::Int(version)),
+            None => {
+                data.remove_tag("Version");
+            }
+        }
+    }
+    fn write_tags(&self, data: &mut TagData) {
+        self.write_settings(data);
+        data.set_tag("LPConditionalMode", TagData::Byte(self.conditional as i8));
+        data.set_tag("LPRedstoneMode", TagData::Byte(!self.auto as i8));
+        data.set_tag("LastOutput", TagData::String(String::new()));
+        data.set_tag(
+            "LastOutputParams",
+            TagData::List(TagKind::String, 0, vec![]),
+        );
+        data.set_tag("SuccessCount", TagData::Int(0));
+    }
+}
+
+pub(crate) fn set_command_block_of_block(
+    structure: &mut MCStructure,
+    permutation: &BlockType,
+    index: u32,
+    command_block: &CommandBlock,
+) -> Result<(), Error> {
+    if CommandBlockKind::from_type_id(&permutation.type_id).is_none() {
+        return Err(Error::UnexpectedBlock {
+            type_id: permutation.type_id.clone(),
+            expected: "command block",
+        });
+    }
+    let data = block_entity_or_insert(structure, index, "CommandBlock", vec![])?;
+    command_block.write_tags(data);
+    Ok(())
+}
+
+impl<'a> Block<'a> {
+    /// Writes the command block settings. The block states are not changed, use
+    /// `CommandBlock::block_type` or `MCStructure::place_command_block` to get them right.
+    pub fn set_command_block(self, command_block: &CommandBlock) -> Result<Self, Error> {
+        set_command_block_of_block(self.structure, &self.permutation, self.index, command_block)?;
+        Ok(self)
+    }
+}
+
+/// How `MCStructure::place_command_chain` arranges command blocks
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ChainLayout {
+    /// Every block in a straight line
+    Line(Facing),
+    /// Rows of `row_length` blocks along `direction`, each row stepping towards `turn` and
+    /// running back the opposite way
+    Snake {
+        direction: Cardinal,
+        turn: Facing,
+        row_length: u32,
+    },
+}
+
+impl ChainLayout {
+    /// Rejects a snake whose `turn` is not perpendicular to its `direction`
+    fn check(self) -> Result<(), Error> {
+        if let ChainLayout::Snake {
+            direction, turn, ..
+        } = self
+        {
+            let (a, b) = (direction.offset(), turn.offset());
+            if a.x() * b.x() + a.y() * b.y() + a.z() * b.z() != 0 {
+                return Err(Error::InvalidChainLayout { direction, turn });
+            }
+        }
+        Ok(())
+    }
+    fn position(self, start: Vec3<i32>, i: u32) -> Vec3<i32> {
+        let scale = |v: Vec3<i32>, n: i32| Vec3::new(v.x() * n, v.y() * n, v.z() * n);
+        match self {
+            ChainLayout::Line(facing) => start + scale(facing.offset(), i as i32),
+            ChainLayout::Snake {
+                direction,
+                turn,
+                row_length,
+            } => {
+                let row_length = row_length.max(1);
+                let (row, col) = (i / row_length, i % row_length);
+                let col = if row % 2 == 0 {
+                    col
+                } else {
+                    row_length - 1 - col
+                };
+                start + scale(direction.offset(), col as i32) + scale(turn.offset(), row as i32)
+            }
+        }
+    }
+}
+
+impl MCStructure {
+    pub fn place_command_block(
+        &mut self,
+        loc: Vec3<i32>,
+        facing: Facing,
+        command_block: &CommandBlock,
+    ) -> Result<Block<'_>, Error> {
+        self.setblock(loc, command_block.block_type(facing))?
+            .set_command_block(command_block)
+    }
+    /// Places command blocks one after another from `start`, each facing the next one.
+    /// Usually the first block is impulse or repeating and the rest are chain blocks.
+    /// Returns the position of every block. Nothing is placed if a block would fall outside
+    /// of the structure.
+    pub fn place_command_chain(
+        &mut self,
+        start: Vec3<i32>,
+        command_blocks: &[CommandBlock],
+        layout: ChainLayout,
+    ) -> Result<Vec<Vec3<i32>>, Error> {
+        layout.check()?;
+        let positions: Vec<Vec3<i32>> = (0..command_blocks.len() as u32 + 1)
+            .map(|i| layout.position(start, i))
+            .collect();
+        for loc in &positions[..command_blocks.len()] {
+            self.check_bounds(*loc)?;
+        }
+        for (i, command_block) in command_blocks.iter().enumerate() {
+            let facing = Facing::from_offset(positions[i + 1] - positions[i])
+                .expect("consecutive chain positions are adjacent");
+            self.place_command_block(positions[i], facing, command_block)?;
+        }
+        Ok(positions[..command_blocks.len()].to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BlockEntity;
+
+    fn facing_at(structure: &MCStructure, loc: Vec3<i32>) -> Option<Facing> {
+        let block = structure.getblock(loc);
+        match block
+            .states
+            .iter()
+            .find(|(state, _)| state == "facing_direction")
+        {
+            Some((_, BlockState::Int(value))) => Facing::from_facing_direction(*value),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn place_command_block_round_trips() {
+        let mut structure = MCStructure::new(Vec3::new(1, 1, 1));
+        let command_block = CommandBlock::new(CommandBlockKind::Repeating, "say hi")
+            .set_custom_name("greeter")
+            .set_auto(true)
+            .set_conditional(true)
+            .set_tick_delay(20)
+            .set_version(38);
+        structure
+            .place_command_block(Vec3::new(0, 0, 0), Facing::Up, &command_block)
+            .unwrap();
+
+        let block = structure.getblock(Vec3::new(0, 0, 0));
+        assert_eq!(block.type_id, "minecraft:repeating_command_block");
+        assert!(
+            block
+                .states
+                .contains(&("conditional_bit".to_string(), BlockState::Bool(1)))
+        );
+        assert_eq!(facing_at(&structure, Vec3::new(0, 0, 0)), Some(Facing::Up));
+        let Some(BlockEntity::CommandBlock(read, _)) = structure.block_entity(Vec3::new(0, 0, 0))
+        else {
+            panic!("expected a command block");
+        };
+        assert_eq!(read, command_block);
+    }
+
+    #[test]
+    fn chain_blocks_are_always_active() {
+        assert!(CommandBlock::new(CommandBlockKind::Chain, "").auto);
+        assert!(!CommandBlock::new(CommandBlockKind::Impulse, "").auto);
+        assert_eq!(
+            CommandBlockKind::from_type_id("minecraft:chain_command_block"),
+            Some(CommandBlockKind::Chain)
+        );
+    }
+
+    #[test]
+    fn line_chain() {
+        let mut structure = MCStructure::new(Vec3::new(4, 1, 1));
+        let chain = [
+            CommandBlock::new(CommandBlockKind::Impulse, "say 1"),
+            CommandBlock::new(CommandBlockKind::Chain, "say 2"),
+            CommandBlock::new(CommandBlockKind::Chain, "say 3"),
+        ];
+        let positions = structure
+            .place_command_chain(Vec3::new(1, 0, 0), &chain, ChainLayout::Line(Facing::East))
+            .unwrap();
+
+        assert_eq!(
+            positions,
+            vec![Vec3::new(1, 0, 0), Vec3::new(2, 0, 0), Vec3::new(3, 0, 0)]
+        );
+        for loc in positions {
+            assert_eq!(facing_at(&structure, loc), Some(Facing::East));
+        }
+        assert_eq!(
+            structure.getblock(Vec3::new(2, 0, 0)).type_id,
+            "minecraft:chain_command_block"
+        );
+    }
+
+    #[test]
+    fn snake_chain() {
+        let mut structure = MCStructure::new(Vec3::new(2, 2, 1));
+        let chain = vec![CommandBlock::new(CommandBlockKind::Chain, "say"); 4];
+        let layout = ChainLayout::Snake {
+            direction: Cardinal::East,
+            turn: Facing::Up,
+            row_length: 2,
+        };
+        let positions = structure
+            .place_command_chain(Vec3::new(0, 0, 0), &chain, layout)
+            .unwrap();
+
+        assert_eq!(
+            positions,
+            vec![
+                Vec3::new(0, 0, 0),
+                Vec3::new(1, 0, 0),
+                Vec3::new(1, 1, 0),
+                Vec3::new(0, 1, 0),
+            ]
+        );
+        let facings: Vec<_> = positions
+            .iter()
+            .map(|loc| facing_at(&structure, *loc).unwrap())
+            .collect();
+        assert_eq!(
+            facings,
+            vec![Facing::East, Facing::Up, Facing::West, Facing::Up]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_chains() {
+        let mut structure = MCStructure::new(Vec3::new(2, 1, 1));
+        let chain = vec![CommandBlock::new(CommandBlockKind::Chain, "say"); 3];
+        assert_eq!(
+            structure.place_command_chain(
+                Vec3::new(0, 0, 0),
+                &chain,
+                ChainLayout::Snake {
+                    direction: Cardinal::East,
+                    turn: Facing::West,
+                    row_length: 2,
+                },
+            ),
+            Err(Error::InvalidChainLayout {
+                direction: Cardinal::East,
+                turn: Facing::West,
+            })
+        );
+        assert!(matches!(
+            structure.place_command_chain(
+                Vec3::new(0, 0, 0),
+                &chain,
+                ChainLayout::Line(Facing::East)
+            ),
+            Err(Error::OutOfBounds { .. })
+        ));
+        assert!(structure.iter().next().is_none());
+
+        assert!(matches!(
+            structure
+                .setblock(Vec3::new(0, 0, 0), BlockType::new("minecraft:stone"))
+                .unwrap()
+                .set_command_block(&chain[0]),
+            Err(Error::UnexpectedBlock { .. })
+        ));
+    }
+}
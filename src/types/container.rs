@@ -0,0 +1,231 @@
+use crate::{
+    nbt::{TagData, TagKind},
+    types::{BlockType, set_tag_unless_default},
+};
+
+/// Block entity details of a block that can hold items
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ContainerInfo {
+    /// The `id` written in the block entity, e.g. `Chest`
+    pub block_entity_id: &'static str,
+    pub slots: u8,
+}
+
+/// Every `id` a container block entity can have
+pub(crate) const CONTAINER_BLOCK_ENTITY_IDS: [&str; 12] = [
+    "Chest",
+    "Barrel",
+    "Hopper",
+    "Dropper",
+    "Dispenser",
+    "Furnace",
+    "BlastFurnace",
+    "Smoker",
+    "BrewingStand",
+    "Crafter",
+    "DecoratedPot",
+    "ShulkerBox",
+];
+
+const fn container(block_entity_id: &'static str, slots: u8) -> Option<ContainerInfo> {
+    Some(ContainerInfo {
+        block_entity_id,
+        slots,
+    })
+}
+
+impl BlockType {
+    pub fn container_info(&self) -> Option<ContainerInfo> {
+        let name = self
+            .type_id
+            .strip_prefix("minecraft:")
+            .unwrap_or(&self.type_id);
+        match name {
+            "chest" | "trapped_chest" => container("Chest", 27),
+            "barrel" => container("Barrel", 27),
+            "hopper" => container("Hopper", 5),
+            "dropper" => container("Dropper", 9),
+            "dispenser" => container("Dispenser", 9),
+            "furnace" | "lit_furnace" => container("Furnace", 3),
+            "blast_furnace" | "lit_blast_furnace" => container("BlastFurnace", 3),
+            "smoker" | "lit_smoker" => container("Smoker", 3),
+            "brewing_stand" => container("BrewingStand", 5),
+            "crafter" => container("Crafter", 9),
+            "decorated_pot" => container("DecoratedPot", 1),
+            _ if name.ends_with("shulker_box") => container("ShulkerBox", 27),
+            _ => None,
+        }
+    }
+    pub fn is_container(&self) -> bool {
+        self.container_info().is_some()
+    }
+}
+
+/// Typed data of a container block entity
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Container {
+    /// Item compounds, each with its `Slot`
+    pub items: Vec<TagData>,
+    /// Loot table path and seed
+    pub loot_table: Option<(String, i32)>,
+    pub custom_name: Option<String>,
+}
+
+impl Container {
+    pub(crate) fn from_tag(data: &TagData) -> Option<Container> {
+        let items = match data.find_tag("Items") {
+            Some(TagData::List(_, _, items)) => items.clone(),
+            // containers that only have a loot table may have no items yet
+            None => vec![],
+            Some(_) => return None,
+        };
+        Some(Container {
+            items,
+            loot_table: match (data.find_tag("LootTable"), data.find_tag("LootTableSeed")) {
+                (Some(TagData::String(path)), Some(TagData::Int(seed))) => {
+                    Some((path.clone(), *seed))
+                }
+                _ => None,
+            },
+            custom_name: match data.find_tag("CustomName") {
+                Some(TagData::String(name)) => Some(name.clone()),
+                _ => None,
+            },
+        })
+    }
+    pub(crate) fn write_tags(&self, data: &mut TagData) {
+        // empty lists keep whatever element kind they were saved with
+        let kind = match data.find_tag("Items") {
+            Some(TagData::List(kind, _, _)) if self.items.is_empty() => kind.clone(),
+            _ => TagKind::Compound,
+        };
+        set_tag_unless_default(
+            data,
+            "Items",
+            TagData::List(kind.clone(), self.items.len() as u32, self.items.clone()),
+            TagData::List(kind, 0, vec![]),
+        );
+        match &self.loot_table {
+            Some((path, seed)) => {
+                data.set_tag("LootTable", TagData::String(path.clone()));
+                data.set_tag("LootTableSeed", TagData::Int(*seed));
+            }
+            None => {
+                data.remove_tag("LootTable");
+                data.remove_tag("LootTableSeed");
+            }
+        }
+        match &self.custom_name {
+            Some(name) => data.set_tag("CustomName", TagData::String(name.clone())),
+            None => {
+                data.remove_tag("CustomName");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::Error,
+        nbt::TagList,
+        structure::MCStructure,
+        types::{BlockEntity, ItemStack, Vec3},
+    };
+
+    #[test]
+    fn container_slots() {
+        let slots = |type_id: &str| BlockType::new(type_id).container_info().map(|c| c.slots);
+        assert_eq!(slots("minecraft:chest"), Some(27));
+        assert_eq!(slots("minecraft:hopper"), Some(5));
+        assert_eq!(slots("minecraft:dispenser"), Some(9));
+        assert_eq!(slots("minecraft:lit_smoker"), Some(3));
+        assert_eq!(slots("minecraft:decorated_pot"), Some(1));
+        assert_eq!(slots("minecraft:red_shulker_box"), Some(27));
+        assert_eq!(slots("minecraft:stone"), None);
+        assert_eq!(
+            BlockType::new("minecraft:trapped_chest")
+                .container_info()
+                .unwrap()
+                .block_entity_id,
+            "Chest"
+        );
+    }
+
+    #[test]
+    fn rejects_bad_slots() {
+        let mut structure = MCStructure::new(Vec3::new(2, 1, 1));
+        let hopper = BlockType::new("minecraft:hopper");
+        let item = ItemStack::new("minecraft:dirt", 1).unwrap();
+        assert!(
+            structure
+                .setblock(Vec3::new(0, 0, 0), hopper.clone())
+                .unwrap()
+                .set_item_slot(4, &item)
+                .is_ok()
+        );
+        assert_eq!(
+            structure
+                .setblock(Vec3::new(0, 0, 0), hopper)
+                .unwrap()
+                .set_item_slot(5, &item)
+                .err(),
+            Some(Error::InvalidSlot {
+                type_id: "minecraft:hopper".to_string(),
+                slot: 5,
+                slots: 5,
+            })
+        );
+        assert_eq!(
+            structure
+                .setblock(Vec3::new(1, 0, 0), BlockType::new("minecraft:stone"))
+                .unwrap()
+                .set_item_slot(0, &item)
+                .err(),
+            Some(Error::NotAContainer("minecraft:stone".to_string()))
+        );
+    }
+
+    #[test]
+    fn set_item_slot_replaces_the_slot() {
+        let mut structure = MCStructure::new(Vec3::new(1, 1, 1));
+        structure
+            .setblock(Vec3::new(0, 0, 0), BlockType::new("minecraft:barrel"))
+            .unwrap()
+            .set_item_slot(3, &ItemStack::new("minecraft:dirt", 1).unwrap())
+            .unwrap()
+            .set_item_slot(3, &ItemStack::new("minecraft:stone", 2).unwrap())
+            .unwrap();
+
+        let Some(BlockEntity::Container(container, _)) = structure.block_entity(Vec3::new(0, 0, 0))
+        else {
+            panic!("expected a container");
+        };
+        assert_eq!(
+            container.items,
+            vec![ItemStack::new("minecraft:stone", 2).unwrap().as_slot_tag(3)]
+        );
+    }
+
+    #[test]
+    fn loot_only_containers_are_typed() {
+        let data = TagData::Compound(TagList::from(vec![
+            ("id".to_string(), TagData::String("Chest".to_string())),
+            (
+                "LootTable".to_string(),
+                TagData::String("loot_tables/chests/igloo_chest.json".to_string()),
+            ),
+            ("LootTableSeed".to_string(), TagData::Int(5)),
+        ]));
+        let BlockEntity::Container(container, _) = BlockEntity::from_tag(&data) else {
+            panic!("expected a container");
+        };
+        assert!(container.items.is_empty());
+        assert_eq!(
+            container.loot_table,
+            Some(("loot_tables/chests/igloo_chest.json".to_string(), 5))
+        );
+        assert_eq!(BlockEntity::from_tag(&data).as_tag(), data);
+    }
+}
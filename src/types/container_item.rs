@@ -0,0 +1,260 @@
+use crate::{
+    error::Error,
+    nbt::{TagData, TagKind},
+    types::{ItemStack, item::max_stack_size},
+};
+
+pub const SHULKER_BOX_SLOTS: u8 = 27;
+pub const BUNDLE_CAPACITY: u32 = 64;
+
+/// Tag holding the contents of a bundle item
+const BUNDLE_CONTENTS: &str = "storage_item_component_content";
+
+pub(crate) fn is_shulker_box(type_id: &str) -> bool {
+    type_id.ends_with("shulker_box")
+}
+
+fn is_bundle(type_id: &str) -> bool {
+    type_id.ends_with("bundle")
+}
+
+/// Space an item takes up in a bundle: 64 divided by how many of it stack, times its count.
+/// Bundles count as 4 plus their contents.
+fn bundle_weight(item: &ItemStack) -> u32 {
+    item_weight(
+        &item.type_id,
+        item.count,
+        item.extra_tags.find_tag(BUNDLE_CONTENTS),
+    )
+}
+
+fn item_weight(type_id: &str, count: u8, contents: Option<&TagData>) -> u32 {
+    let weight = if is_bundle(type_id) {
+        4 + match contents {
+            Some(TagData::List(_, _, contents)) => contents.iter().map(tag_weight).sum(),
+            _ => 0,
+        }
+    } else {
+        BUNDLE_CAPACITY / max_stack_size(type_id) as u32
+    };
+    weight * count as u32
+}
+
+/// Weight of an item compound inside a bundle
+fn tag_weight(item: &TagData) -> u32 {
+    let (Some(TagData::String(type_id)), Some(TagData::Byte(count))) =
+        (item.find_tag("Name"), item.find_tag("Count"))
+    else {
+        return 0;
+    };
+    let contents = item
+        .find_tag("tag")
+        .and_then(|tag| tag.find_tag(BUNDLE_CONTENTS));
+    item_weight(type_id, *count as u8, contents)
+}
+
+fn item_list(items: Vec<TagData>) -> TagData {
+    TagData::List(TagKind::Compound, items.len() as u32, items)
+}
+
+/// Builds a shulker box item with items inside
+pub struct ShulkerBoxItem {
+    type_id: String,
+    items: Vec<(u8, ItemStack)>,
+}
+
+impl ShulkerBoxItem {
+    pub fn new(type_id: &str) -> Result<Self, Error> {
+        if !is_shulker_box(type_id) {
+            return Err(Error::NotAContainer(type_id.to_string()));
+        }
+        Ok(ShulkerBoxItem {
+            type_id: type_id.to_string(),
+            items: vec![],
+        })
+    }
+    pub fn set_item_slot(mut self, slot: u8, item: ItemStack) -> Result<Self, Error> {
+        if slot >= SHULKER_BOX_SLOTS {
+            return Err(Error::InvalidSlot {
+                type_id: self.type_id,
+                slot,
+                slots: SHULKER_BOX_SLOTS,
+            });
+        }
+        if is_shulker_box(&item.type_id) {
+            return Err(Error::InvalidNesting {
+                item: item.type_id,
+                container: self.type_id,
+            });
+        }
+        self.items.retain(|(existing, _)| *existing != slot);
+        self.items.push((slot, item));
+        Ok(self)
+    }
+    pub fn build(self) -> ItemStack {
+        let items = self
+            .items
+            .iter()
+            .map(|(slot, item)| item.as_slot_tag(*slot))
+            .collect();
+        ItemStack::new(&self.type_id, 1)
+            .expect("one item always fits in a slot")
+            .add_tag("Items", item_list(items))
+    }
+}
+
+/// Builds a bundle item with items inside
+pub struct BundleItem {
+    type_id: String,
+    items: Vec<ItemStack>,
+}
+
+impl BundleItem {
+    pub fn new(type_id: &str) -> Result<Self, Error> {
+        if !is_bundle(type_id) {
+            return Err(Error::NotAContainer(type_id.to_string()));
+        }
+        Ok(BundleItem {
+            type_id: type_id.to_string(),
+            items: vec![],
+        })
+    }
+    pub fn add_item(mut self, item: ItemStack) -> Result<Self, Error> {
+        if is_shulker_box(&item.type_id) {
+            return Err(Error::InvalidNesting {
+                item: item.type_id,
+                container: self.type_id,
+            });
+        }
+        let weight = self.items.iter().map(bundle_weight).sum::<u32>() + bundle_weight(&item);
+        if weight > BUNDLE_CAPACITY {
+            return Err(Error::BundleFull {
+                weight,
+                capacity: BUNDLE_CAPACITY,
+            });
+        }
+        self.items.push(item);
+        Ok(self)
+    }
+    pub fn build(self) -> ItemStack {
+        let items = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(slot, item)| item.as_slot_tag(slot as u8))
+            .collect();
+        ItemStack::new(&self.type_id, 1)
+            .expect("one item always fits in a slot")
+            .add_tag(BUNDLE_CONTENTS, item_list(items))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        structure::MCStructure,
+        types::{BlockType, Vec3},
+    };
+
+    fn item(type_id: &str, count: u8) -> ItemStack {
+        ItemStack::new(type_id, count).unwrap()
+    }
+
+    #[test]
+    fn shulker_box_contents() {
+        let shulker_box = ShulkerBoxItem::new("minecraft:red_shulker_box")
+            .unwrap()
+            .set_item_slot(26, item("minecraft:dirt", 5))
+            .unwrap()
+            .build();
+        let Some(TagData::List(_, 1, items)) = shulker_box.extra_tags.find_tag("Items") else {
+            panic!("expected one item");
+        };
+        assert_eq!(items[0], item("minecraft:dirt", 5).as_slot_tag(26));
+    }
+
+    #[test]
+    fn shulker_box_rejects_bad_items() {
+        let shulker_box = || ShulkerBoxItem::new("minecraft:shulker_box").unwrap();
+        assert!(matches!(
+            shulker_box().set_item_slot(27, item("minecraft:dirt", 1)),
+            Err(Error::InvalidSlot { slot: 27, .. })
+        ));
+        assert!(matches!(
+            shulker_box().set_item_slot(0, item("minecraft:blue_shulker_box", 1)),
+            Err(Error::InvalidNesting { .. })
+        ));
+        assert!(matches!(
+            ShulkerBoxItem::new("minecraft:chest"),
+            Err(Error::NotAContainer(_))
+        ));
+    }
+
+    #[test]
+    fn bundle_capacity() {
+        let bundle = || BundleItem::new("minecraft:bundle").unwrap();
+        assert!(bundle().add_item(item("minecraft:dirt", 64)).is_ok());
+        assert!(bundle().add_item(item("minecraft:ender_pearl", 16)).is_ok());
+        assert!(
+            bundle()
+                .add_item(item("minecraft:dirt", 60))
+                .unwrap()
+                .add_item(item("minecraft:ender_pearl", 1))
+                .is_ok()
+        );
+        assert_eq!(
+            bundle()
+                .add_item(item("minecraft:dirt", 61))
+                .unwrap()
+                .add_item(item("minecraft:ender_pearl", 1))
+                .err(),
+            Some(Error::BundleFull {
+                weight: 65,
+                capacity: BUNDLE_CAPACITY,
+            })
+        );
+        assert!(matches!(
+            bundle().add_item(item("minecraft:shulker_box", 1)),
+            Err(Error::InvalidNesting { .. })
+        ));
+    }
+
+    #[test]
+    fn nested_bundles_weigh_their_contents() {
+        let inner = BundleItem::new("minecraft:bundle")
+            .unwrap()
+            .add_item(item("minecraft:dirt", 32))
+            .unwrap()
+            .build();
+        assert_eq!(bundle_weight(&inner), 36);
+        let outer = BundleItem::new("minecraft:bundle")
+            .unwrap()
+            .add_item(inner)
+            .unwrap();
+        assert!(outer.add_item(item("minecraft:dirt", 29)).is_err());
+    }
+
+    #[test]
+    fn shulker_boxes_go_in_chests_but_not_shulker_boxes() {
+        let mut structure = MCStructure::new(Vec3::new(2, 1, 1));
+        let shulker_box = ShulkerBoxItem::new("minecraft:shulker_box")
+            .unwrap()
+            .build();
+        assert!(
+            structure
+                .setblock(Vec3::new(0, 0, 0), BlockType::new("minecraft:chest"))
+                .unwrap()
+                .set_item_slot(0, &shulker_box)
+                .is_ok()
+        );
+        assert!(matches!(
+            structure
+                .setblock(Vec3::new(1, 0, 0), BlockType::new("minecraft:shulker_box"))
+                .unwrap()
+                .set_item_slot(0, &shulker_box)
+                .err(),
+            Some(Error::InvalidNesting { .. })
+        ));
+    }
+}
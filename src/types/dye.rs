@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum DyeColor {
+    White,
+    Orange,
+    Magenta,
+    LightBlue,
+    Yellow,
+    Lime,
+    Pink,
+    Gray,
+    LightGray,
+    Cyan,
+    Purple,
+    Blue,
+    Brown,
+    Green,
+    Red,
+    Black,
+}
+
+impl DyeColor {
+    pub const ALL: [DyeColor; 16] = [
+        DyeColor::White,
+        DyeColor::Orange,
+        DyeColor::Magenta,
+        DyeColor::LightBlue,
+        DyeColor::Yellow,
+        DyeColor::Lime,
+        DyeColor::Pink,
+        DyeColor::Gray,
+        DyeColor::LightGray,
+        DyeColor::Cyan,
+        DyeColor::Purple,
+        DyeColor::Blue,
+        DyeColor::Brown,
+        DyeColor::Green,
+        DyeColor::Red,
+        DyeColor::Black,
+    ];
+
+    /// Name used in block ids, e.g. `light_blue` in `minecraft:light_blue_wool`
+    pub fn name(self) -> &'static str {
+        match self {
+            DyeColor::White => "white",
+            DyeColor::Orange => "orange",
+            DyeColor::Magenta => "magenta",
+            DyeColor::LightBlue => "light_blue",
+            DyeColor::Yellow => "yellow",
+            DyeColor::Lime => "lime",
+            DyeColor::Pink => "pink",
+            DyeColor::Gray => "gray",
+            DyeColor::LightGray => "light_gray",
+            DyeColor::Cyan => "cyan",
+            DyeColor::Purple => "purple",
+            DyeColor::Blue => "blue",
+            DyeColor::Brown => "brown",
+            DyeColor::Green => "green",
+            DyeColor::Red => "red",
+            DyeColor::Black => "black",
+        }
+    }
+    pub fn from_name(name: &str) -> Option<DyeColor> {
+        DyeColor::ALL.into_iter().find(|color| color.name() == name)
+    }
+    /// Color code used by banners, in banner `Base`, pattern `Color` and banner item `Damage`.
+    /// Runs the other way round from the usual order, black is 0 and white is 15.
+    pub fn banner_color(self) -> i32 {
+        15 - DyeColor::ALL
+            .iter()
+            .position(|color| *color == self)
+            .unwrap() as i32
+    }
+    pub fn from_banner_color(color: i32) -> Option<DyeColor> {
+        DyeColor::ALL
+            .get(usize::try_from(15 - color).ok()?)
+            .copied()
+    }
+    /// ARGB color the game writes when dyeing sign text
+    pub fn argb(self) -> u32 {
+        match self {
+            DyeColor::White => 0xFFF0F0F0,
+            DyeColor::Orange => 0xFFF9801D,
+            DyeColor::Magenta => 0xFFC74EBD,
+            DyeColor::LightBlue => 0xFF3AB3DA,
+            DyeColor::Yellow => 0xFFFED83D,
+            DyeColor::Lime => 0xFF80C71F,
+            DyeColor::Pink => 0xFFF38BAA,
+            DyeColor::Gray => 0xFF474F52,
+            DyeColor::LightGray => 0xFF9D9D97,
+            DyeColor::Cyan => 0xFF169C9C,
+            DyeColor::Purple => 0xFF8932B8,
+            DyeColor::Blue => 0xFF3C44AA,
+            DyeColor::Brown => 0xFF835432,
+            DyeColor::Green => 0xFF5E7C16,
+            DyeColor::Red => 0xFFB02E26,
+            DyeColor::Black => 0xFF1D1D21,
+        }
+    }
+}
@@ -0,0 +1,413 @@
+use crate::{
+    error::Error,
+    nbt::{TagData, TagKind, TagList},
+    types::{BlockType, DEFAULT_BLOCK_VERSION},
+};
+
+/// Bedrock enchantment ids
+#[repr(i16)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Enchantment {
+    Protection,
+    FireProtection,
+    FeatherFalling,
+    BlastProtection,
+    ProjectileProtection,
+    Thorns,
+    Respiration,
+    DepthStrider,
+    AquaAffinity,
+    Sharpness,
+    Smite,
+    BaneOfArthropods,
+    Knockback,
+    FireAspect,
+    Looting,
+    Efficiency,
+    SilkTouch,
+    Unbreaking,
+    Fortune,
+    Power,
+    Punch,
+    Flame,
+    Infinity,
+    LuckOfTheSea,
+    Lure,
+    FrostWalker,
+    Mending,
+    Binding,
+    Vanishing,
+    Impaling,
+    Riptide,
+    Loyalty,
+    Channeling,
+    Multishot,
+    Piercing,
+    QuickCharge,
+    SoulSpeed,
+    SwiftSneak,
+    WindBurst,
+    Density,
+    Breach,
+}
+
+/// Value of the `minecraft:item_lock` component
+#[repr(i8)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ItemLock {
+    InSlot = 1,
+    InInventory = 2,
+}
+
+/// Items that do not stack, by the end of their id
+const UNSTACKABLE_SUFFIXES: [&str; 24] = [
+    "_sword",
+    "_pickaxe",
+    "_axe",
+    "_shovel",
+    "_hoe",
+    "_spear",
+    "_helmet",
+    "_chestplate",
+    "_leggings",
+    "_boots",
+    "_horse_armor",
+    "_harness",
+    "_bucket",
+    "boat",
+    "_raft",
+    "minecart",
+    "potion",
+    "_stew",
+    "_soup",
+    "_bed",
+    "_book",
+    "shulker_box",
+    "bundle",
+    "_banner_pattern",
+];
+
+/// Items that do not stack, by their id without namespace
+const UNSTACKABLE: [&str; 20] = [
+    "bow",
+    "crossbow",
+    "trident",
+    "mace",
+    "shield",
+    "fishing_rod",
+    "carrot_on_a_stick",
+    "warped_fungus_on_a_stick",
+    "flint_and_steel",
+    "shears",
+    "brush",
+    "spyglass",
+    "elytra",
+    "wolf_armor",
+    "saddle",
+    "bed",
+    "cake",
+    "totem_of_undying",
+    "goat_horn",
+    "debug_stick",
+];
+
+/// Items that stack to 16, by their id without namespace
+const STACKS_OF_16: [&str; 10] = [
+    "ender_pearl",
+    "snowball",
+    "egg",
+    "blue_egg",
+    "brown_egg",
+    "bucket",
+    "honey_bottle",
+    "armor_stand",
+    "written_book",
+    "banner",
+];
+
+/// Largest count of an item that fits in one slot
+pub(crate) fn max_stack_size(type_id: &str) -> u8 {
+    let name = type_id.strip_prefix("minecraft:").unwrap_or(type_id);
+    if STACKS_OF_16.contains(&name) || name.ends_with("_sign") || name.ends_with("_banner") {
+        16
+    } else if UNSTACKABLE.contains(&name)
+        || name.starts_with("music_disc_")
+        || UNSTACKABLE_SUFFIXES
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+    {
+        1
+    } else {
+        64
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ItemStack {
+    pub type_id: String,
+    pub count: u8,
+    /// Aux value, e.g. the color of wool in older versions
+    pub damage: i16,
+    /// Durability lost, stored as `tag.Damage`
+    pub durability_damage: Option<i32>,
+    pub custom_name: Option<String>,
+    pub lore: Vec<String>,
+    pub enchantments: Vec<(i16, i16)>,
+    pub can_place_on: Vec<String>,
+    pub can_destroy: Vec<String>,
+    pub keep_on_death: bool,
+    pub item_lock: Option<ItemLock>,
+    /// Block placed by this item
+    pub block: Option<BlockType>,
+    /// Extra tags added to the item's `tag` compound
+    pub extra_tags: TagList,
+}
+
+impl ItemStack {
+    /// Fails if `count` is 0 or more than fits in one slot, see `max_stack_size`
+    pub fn new(type_id: &str, count: u8) -> Result<Self, Error> {
+        let max = max_stack_size(type_id);
+        if count == 0 || count > max {
+            return Err(Error::InvalidItemCount {
+                type_id: type_id.to_string(),
+                count,
+                max,
+            });
+        }
+        Ok(ItemStack {
+            type_id: type_id.to_string(),
+            count,
+            damage: 0,
+            durability_damage: None,
+            custom_name: None,
+            lore: vec![],
+            enchantments: vec![],
+            can_place_on: vec![],
+            can_destroy: vec![],
+            keep_on_death: false,
+            item_lock: None,
+            block: None,
+            extra_tags: TagList::new(),
+        })
+    }
+    pub fn set_damage(mut self, damage: i16) -> Self {
+        self.damage = damage;
+        self
+    }
+    pub fn set_durability_damage(mut self, damage: i32) -> Self {
+        self.durability_damage = Some(damage);
+        self
+    }
+    pub fn set_custom_name(mut self, name: &str) -> Self {
+        self.custom_name = Some(name.to_string());
+        self
+    }
+    pub fn add_lore(mut self, line: &str) -> Self {
+        self.lore.push(line.to_string());
+        self
+    }
+    pub fn add_enchantment(self, enchantment: Enchantment, level: i16) -> Self {
+        self.add_enchantment_id(enchantment as i16, level)
+    }
+    pub fn add_enchantment_id(mut self, id: i16, level: i16) -> Self {
+        self.enchantments.retain(|(existing, _)| *existing != id);
+        self.enchantments.push((id, level));
+        self
+    }
+    pub fn add_can_place_on(mut self, type_id: &str) -> Self {
+        self.can_place_on.push(type_id.to_string());
+        self
+    }
+    pub fn add_can_destroy(mut self, type_id: &str) -> Self {
+        self.can_destroy.push(type_id.to_string());
+        self
+    }
+    pub fn set_keep_on_death(mut self, keep_on_death: bool) -> Self {
+        self.keep_on_death = keep_on_death;
+        self
+    }
+    pub fn set_item_lock(mut self, item_lock: ItemLock) -> Self {
+        self.item_lock = Some(item_lock);
+        self
+    }
+    pub fn set_block(mut self, block: BlockType) -> Self {
+        self.block = Some(block);
+        self
+    }
+    pub fn add_tag(mut self, id: &str, data: TagData) -> Self {
+        self.extra_tags.set_tag(id, data);
+        self
+    }
+    /// Largest count of this item that fits in one slot
+    pub fn max_stack_size(&self) -> u8 {
+        max_stack_size(&self.type_id)
+    }
+
+    fn tag(&self) -> Option<TagData> {
+        let mut tag = TagList::new();
+        if self.custom_name.is_some() || !self.lore.is_empty() {
+            let mut display = TagList::new();
+            if let Some(name) = &self.custom_name {
+                display.add_tag("Name", TagData::String(name.clone()));
+            }
+            if !self.lore.is_empty() {
+                display.add_tag("Lore", string_list(&self.lore));
+            }
+            tag.add_tag("display", TagData::Compound(display));
+        }
+        if !self.enchantments.is_empty() {
+            tag.add_tag(
+                "ench",
+                TagData::List(
+                    TagKind::Compound,
+                    self.enchantments.len() as u32,
+                    self.enchantments
+                        .iter()
+                        .map(|(id, level)| {
+                            TagData::Compound(TagList::from(vec![
+                                ("id".to_string(), TagData::Short(*id)),
+                                ("lvl".to_string(), TagData::Short(*level)),
+                            ]))
+                        })
+                        .collect(),
+                ),
+            );
+        }
+        if let Some(damage) = self.durability_damage {
+            tag.add_tag("Damage", TagData::Int(damage));
+        }
+        if self.keep_on_death {
+            tag.add_tag("minecraft:keep_on_death", TagData::Byte(1));
+        }
+        if let Some(item_lock) = self.item_lock {
+            tag.add_tag("minecraft:item_lock", TagData::Byte(item_lock as i8));
+        }
+        for (id, data) in self.extra_tags.iter() {
+            tag.set_tag(id, data.clone());
+        }
+        if tag.iter().next().is_none() {
+            None
+        } else {
+            Some(TagData::Compound(tag))
+        }
+    }
+
+    /// Item compound as found in `Items` lists, without a `Slot`
+    pub fn as_tag(&self) -> TagData {
+        let mut item = TagList::from(vec![
+            ("Count".to_string(), TagData::Byte(self.count as i8)),
+            ("Damage".to_string(), TagData::Short(self.damage)),
+            ("Name".to_string(), TagData::String(self.type_id.clone())),
+            ("WasPickedUp".to_string(), TagData::Byte(0)),
+        ]);
+        if let Some(block) = &self.block {
+            item.add_tag("Block", block.as_tag(DEFAULT_BLOCK_VERSION));
+        }
+        if !self.can_place_on.is_empty() {
+            item.add_tag("CanPlaceOn", string_list(&self.can_place_on));
+        }
+        if !self.can_destroy.is_empty() {
+            item.add_tag("CanDestroy", string_list(&self.can_destroy));
+        }
+        if let Some(tag) = self.tag() {
+            item.add_tag("tag", tag);
+        }
+        TagData::Compound(item)
+    }
+    /// Item compound with a `Slot` tag, for use in containers
+    pub fn as_slot_tag(&self, slot: u8) -> TagData {
+        let mut item = self.as_tag();
+        item.add_tag("Slot", TagData::Byte(slot as i8));
+        item
+    }
+}
+
+fn string_list(strings: &[String]) -> TagData {
+    TagData::List(
+        TagKind::String,
+        strings.len() as u32,
+        strings
+            .iter()
+            .map(|string| TagData::String(string.clone()))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stack_sizes() {
+        assert_eq!(max_stack_size("minecraft:dirt"), 64);
+        assert_eq!(max_stack_size("minecraft:ender_pearl"), 16);
+        assert_eq!(max_stack_size("minecraft:oak_sign"), 16);
+        assert_eq!(max_stack_size("minecraft:diamond_sword"), 1);
+        assert_eq!(max_stack_size("minecraft:water_bucket"), 1);
+        assert_eq!(max_stack_size("minecraft:music_disc_cat"), 1);
+        assert_eq!(max_stack_size("minecraft:blue_shulker_box"), 1);
+    }
+
+    #[test]
+    fn new_checks_the_count() {
+        assert_eq!(ItemStack::new("minecraft:dirt", 64).unwrap().count, 64);
+        assert_eq!(
+            ItemStack::new("minecraft:dirt", 0),
+            Err(Error::InvalidItemCount {
+                type_id: "minecraft:dirt".to_string(),
+                count: 0,
+                max: 64,
+            })
+        );
+        assert!(ItemStack::new("minecraft:dirt", 65).is_err());
+        assert!(ItemStack::new("minecraft:snowball", 17).is_err());
+        assert!(ItemStack::new("minecraft:iron_pickaxe", 2).is_err());
+        assert!(ItemStack::new("minecraft:iron_pickaxe", 1).is_ok());
+    }
+
+    #[test]
+    fn item_tags() {
+        let item = ItemStack::new("minecraft:diamond_sword", 1)
+            .unwrap()
+            .set_custom_name("Blade")
+            .add_lore("sharp")
+            .add_enchantment(Enchantment::Sharpness, 5)
+            .add_enchantment(Enchantment::Sharpness, 3)
+            .set_durability_damage(10)
+            .set_item_lock(ItemLock::InInventory)
+            .as_slot_tag(2);
+
+        assert_eq!(item.find_tag("Count"), Some(&TagData::Byte(1)));
+        assert_eq!(item.find_tag("Slot"), Some(&TagData::Byte(2)));
+        assert_eq!(
+            item.find_tag("Name"),
+            Some(&TagData::String("minecraft:diamond_sword".to_string()))
+        );
+        let tag = item.find_tag("tag").unwrap();
+        assert_eq!(
+            tag.find_tag("display")
+                .and_then(|display| display.find_tag("Name")),
+            Some(&TagData::String("Blade".to_string()))
+        );
+        assert_eq!(
+            tag.find_tag("ench"),
+            Some(&TagData::List(
+                TagKind::Compound,
+                1,
+                vec![TagData::Compound(TagList::from(vec![
+                    ("id".to_string(), TagData::Short(9)),
+                    ("lvl".to_string(), TagData::Short(3)),
+                ]))]
+            ))
+        );
+        assert_eq!(tag.find_tag("Damage"), Some(&TagData::Int(10)));
+        assert_eq!(tag.find_tag("minecraft:item_lock"), Some(&TagData::Byte(2)));
+    }
+
+    #[test]
+    fn plain_items_have_no_tag() {
+        let item = ItemStack::new("minecraft:dirt", 3).unwrap().as_tag();
+        assert_eq!(item.find_tag("tag"), None);
+        assert_eq!(item.find_tag("Count"), Some(&TagData::Byte(3)));
+    }
+}
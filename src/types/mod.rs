@@ -1,11 +1,46 @@
 use crate::{
+    error::Error,
     nbt::{TagData, TagKind, TagList},
     structure::{MCStructure, vec3_from_index},
 };
 
+use std::ops::{Add, Sub};
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+mod banner;
+mod block_entity;
+mod block_string;
+mod command_block;
+mod container;
+mod container_item;
+mod dye;
+mod item;
+mod registry;
+mod sign;
+mod spawner;
+mod structure_block;
+mod transform;
+
+pub use banner::{Banner, BannerPattern, BannerPlacement};
+pub(crate) use block_entity::set_tag_unless_default;
+pub use block_entity::{Beacon, BlockEntity, BlockEntityMut};
+pub use command_block::{ChainLayout, CommandBlock, CommandBlockKind};
+pub use container::{Container, ContainerInfo};
+pub use container_item::{BUNDLE_CAPACITY, BundleItem, SHULKER_BOX_SLOTS, ShulkerBoxItem};
+pub use dye::DyeColor;
+pub(crate) use item::max_stack_size;
+pub use item::{Enchantment, ItemLock, ItemStack};
+pub use registry::{BlockInfo, StateValues, block_info};
+pub use sign::{DEFAULT_SIGN_COLOR, Sign, SignKind, SignSide, SignText, WoodType};
+pub use spawner::{MOB_SPAWNER_BLOCK, MobSpawner};
+pub use structure_block::{
+    JIGSAW_BLOCK, JigsawBlock, JigsawJoint, STRUCTURE_BLOCK, StructureBlock, StructureBlockMode,
+};
+pub use transform::{Axis, Rotation};
+pub(crate) use transform::{DOOR_DIRECTION, LEGACY_DIRECTION, Transform, WEIRDO_DIRECTION};
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
 pub struct Vec3<T> {
     e: [T; 3],
 }
@@ -28,21 +63,224 @@ impl<T> Vec3<T> {
 
 impl Vec3<i32> {
     pub const _000: Vec3<i32> = Vec3 { e: [0, 0, 0] };
+
+    pub(crate) fn wrapping_add(self, rhs: Vec3<i32>) -> Vec3<i32> {
+        Vec3::new(
+            self.e[0].wrapping_add(rhs.e[0]),
+            self.e[1].wrapping_add(rhs.e[1]),
+            self.e[2].wrapping_add(rhs.e[2]),
+        )
+    }
+}
+
+impl<T: Copy + Add<Output = T>> Add for Vec3<T> {
+    type Output = Vec3<T>;
+    fn add(self, rhs: Vec3<T>) -> Vec3<T> {
+        Vec3::new(
+            self.e[0] + rhs.e[0],
+            self.e[1] + rhs.e[1],
+            self.e[2] + rhs.e[2],
+        )
+    }
+}
+
+impl<T: Copy + Sub<Output = T>> Sub for Vec3<T> {
+    type Output = Vec3<T>;
+    fn sub(self, rhs: Vec3<T>) -> Vec3<T> {
+        Vec3::new(
+            self.e[0] - rhs.e[0],
+            self.e[1] - rhs.e[1],
+            self.e[2] - rhs.e[2],
+        )
+    }
+}
+
+/// Horizontal direction, as used by `minecraft:cardinal_direction`
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum Cardinal {
+    North,
+    East,
+    South,
+    West,
 }
 
-#[derive(Clone, Debug)]
+impl Cardinal {
+    pub const CLOCKWISE: [Cardinal; 4] = [
+        Cardinal::North,
+        Cardinal::East,
+        Cardinal::South,
+        Cardinal::West,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Cardinal> {
+        match name {
+            "north" => Some(Cardinal::North),
+            "east" => Some(Cardinal::East),
+            "south" => Some(Cardinal::South),
+            "west" => Some(Cardinal::West),
+            _ => None,
+        }
+    }
+    pub fn name(self) -> &'static str {
+        match self {
+            Cardinal::North => "north",
+            Cardinal::East => "east",
+            Cardinal::South => "south",
+            Cardinal::West => "west",
+        }
+    }
+    /// Unit vector pointing in this direction
+    pub fn offset(self) -> Vec3<i32> {
+        match self {
+            Cardinal::North => Vec3::new(0, 0, -1),
+            Cardinal::East => Vec3::new(1, 0, 0),
+            Cardinal::South => Vec3::new(0, 0, 1),
+            Cardinal::West => Vec3::new(-1, 0, 0),
+        }
+    }
+    pub fn clockwise(self) -> Cardinal {
+        self.transform(Transform::Rotate(Rotation::Cw90))
+    }
+    pub fn opposite(self) -> Cardinal {
+        self.transform(Transform::Rotate(Rotation::Cw180))
+    }
+}
+
+/// Direction including up and down, as used by the `facing_direction` state
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum Facing {
+    Down,
+    Up,
+    North,
+    South,
+    West,
+    East,
+}
+
+impl Facing {
+    pub const ALL: [Facing; 6] = [
+        Facing::Down,
+        Facing::Up,
+        Facing::North,
+        Facing::South,
+        Facing::West,
+        Facing::East,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Facing> {
+        Facing::ALL.into_iter().find(|facing| facing.name() == name)
+    }
+    pub fn name(self) -> &'static str {
+        match self {
+            Facing::Down => "down",
+            Facing::Up => "up",
+            Facing::North => "north",
+            Facing::South => "south",
+            Facing::West => "west",
+            Facing::East => "east",
+        }
+    }
+    /// Value of the `facing_direction` state
+    pub fn facing_direction(self) -> i32 {
+        self as i32
+    }
+    pub fn offset(self) -> Vec3<i32> {
+        match self {
+            Facing::Down => Vec3::new(0, -1, 0),
+            Facing::Up => Vec3::new(0, 1, 0),
+            Facing::North => Cardinal::North.offset(),
+            Facing::South => Cardinal::South.offset(),
+            Facing::West => Cardinal::West.offset(),
+            Facing::East => Cardinal::East.offset(),
+        }
+    }
+    /// The facing pointing along a unit vector
+    pub fn from_offset(offset: Vec3<i32>) -> Option<Facing> {
+        Facing::ALL
+            .into_iter()
+            .find(|facing| facing.offset() == offset)
+    }
+    pub fn from_facing_direction(value: i32) -> Option<Facing> {
+        Facing::ALL.get(usize::try_from(value).ok()?).copied()
+    }
+    pub fn opposite(self) -> Facing {
+        match self {
+            Facing::Down => Facing::Up,
+            Facing::Up => Facing::Down,
+            Facing::North => Facing::South,
+            Facing::South => Facing::North,
+            Facing::West => Facing::East,
+            Facing::East => Facing::West,
+        }
+    }
+}
+
+impl From<Cardinal> for Facing {
+    fn from(cardinal: Cardinal) -> Facing {
+        match cardinal {
+            Cardinal::North => Facing::North,
+            Cardinal::East => Facing::East,
+            Cardinal::South => Facing::South,
+            Cardinal::West => Facing::West,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct BlockType {
     pub type_id: String,
     pub states: Vec<(String, BlockState)>,
+    /// Block data version the states were written for, see `block_version`
+    pub version: Option<i32>,
+}
+
+/// Packs a game version into the palette `version` int, e.g. `block_version(1, 21, 60, 33)`
+pub const fn block_version(major: u8, minor: u8, patch: u8, revision: u8) -> i32 {
+    (major as i32) << 24 | (minor as i32) << 16 | (patch as i32) << 8 | revision as i32
 }
 
+pub const DEFAULT_BLOCK_VERSION: i32 = block_version(1, 21, 60, 33);
+
 impl BlockType {
     pub fn new(namespace: &str) -> Self {
         BlockType {
             type_id: namespace.to_string(),
             states: vec![],
+            version: None,
         }
     }
+    pub fn set_version(mut self, version: i32) -> Self {
+        self.version = Some(version);
+        self
+    }
+    /// Palette compound of the block, using `default_version` if the block has no version
+    pub fn as_tag(&self, default_version: i32) -> TagData {
+        TagData::Compound(TagList::from(vec![
+            ("name".to_string(), TagData::String(self.type_id.clone())),
+            (
+                "states".to_string(),
+                TagData::Compound(TagList::from(
+                    self.states
+                        .iter()
+                        .map(|(name, state)| {
+                            (
+                                name.clone(),
+                                match state {
+                                    BlockState::String(string) => TagData::String(string.clone()),
+                                    BlockState::Int(int) => TagData::Int(*int),
+                                    BlockState::Bool(b) => TagData::Byte(*b as i8),
+                                },
+                            )
+                        })
+                        .collect(),
+                )),
+            ),
+            (
+                "version".to_string(),
+                TagData::Int(self.version.unwrap_or(default_version)),
+            ),
+        ]))
+    }
     pub fn set_state(mut self, state_name: &str, state: &BlockState) -> Self {
         let mut state_idx: Option<usize> = None;
         for (idx, state) in &mut self.states.iter().enumerate() {
@@ -57,9 +295,6 @@ impl BlockType {
         }
         self
     }
-    pub fn is_container(&self) -> bool {
-        self.type_id == "minecraft:barrel" || self.type_id == "minecraft:chest"
-    }
 }
 
 pub struct Block<'a> {
@@ -68,64 +303,116 @@ pub struct Block<'a> {
     pub(crate) index: u32,
 }
 
+/// Finds the block entity at `index`, creating it with `id` and the given extra fields if it
+/// does not exist yet. Fails if there is a block entity with another `id`.
+pub(crate) fn block_entity_or_insert<'a>(
+    structure: &'a mut MCStructure,
+    index: u32,
+    id: &str,
+    fields: Vec<(String, TagData)>,
+) -> Result<&'a mut TagData, Error> {
+    if let Some(data) = structure.block_position_data.get(&index) {
+        let existing = match data.find_tag("id") {
+            Some(TagData::String(existing)) => existing.as_str(),
+            _ => "",
+        };
+        if existing != id {
+            return Err(Error::BlockEntityMismatch {
+                existing: existing.to_string(),
+                expected: id.to_string(),
+            });
+        }
+    }
+    let size = structure.size;
+    Ok(structure
+        .block_position_data
+        .entry(index)
+        .or_insert_with(|| {
+            let position = vec3_from_index(index as usize, size);
+            let mut data = TagList::from(fields);
+            data.add_tag("id", TagData::String(id.to_string()));
+            data.add_tag("isMovable", TagData::Byte(1));
+            data.add_tag("x", TagData::Int(*position.x()));
+            data.add_tag("y", TagData::Int(*position.y()));
+            data.add_tag("z", TagData::Int(*position.z()));
+            TagData::Compound(data)
+        }))
+}
+
+/// Finds the block entity of a container, creating it if it does not exist yet
+pub(crate) fn container_block_entity<'a>(
+    structure: &'a mut MCStructure,
+    permutation: &BlockType,
+    index: u32,
+) -> Result<&'a mut TagData, Error> {
+    let Some(container) = permutation.container_info() else {
+        return Err(Error::NotAContainer(permutation.type_id.clone()));
+    };
+    block_entity_or_insert(
+        structure,
+        index,
+        container.block_entity_id,
+        vec![
+            ("Findable".to_string(), TagData::Byte(0)),
+            (
+                "Items".to_string(),
+                TagData::List(TagKind::Compound, 0, vec![]),
+            ),
+        ],
+    )
+}
+
 pub(crate) fn set_item_slot_of_block(
     structure: &mut MCStructure,
     permutation: &BlockType,
     index: u32,
     slot: u8,
-    item_type_id: &str,
-    count: u8,
-) {
-    if !permutation.is_container() {
-        panic!("the permutation is not a container");
-    }
-    let mut index_in_data = None::<usize>;
-    for (i, (j, _data)) in structure.block_position_data.iter().enumerate() {
-        if *j == index {
-            index_in_data = Some(i);
-            break;
-        }
+    item: &ItemStack,
+) -> Result<(), Error> {
+    let Some(container) = permutation.container_info() else {
+        return Err(Error::NotAContainer(permutation.type_id.clone()));
+    };
+    if slot >= container.slots {
+        return Err(Error::InvalidSlot {
+            type_id: permutation.type_id.clone(),
+            slot,
+            slots: container.slots,
+        });
     }
-    if let None = index_in_data {
-        index_in_data = Some(structure.block_position_data.len());
-        let position = vec3_from_index(index as usize, structure.size);
-        structure.block_position_data.push((
-            index,
-            TagData::Compound(TagList::from(vec![
-                ("Findable".to_string(), TagData::Byte(0)),
-                (
-                    "Items".to_string(),
-                    TagData::List(TagKind::Compound, 0, vec![]),
-                ),
-                ("id".to_string(), TagData::String("Barrel".to_string())),
-                ("isMovable".to_string(), TagData::Byte(1)),
-                ("x".to_string(), TagData::Int(*position.x())),
-                ("y".to_string(), TagData::Int(*position.y())),
-                ("z".to_string(), TagData::Int(*position.z())),
-            ])),
-        ))
-    }
-    let Some(index_in_data) = index_in_data else {
+    if container.block_entity_id == "ShulkerBox" && container_item::is_shulker_box(&item.type_id) {
+        return Err(Error::InvalidNesting {
+            item: item.type_id.clone(),
+            container: permutation.type_id.clone(),
+        });
+    }
+    let actual_data = container_block_entity(structure, permutation, index)?;
+    if !matches!(actual_data.get_tag("Items"), Some(TagData::List(..))) {
+        actual_data.set_tag("Items", TagData::List(TagKind::Compound, 0, vec![]));
+    }
+    let Some(TagData::List(kind, size, list)) = actual_data.get_tag("Items") else {
         panic!("unreachable code");
     };
-    let actual_data = &mut structure.block_position_data[index_in_data].1;
-    let Some(items) = actual_data.get_tag("Items") else {
-        panic!("unreachable");
-    };
 
-    if let TagData::List(_kind, size, list) = items {
-        list.push(TagData::Compound(TagList::from(vec![
-            ("Count".to_string(), TagData::Byte(count as i8)),
-            ("Damage".to_string(), TagData::Short(0)),
-            (
-                "Name".to_string(),
-                TagData::String(item_type_id.to_string()),
-            ),
-            ("Slot".to_string(), TagData::Byte(slot as i8)),
-            ("WasPickedUp".to_string(), TagData::Byte(0)),
-        ])));
-        *size += 1;
-    }
+    list.retain_mut(
+        |existing| !matches!(existing.get_tag("Slot"), Some(TagData::Byte(s)) if *s == slot as i8),
+    );
+    list.push(item.as_slot_tag(slot));
+    *kind = TagKind::Compound;
+    *size = list.len() as u32;
+    Ok(())
+}
+
+pub(crate) fn set_loot_table_of_block(
+    structure: &mut MCStructure,
+    permutation: &BlockType,
+    index: u32,
+    path: &str,
+    seed: i32,
+) -> Result<(), Error> {
+    let data = container_block_entity(structure, permutation, index)?;
+    data.set_tag("LootTable", TagData::String(path.to_string()));
+    data.set_tag("LootTableSeed", TagData::Int(seed));
+    Ok(())
 }
 
 impl<'a> Block<'a> {
@@ -136,23 +423,122 @@ impl<'a> Block<'a> {
             structure,
         }
     }
-    pub fn set_item_slot(self, slot: u8, item_type_id: &str, count: u8) -> Self {
-        set_item_slot_of_block(
-            self.structure,
-            &self.permutation,
-            self.index,
-            slot,
-            item_type_id,
-            count,
-        );
-        self
+    pub fn set_item_slot(self, slot: u8, item: &ItemStack) -> Result<Self, Error> {
+        set_item_slot_of_block(self.structure, &self.permutation, self.index, slot, item)?;
+        Ok(self)
+    }
+    /// Makes the container generate its contents from a loot table, e.g.
+    /// `loot_tables/chests/simple_dungeon.json`, when it is first opened. A seed of 0 picks a
+    /// random seed.
+    pub fn set_loot_table(self, path: &str, seed: i32) -> Result<Self, Error> {
+        set_loot_table_of_block(self.structure, &self.permutation, self.index, path, seed)?;
+        Ok(self)
+    }
+    pub fn loot_table(&self) -> Option<(String, i32)> {
+        self.structure.loot_table_at_index(self.index)
     }
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 #[serde(tag = "tag", content = "contents")]
 pub enum BlockState {
     String(String),
     Int(i32),
     Bool(u8),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(tag: &TagData) -> Option<&TagData> {
+        tag.find_tag("version")
+    }
+
+    #[test]
+    fn block_version_packs_the_game_version() {
+        assert_eq!(block_version(1, 21, 60, 33), 0x01_15_3c_21);
+        assert_eq!(DEFAULT_BLOCK_VERSION, 18168865);
+    }
+
+    #[test]
+    fn palette_entries_use_their_own_version() {
+        let block = BlockType::new("minecraft:stone");
+        assert_eq!(version(&block.as_tag(7)), Some(&TagData::Int(7)));
+        let block = block.set_version(block_version(1, 20, 0, 1));
+        assert_eq!(
+            version(&block.as_tag(7)),
+            Some(&TagData::Int(block_version(1, 20, 0, 1)))
+        );
+    }
+
+    #[test]
+    fn versions_round_trip() {
+        let old = block_version(1, 19, 0, 0);
+        let mut structure = MCStructure::new(Vec3::new(2, 1, 1));
+        structure.set_default_block_version(block_version(1, 21, 0, 3));
+        structure
+            .setblock(Vec3::new(0, 0, 0), BlockType::new("minecraft:stone"))
+            .unwrap();
+        structure
+            .setblock(
+                Vec3::new(1, 0, 0),
+                BlockType::new("minecraft:dirt").set_version(old),
+            )
+            .unwrap();
+
+        let read = MCStructure::from_bytes(structure.as_bytes()).unwrap();
+        assert_eq!(
+            read.getblock(Vec3::new(0, 0, 0)).version,
+            Some(block_version(1, 21, 0, 3))
+        );
+        assert_eq!(read.getblock(Vec3::new(1, 0, 0)).version, Some(old));
+    }
+
+    #[test]
+    fn loot_tables() {
+        let mut structure = MCStructure::new(Vec3::new(2, 1, 1));
+        let path = "loot_tables/chests/simple_dungeon.json";
+        let block = structure
+            .setblock(Vec3::new(0, 0, 0), BlockType::new("minecraft:barrel"))
+            .unwrap()
+            .set_loot_table(path, 42)
+            .unwrap();
+        assert_eq!(block.loot_table(), Some((path.to_string(), 42)));
+        assert_eq!(
+            structure.loot_table(Vec3::new(0, 0, 0)),
+            Some((path.to_string(), 42))
+        );
+        assert_eq!(structure.loot_table(Vec3::new(1, 0, 0)), None);
+        assert_eq!(structure.loot_table(Vec3::new(2, 0, 0)), None);
+        assert_eq!(structure.loot_table(Vec3::new(0, -1, 0)), None);
+
+        assert_eq!(
+            structure
+                .setblock(Vec3::new(1, 0, 0), BlockType::new("minecraft:stone"))
+                .unwrap()
+                .set_loot_table(path, 0)
+                .err(),
+            Some(Error::NotAContainer("minecraft:stone".to_string()))
+        );
+    }
+
+    #[test]
+    fn loot_table_seed_defaults_to_zero() {
+        let mut structure = MCStructure::new(Vec3::new(1, 1, 1));
+        structure
+            .setblock(Vec3::new(0, 0, 0), BlockType::new("minecraft:chest"))
+            .unwrap()
+            .set_loot_table("loot_tables/chests/igloo_chest.json", 7)
+            .unwrap();
+        structure
+            .block_position_data
+            .get_mut(&0)
+            .unwrap()
+            .remove_tag("LootTableSeed");
+        assert_eq!(
+            structure.loot_table(Vec3::new(0, 0, 0)),
+            Some(("loot_tables/chests/igloo_chest.json".to_string(), 0))
+        );
+    }
+}
@@ -0,0 +1,410 @@
+use std::{collections::HashMap, fmt, sync::OnceLock};
+
+use crate::{
+    error::Error,
+    types::{BlockState, BlockType},
+};
+
+const BLOCK_DATA: &str = include_str!("bedrock_blocks.txt");
+
+/// Values a block state may take
+#[derive(Clone, Debug, PartialEq)]
+pub enum StateValues {
+    Bool,
+    /// Inclusive range
+    Int(i32, i32),
+    String(Vec<String>),
+}
+
+impl StateValues {
+    fn default_state(&self) -> BlockState {
+        match self {
+            StateValues::Bool => BlockState::Bool(0),
+            StateValues::Int(min, _) => BlockState::Int(*min),
+            StateValues::String(values) => BlockState::String(values[0].clone()),
+        }
+    }
+    pub fn allows(&self, state: &BlockState) -> bool {
+        match (self, state) {
+            (StateValues::Bool, BlockState::Bool(value)) => *value <= 1,
+            (StateValues::Int(min, max), BlockState::Int(value)) => (min..=max).contains(&value),
+            (StateValues::String(values), BlockState::String(value)) => values.contains(value),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for StateValues {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateValues::Bool => write!(f, "true or false"),
+            StateValues::Int(min, max) => write!(f, "an integer from {} to {}", min, max),
+            StateValues::String(values) => write!(f, "one of \"{}\"", values.join("\", \"")),
+        }
+    }
+}
+
+/// A vanilla block and the states it has
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockInfo {
+    pub type_id: String,
+    pub states: Vec<(String, StateValues)>,
+}
+
+impl BlockInfo {
+    /// The block with every state at its default value
+    pub fn default_block(&self) -> BlockType {
+        self.states
+            .iter()
+            .fold(BlockType::new(&self.type_id), |block, (name, values)| {
+                block.set_state(name, &values.default_state())
+            })
+    }
+}
+
+fn parse_values(values: &str) -> StateValues {
+    if values == "bool" {
+        return StateValues::Bool;
+    }
+    if let Some((min, max)) = values.split_once("..")
+        && let (Ok(min), Ok(max)) = (min.parse(), max.parse())
+    {
+        return StateValues::Int(min, max);
+    }
+    StateValues::String(values.split('|').map(|value| value.to_string()).collect())
+}
+
+/// Expands `{a,b}` groups, with `$name` standing for a list defined in the data file
+fn expand(pattern: &str, lists: &HashMap<&str, Vec<&str>>) -> Vec<String> {
+    let (Some(start), Some(end)) = (pattern.find('{'), pattern.find('}')) else {
+        return vec![pattern.to_string()];
+    };
+    let mut expanded = vec![];
+    for alternative in pattern[start + 1..end].split(',') {
+        let alternatives = match alternative.strip_prefix('$') {
+            Some(list) => lists[list].clone(),
+            None => vec![alternative],
+        };
+        for alternative in alternatives {
+            let pattern = format!(
+                "{}{}{}",
+                &pattern[..start],
+                alternative,
+                &pattern[end + 1..]
+            );
+            expanded.extend(expand(&pattern, lists));
+        }
+    }
+    expanded
+}
+
+fn registry() -> &'static HashMap<String, BlockInfo> {
+    static REGISTRY: OnceLock<HashMap<String, BlockInfo>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let mut lists = HashMap::new();
+        let mut groups: HashMap<&str, Vec<(String, StateValues)>> = HashMap::new();
+        let mut blocks = HashMap::new();
+        for line in BLOCK_DATA.lines() {
+            let mut words = line.split_whitespace();
+            let Some(first) = words.next().filter(|word| !word.starts_with('#')) else {
+                continue;
+            };
+            if let Some(name) = first.strip_prefix('$') {
+                lists.insert(name, words.flat_map(|word| word.split(',')).collect());
+                continue;
+            }
+            let mut states = vec![];
+            for word in words {
+                if let Some(group) = word.strip_prefix('@') {
+                    states.extend(groups[group].iter().cloned());
+                } else if let Some((name, values)) = word.split_once('=') {
+                    states.push((name.to_string(), parse_values(values)));
+                }
+            }
+            if let Some(name) = first.strip_prefix('@') {
+                groups.insert(name, states);
+                continue;
+            }
+            for type_id in expand(first, &lists) {
+                blocks.insert(
+                    type_id.clone(),
+                    BlockInfo {
+                        type_id,
+                        states: states.clone(),
+                    },
+                );
+            }
+        }
+        blocks
+    })
+}
+
+/// Looks up a vanilla block in the bundled registry
+pub fn block_info(type_id: &str) -> Option<&'static BlockInfo> {
+    registry().get(type_id)
+}
+
+impl BlockType {
+    /// The default permutation of a vanilla block
+    pub fn default_for(type_id: &str) -> Result<BlockType, Error> {
+        block_info(type_id)
+            .map(BlockInfo::default_block)
+            .ok_or_else(|| Error::UnknownBlock(type_id.to_string()))
+    }
+    /// Checks the block and its states against the bundled registry. Blocks outside the
+    /// `minecraft` namespace come from add-ons and are not checked. States that are left out
+    /// take their default value in game, so only states that are present are checked.
+    pub fn validate(&self) -> Result<(), Error> {
+        if !self.type_id.starts_with("minecraft:") {
+            return Ok(());
+        }
+        let info =
+            block_info(&self.type_id).ok_or_else(|| Error::UnknownBlock(self.type_id.clone()))?;
+        for (name, state) in &self.states {
+            let invalid = |reason: String| Error::InvalidBlockState {
+                type_id: self.type_id.clone(),
+                state: name.clone(),
+                reason,
+            };
+            let Some((_, values)) = info.states.iter().find(|(state, _)| state == name) else {
+                let known: Vec<&str> = info.states.iter().map(|(name, _)| name.as_str()).collect();
+                return Err(invalid(if known.is_empty() {
+                    "the block has no states".to_string()
+                } else {
+                    format!("unknown state, the block has \"{}\"", known.join("\", \""))
+                }));
+            };
+            if !values.allows(state) {
+                let value = match state {
+                    BlockState::String(value) => format!("\"{}\"", value),
+                    BlockState::Int(value) => value.to_string(),
+                    BlockState::Bool(value) => (*value != 0).to_string(),
+                };
+                return Err(invalid(format!("{} is not {}", value, values)));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{structure::MCStructure, types::Vec3};
+
+    fn states(type_id: &str) -> Vec<(&str, StateValues)> {
+        block_info(type_id)
+            .unwrap_or_else(|| panic!("{} is not in the registry", type_id))
+            .states
+            .iter()
+            .map(|(name, values)| (name.as_str(), values.clone()))
+            .collect()
+    }
+
+    fn strings(values: &[&str]) -> StateValues {
+        StateValues::String(values.iter().map(|value| value.to_string()).collect())
+    }
+
+    #[test]
+    fn known_blocks() {
+        assert_eq!(states("minecraft:stone"), vec![]);
+        assert_eq!(
+            states("minecraft:oak_stairs"),
+            vec![
+                ("weirdo_direction", StateValues::Int(0, 3)),
+                ("upside_down_bit", StateValues::Bool),
+            ]
+        );
+        assert_eq!(
+            states("minecraft:chest"),
+            vec![(
+                "minecraft:cardinal_direction",
+                strings(&["south", "west", "north", "east"])
+            )]
+        );
+        assert_eq!(
+            states("minecraft:jigsaw"),
+            vec![
+                ("facing_direction", StateValues::Int(0, 5)),
+                ("rotation", StateValues::Int(0, 3)),
+            ]
+        );
+        for type_id in [
+            "minecraft:quartz_block",
+            "minecraft:purpur_block",
+            "minecraft:quartz_pillar",
+            "minecraft:pale_oak_log",
+            "minecraft:crimson_stem",
+        ] {
+            assert_eq!(
+                states(type_id),
+                vec![("pillar_axis", strings(&["y", "x", "z"]))]
+            );
+        }
+        for type_id in [
+            "minecraft:skeleton_skull",
+            "minecraft:wither_skeleton_skull",
+            "minecraft:zombie_head",
+            "minecraft:player_head",
+            "minecraft:creeper_head",
+            "minecraft:dragon_head",
+            "minecraft:piglin_head",
+        ] {
+            assert_eq!(
+                states(type_id),
+                vec![("facing_direction", StateValues::Int(0, 5))]
+            );
+        }
+        for wood in ["oak", "dark_oak", "cherry", "pale_oak"] {
+            assert_eq!(
+                states(&format!("minecraft:{}_sapling", wood)),
+                vec![("age_bit", StateValues::Bool)]
+            );
+        }
+        assert!(block_info("minecraft:light_block_15").is_some());
+        assert!(block_info("minecraft:element_118").is_some());
+    }
+
+    #[test]
+    fn blocks_that_do_not_exist() {
+        for type_id in [
+            "minecraft:mangrove_sapling",
+            "minecraft:skull",
+            "minecraft:light_block_16",
+            "minecraft:element_119",
+            "minecraft:oak_planks_stairs",
+            "stone",
+        ] {
+            assert_eq!(block_info(type_id), None, "{}", type_id);
+        }
+    }
+
+    #[test]
+    fn every_state_has_values() {
+        for info in registry().values() {
+            for (name, values) in &info.states {
+                match values {
+                    StateValues::Bool => {}
+                    StateValues::Int(min, max) => assert!(min <= max, "{} {}", info.type_id, name),
+                    StateValues::String(values) => assert!(
+                        values.iter().all(|value| !value.is_empty()),
+                        "{} {}",
+                        info.type_id,
+                        name
+                    ),
+                }
+            }
+            assert!(info.default_block().validate().is_ok(), "{}", info.type_id);
+        }
+    }
+
+    #[test]
+    fn default_for() {
+        assert_eq!(
+            BlockType::default_for("minecraft:oak_stairs"),
+            Ok(BlockType::new("minecraft:oak_stairs")
+                .set_state("weirdo_direction", &BlockState::Int(0))
+                .set_state("upside_down_bit", &BlockState::Bool(0)))
+        );
+        assert_eq!(
+            BlockType::default_for("minecraft:purpur_block"),
+            Ok(BlockType::new("minecraft:purpur_block")
+                .set_state("pillar_axis", &BlockState::String("y".to_string())))
+        );
+        assert_eq!(
+            BlockType::default_for("minecraft:skull"),
+            Err(Error::UnknownBlock("minecraft:skull".to_string()))
+        );
+    }
+
+    #[test]
+    fn validate() {
+        let stairs = BlockType::new("minecraft:oak_stairs");
+        assert_eq!(stairs.validate(), Ok(()));
+        assert_eq!(
+            stairs
+                .clone()
+                .set_state("weirdo_direction", &BlockState::Int(3))
+                .validate(),
+            Ok(())
+        );
+        assert_eq!(
+            stairs
+                .clone()
+                .set_state("weirdo_direction", &BlockState::Int(4))
+                .validate(),
+            Err(Error::InvalidBlockState {
+                type_id: "minecraft:oak_stairs".to_string(),
+                state: "weirdo_direction".to_string(),
+                reason: "4 is not an integer from 0 to 3".to_string(),
+            })
+        );
+        assert_eq!(
+            stairs
+                .set_state("facing", &BlockState::String("north".to_string()))
+                .validate(),
+            Err(Error::InvalidBlockState {
+                type_id: "minecraft:oak_stairs".to_string(),
+                state: "facing".to_string(),
+                reason: "unknown state, the block has \"weirdo_direction\", \"upside_down_bit\""
+                    .to_string(),
+            })
+        );
+        assert_eq!(
+            BlockType::new("minecraft:stone")
+                .set_state("pillar_axis", &BlockState::String("x".to_string()))
+                .validate(),
+            Err(Error::InvalidBlockState {
+                type_id: "minecraft:stone".to_string(),
+                state: "pillar_axis".to_string(),
+                reason: "the block has no states".to_string(),
+            })
+        );
+        assert_eq!(
+            BlockType::new("minecraft:chest")
+                .set_state(
+                    "minecraft:cardinal_direction",
+                    &BlockState::String("up".to_string())
+                )
+                .validate(),
+            Err(Error::InvalidBlockState {
+                type_id: "minecraft:chest".to_string(),
+                state: "minecraft:cardinal_direction".to_string(),
+                reason: "\"up\" is not one of \"south\", \"west\", \"north\", \"east\"".to_string(),
+            })
+        );
+        assert_eq!(
+            BlockType::new("minecraft:mangrove_sapling").validate(),
+            Err(Error::UnknownBlock(
+                "minecraft:mangrove_sapling".to_string()
+            ))
+        );
+        assert_eq!(BlockType::new("mymod:anything").validate(), Ok(()));
+    }
+
+    #[test]
+    fn strict_structures_check_blocks() {
+        let mut structure = MCStructure::new(Vec3::new(1, 1, 1));
+        structure.set_strict(true);
+        assert_eq!(
+            structure
+                .setblock(Vec3::new(0, 0, 0), BlockType::new("minecraft:skull"))
+                .err(),
+            Some(Error::UnknownBlock("minecraft:skull".to_string()))
+        );
+        assert!(
+            structure
+                .setblock(
+                    Vec3::new(0, 0, 0),
+                    BlockType::default_for("minecraft:zombie_head").unwrap()
+                )
+                .is_ok()
+        );
+        structure.set_strict(false);
+        assert!(
+            structure
+                .setblock(Vec3::new(0, 0, 0), BlockType::new("minecraft:skull"))
+                .is_ok()
+        );
+    }
+}
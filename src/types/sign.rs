@@ -0,0 +1,386 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Error,
+    nbt::{TagData, TagList},
+    structure::{MCStructure, index_from_vec3},
+    types::{Block, BlockType, Vec3, block_entity_or_insert},
+};
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum WoodType {
+    Oak,
+    Spruce,
+    Birch,
+    Jungle,
+    Acacia,
+    DarkOak,
+    Mangrove,
+    Cherry,
+    Bamboo,
+    Crimson,
+    Warped,
+    PaleOak,
+}
+
+impl WoodType {
+    pub fn name(self) -> &'static str {
+        match self {
+            WoodType::Oak => "oak",
+            WoodType::Spruce => "spruce",
+            WoodType::Birch => "birch",
+            WoodType::Jungle => "jungle",
+            WoodType::Acacia => "acacia",
+            WoodType::DarkOak => "dark_oak",
+            WoodType::Mangrove => "mangrove",
+            WoodType::Cherry => "cherry",
+            WoodType::Bamboo => "bamboo",
+            WoodType::Crimson => "crimson",
+            WoodType::Warped => "warped",
+            WoodType::PaleOak => "pale_oak",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum SignKind {
+    Standing,
+    Wall,
+    Hanging,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum SignSide {
+    Front,
+    Back,
+}
+
+impl SignSide {
+    fn tag_name(self) -> &'static str {
+        match self {
+            SignSide::Front => "FrontText",
+            SignSide::Back => "BackText",
+        }
+    }
+}
+
+/// ARGB color of sign text that has not been dyed
+pub const DEFAULT_SIGN_COLOR: u32 = 0xFF000000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignText {
+    pub lines: Vec<String>,
+    /// ARGB, see `DyeColor::argb`
+    pub color: u32,
+    pub glowing: bool,
+}
+
+impl SignText {
+    fn as_tag(&self) -> TagData {
+        TagData::Compound(TagList::from(vec![
+            ("GlowingText".to_string(), TagData::Byte(self.glowing as i8)),
+            ("HideGlowOutline".to_string(), TagData::Byte(0)),
+            ("PersistFormatting".to_string(), TagData::Byte(1)),
+            ("SignTextColor".to_string(), TagData::Int(self.color as i32)),
+            ("Text".to_string(), TagData::String(self.lines.join("\n"))),
+            ("TextOwner".to_string(), TagData::String(String::new())),
+        ]))
+    }
+    /// Writes the text into an existing `FrontText`/`BackText` compound, leaving its other
+    /// tags alone
+    fn write_tags(&self, data: &mut TagData) {
+        data.set_tag("GlowingText", TagData::Byte(self.glowing as i8));
+        data.set_tag("SignTextColor", TagData::Int(self.color as i32));
+        data.set_tag("Text", TagData::String(self.lines.join("\n")));
+    }
+    /// Reads text from a `FrontText`/`BackText` compound, or from the sign itself for signs
+    /// saved before signs had two sides
+    pub(crate) fn from_tag(data: &TagData) -> Option<SignText> {
+        let Some(TagData::String(text)) = data.find_tag("Text") else {
+            return None;
+        };
+        Some(SignText {
+            lines: text.split('\n').map(|line| line.to_string()).collect(),
+            color: match data.find_tag("SignTextColor") {
+                Some(TagData::Int(color)) => *color as u32,
+                _ => DEFAULT_SIGN_COLOR,
+            },
+            glowing: matches!(data.find_tag("GlowingText"), Some(TagData::Byte(1))),
+        })
+    }
+}
+
+/// Typed data of a sign or hanging sign block entity
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sign {
+    pub front: Option<SignText>,
+    pub back: Option<SignText>,
+    pub waxed: bool,
+}
+
+impl Sign {
+    pub(crate) fn from_tag(data: &TagData) -> Option<Sign> {
+        Some(Sign {
+            front: data.find_tag("FrontText").and_then(SignText::from_tag),
+            back: data.find_tag("BackText").and_then(SignText::from_tag),
+            waxed: matches!(data.find_tag("IsWaxed")?, TagData::Byte(1)),
+        })
+    }
+    pub(crate) fn write_tags(&self, data: &mut TagData) {
+        for (side, text) in [(SignSide::Front, &self.front), (SignSide::Back, &self.back)] {
+            match (text, data.get_tag(side.tag_name())) {
+                (Some(text), Some(existing @ TagData::Compound(_))) => text.write_tags(existing),
+                (Some(text), _) => data.set_tag(side.tag_name(), text.as_tag()),
+                (None, _) => {
+                    data.remove_tag(side.tag_name());
+                }
+            }
+        }
+        data.set_tag("IsWaxed", TagData::Byte(self.waxed as i8));
+    }
+}
+
+impl BlockType {
+    pub fn sign(wood: WoodType, kind: SignKind) -> BlockType {
+        let type_id = match (wood, kind) {
+            (WoodType::Oak, SignKind::Standing) => "minecraft:standing_sign".to_string(),
+            (WoodType::Oak, SignKind::Wall) => "minecraft:wall_sign".to_string(),
+            // standing and wall signs spell dark oak without an underscore
+            (WoodType::DarkOak, SignKind::Standing) => {
+                "minecraft:darkoak_standing_sign".to_string()
+            }
+            (WoodType::DarkOak, SignKind::Wall) => "minecraft:darkoak_wall_sign".to_string(),
+            (wood, SignKind::Standing) => format!("minecraft:{}_standing_sign", wood.name()),
+            (wood, SignKind::Wall) => format!("minecraft:{}_wall_sign", wood.name()),
+            (wood, SignKind::Hanging) => format!("minecraft:{}_hanging_sign", wood.name()),
+        };
+        BlockType::new(&type_id)
+    }
+    pub fn sign_kind(&self) -> Option<SignKind> {
+        if !self.type_id.starts_with("minecraft:") {
+            None
+        } else if self.type_id.ends_with("hanging_sign") {
+            Some(SignKind::Hanging)
+        } else if self.type_id.ends_with("wall_sign") {
+            Some(SignKind::Wall)
+        } else if self.type_id.ends_with("standing_sign") {
+            Some(SignKind::Standing)
+        } else {
+            None
+        }
+    }
+}
+
+fn set_sign_text_of_block(
+    structure: &mut MCStructure,
+    permutation: &BlockType,
+    index: u32,
+    side: SignSide,
+    text: &SignText,
+) -> Result<(), Error> {
+    let id = match permutation.sign_kind() {
+        Some(SignKind::Hanging) => "HangingSign",
+        Some(_) => "Sign",
+        None => {
+            return Err(Error::UnexpectedBlock {
+                type_id: permutation.type_id.clone(),
+                expected: "sign",
+            });
+        }
+    };
+    let data = block_entity_or_insert(
+        structure,
+        index,
+        id,
+        vec![("IsWaxed".to_string(), TagData::Byte(0))],
+    )?;
+    data.set_tag(side.tag_name(), text.as_tag());
+    Ok(())
+}
+
+impl<'a> Block<'a> {
+    /// Writes the text of one side of a sign. `color` is ARGB, see `DyeColor::argb`. Whether
+    /// the sign is waxed is left alone, it is set through the typed `Sign`.
+    pub fn set_sign_text(
+        self,
+        side: SignSide,
+        lines: &[&str],
+        color: u32,
+        glowing: bool,
+    ) -> Result<Self, Error> {
+        let text = SignText {
+            lines: lines.iter().map(|line| line.to_string()).collect(),
+            color,
+            glowing,
+        };
+        set_sign_text_of_block(self.structure, &self.permutation, self.index, side, &text)?;
+        Ok(self)
+    }
+    pub fn sign_text(&self, side: SignSide) -> Option<SignText> {
+        self.structure.sign_text_at_index(self.index, side)
+    }
+}
+
+impl MCStructure {
+    pub fn sign_text(&self, loc: Vec3<i32>, side: SignSide) -> Option<SignText> {
+        if !self.contains(loc) {
+            return None;
+        }
+        self.sign_text_at_index(index_from_vec3(loc, self.size) as u32, side)
+    }
+    pub fn is_sign_waxed(&self, loc: Vec3<i32>) -> bool {
+        self.contains(loc)
+            && self
+                .block_entity_at_index(index_from_vec3(loc, self.size) as u32)
+                .and_then(|data| data.find_tag("IsWaxed"))
+                == Some(&TagData::Byte(1))
+    }
+    fn sign_text_at_index(&self, index: u32, side: SignSide) -> Option<SignText> {
+        let data = self.block_entity_at_index(index)?;
+        match (data.find_tag(side.tag_name()), side) {
+            (Some(text), _) => SignText::from_tag(text),
+            (None, SignSide::Front) => SignText::from_tag(data),
+            (None, SignSide::Back) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{BlockEntity, DyeColor};
+
+    fn structure_with_sign(kind: SignKind) -> MCStructure {
+        let mut structure = MCStructure::new(Vec3::new(1, 1, 1));
+        structure
+            .setblock(Vec3::new(0, 0, 0), BlockType::sign(WoodType::Oak, kind))
+            .unwrap()
+            .set_sign_text(
+                SignSide::Front,
+                &["Hello", "World"],
+                DEFAULT_SIGN_COLOR,
+                false,
+            )
+            .unwrap();
+        structure
+    }
+
+    #[test]
+    fn sign_block_ids() {
+        let id = |wood, kind| BlockType::sign(wood, kind).type_id;
+        assert_eq!(
+            id(WoodType::Oak, SignKind::Standing),
+            "minecraft:standing_sign"
+        );
+        assert_eq!(
+            id(WoodType::DarkOak, SignKind::Wall),
+            "minecraft:darkoak_wall_sign"
+        );
+        assert_eq!(
+            id(WoodType::DarkOak, SignKind::Hanging),
+            "minecraft:dark_oak_hanging_sign"
+        );
+        assert_eq!(
+            id(WoodType::Cherry, SignKind::Standing),
+            "minecraft:cherry_standing_sign"
+        );
+        assert_eq!(
+            BlockType::sign(WoodType::Warped, SignKind::Hanging).sign_kind(),
+            Some(SignKind::Hanging)
+        );
+        assert_eq!(BlockType::new("minecraft:stone").sign_kind(), None);
+    }
+
+    #[test]
+    fn writes_both_sides() {
+        let mut structure = structure_with_sign(SignKind::Hanging);
+        let loc = Vec3::new(0, 0, 0);
+        structure
+            .setblock(loc, BlockType::sign(WoodType::Oak, SignKind::Hanging))
+            .unwrap()
+            .set_sign_text(SignSide::Back, &["Back"], DyeColor::Red.argb(), true)
+            .unwrap();
+
+        let front = structure.sign_text(loc, SignSide::Front).unwrap();
+        assert_eq!(front.lines, vec!["Hello", "World"]);
+        assert!(!front.glowing);
+        let back = structure.sign_text(loc, SignSide::Back).unwrap();
+        assert_eq!(back.lines, vec!["Back"]);
+        assert_eq!(back.color, DyeColor::Red.argb());
+        assert!(back.glowing);
+        assert_eq!(
+            structure.block_entity_at_index(0).unwrap().find_tag("id"),
+            Some(&TagData::String("HangingSign".to_string()))
+        );
+    }
+
+    #[test]
+    fn writing_text_keeps_the_wax() {
+        let mut structure = structure_with_sign(SignKind::Standing);
+        let loc = Vec3::new(0, 0, 0);
+        assert!(!structure.is_sign_waxed(loc));
+        if let BlockEntity::Sign(sign, _) = &mut *structure.block_entity_mut(loc).unwrap() {
+            sign.waxed = true;
+        }
+        assert!(structure.is_sign_waxed(loc));
+
+        structure
+            .setblock(loc, BlockType::sign(WoodType::Oak, SignKind::Standing))
+            .unwrap()
+            .set_sign_text(SignSide::Back, &["Back"], DEFAULT_SIGN_COLOR, false)
+            .unwrap();
+        assert!(structure.is_sign_waxed(loc));
+    }
+
+    #[test]
+    fn reads_legacy_signs() {
+        let mut structure = MCStructure::new(Vec3::new(1, 1, 1));
+        structure
+            .setblock(
+                Vec3::new(0, 0, 0),
+                BlockType::sign(WoodType::Oak, SignKind::Wall),
+            )
+            .unwrap();
+        structure.block_position_data.insert(
+            0,
+            TagData::Compound(TagList::from(vec![
+                ("id".to_string(), TagData::String("Sign".to_string())),
+                ("Text".to_string(), TagData::String("Old\nSign".to_string())),
+            ])),
+        );
+        let text = structure
+            .sign_text(Vec3::new(0, 0, 0), SignSide::Front)
+            .unwrap();
+        assert_eq!(text.lines, vec!["Old", "Sign"]);
+        assert_eq!(text.color, DEFAULT_SIGN_COLOR);
+        assert_eq!(
+            structure.sign_text(Vec3::new(0, 0, 0), SignSide::Back),
+            None
+        );
+    }
+
+    #[test]
+    fn rejects_other_blocks_and_positions() {
+        let mut structure = structure_with_sign(SignKind::Wall);
+        assert_eq!(
+            structure
+                .setblock(Vec3::new(0, 0, 0), BlockType::new("minecraft:chest"))
+                .unwrap()
+                .set_sign_text(SignSide::Front, &["x"], DEFAULT_SIGN_COLOR, false)
+                .err(),
+            Some(Error::UnexpectedBlock {
+                type_id: "minecraft:chest".to_string(),
+                expected: "sign",
+            })
+        );
+        assert_eq!(
+            structure.sign_text(Vec3::new(1, 0, 0), SignSide::Front),
+            None
+        );
+        assert_eq!(
+            structure.sign_text(Vec3::new(0, -1, 0), SignSide::Back),
+            None
+        );
+        assert!(!structure.is_sign_waxed(Vec3::new(0, 0, 5)));
+    }
+}
@@ -0,0 +1,237 @@
+use crate::{
+    error::Error,
+    nbt::TagData,
+    structure::{MCStructure, index_from_vec3, vec3_from_index},
+    types::{Block, BlockType, Vec3, block_entity_or_insert},
+};
+
+pub const MOB_SPAWNER_BLOCK: &str = "minecraft:mob_spawner";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MobSpawner {
+    /// Entity to spawn, e.g. `minecraft:zombie`
+    pub entity_identifier: String,
+    /// Ticks until the next spawn attempt
+    pub delay: i16,
+    pub min_spawn_delay: i16,
+    pub max_spawn_delay: i16,
+    pub spawn_count: i16,
+    pub spawn_range: i16,
+    pub required_player_range: i16,
+    pub max_nearby_entities: i16,
+    /// Size of the entity spinning inside the spawner
+    pub display_entity_scale: f32,
+}
+
+impl MobSpawner {
+    pub fn new(entity_identifier: &str) -> Self {
+        MobSpawner {
+            entity_identifier: entity_identifier.to_string(),
+            delay: 20,
+            min_spawn_delay: 200,
+            max_spawn_delay: 800,
+            spawn_count: 4,
+            spawn_range: 4,
+            required_player_range: 16,
+            max_nearby_entities: 6,
+            display_entity_scale: 1.0,
+        }
+    }
+    pub fn set_delay(mut self, delay: i16) -> Self {
+        self.delay = delay;
+        self
+    }
+    pub fn set_spawn_delay(mut self, min: i16, max: i16) -> Self {
+        self.min_spawn_delay = min;
+        self.max_spawn_delay = max;
+        self
+    }
+    pub fn set_spawn_count(mut self, spawn_count: i16) -> Self {
+        self.spawn_count = spawn_count;
+        self
+    }
+    pub fn set_spawn_range(mut self, spawn_range: i16) -> Self {
+        self.spawn_range = spawn_range;
+        self
+    }
+    pub fn set_required_player_range(mut self, required_player_range: i16) -> Self {
+        self.required_player_range = required_player_range;
+        self
+    }
+    pub fn set_max_nearby_entities(mut self, max_nearby_entities: i16) -> Self {
+        self.max_nearby_entities = max_nearby_entities;
+        self
+    }
+    pub fn set_display_entity_scale(mut self, display_entity_scale: f32) -> Self {
+        self.display_entity_scale = display_entity_scale;
+        self
+    }
+
+    pub(crate) fn write_tags(&self, data: &mut TagData) {
+        data.set_tag(
+            "EntityIdentifier",
+            TagData::String(self.entity_identifier.clone()),
+        );
+        data.set_tag("Delay", TagData::Short(self.delay));
+        data.set_tag("MinSpawnDelay", TagData::Short(self.min_spawn_delay));
+        data.set_tag("MaxSpawnDelay", TagData::Short(self.max_spawn_delay));
+        data.set_tag("SpawnCount", TagData::Short(self.spawn_count));
+        data.set_tag("SpawnRange", TagData::Short(self.spawn_range));
+        data.set_tag(
+            "RequiredPlayerRange",
+            TagData::Short(self.required_player_range),
+        );
+        data.set_tag(
+            "MaxNearbyEntities",
+            TagData::Short(self.max_nearby_entities),
+        );
+        data.set_tag(
+            "DisplayEntityScale",
+            TagData::Float(self.display_entity_scale),
+        );
+    }
+    /// Reads spawner settings from a `MobSpawner` block entity, using defaults for anything
+    /// that is missing
+    pub(crate) fn from_tag(data: &TagData) -> Option<MobSpawner> {
+        if data.find_tag("id") != Some(&TagData::String("MobSpawner".to_string())) {
+            return None;
+        }
+        let short = |id: &str, default: i16| match data.find_tag(id) {
+            Some(TagData::Short(value)) => *value,
+            _ => default,
+        };
+        let defaults = MobSpawner::new("");
+        Some(MobSpawner {
+            entity_identifier: match data.find_tag("EntityIdentifier") {
+                Some(TagData::String(identifier)) => identifier.clone(),
+                _ => String::new(),
+            },
+            delay: short("Delay", defaults.delay),
+            min_spawn_delay: short("MinSpawnDelay", defaults.min_spawn_delay),
+            max_spawn_delay: short("MaxSpawnDelay", defaults.max_spawn_delay),
+            spawn_count: short("SpawnCount", defaults.spawn_count),
+            spawn_range: short("SpawnRange", defaults.spawn_range),
+            required_player_range: short("RequiredPlayerRange", defaults.required_player_range),
+            max_nearby_entities: short("MaxNearbyEntities", defaults.max_nearby_entities),
+            display_entity_scale: match data.find_tag("DisplayEntityScale") {
+                Some(TagData::Float(scale)) => *scale,
+                _ => defaults.display_entity_scale,
+            },
+        })
+    }
+}
+
+pub(crate) fn set_mob_spawner_of_block(
+    structure: &mut MCStructure,
+    permutation: &BlockType,
+    index: u32,
+    spawner: &MobSpawner,
+) -> Result<(), Error> {
+    if permutation.type_id != MOB_SPAWNER_BLOCK {
+        return Err(Error::UnexpectedBlock {
+            type_id: permutation.type_id.clone(),
+            expected: "mob spawner",
+        });
+    }
+    let data = block_entity_or_insert(structure, index, "MobSpawner", vec![])?;
+    spawner.write_tags(data);
+    Ok(())
+}
+
+impl<'a> Block<'a> {
+    pub fn set_mob_spawner(self, spawner: &MobSpawner) -> Result<Self, Error> {
+        set_mob_spawner_of_block(self.structure, &self.permutation, self.index, spawner)?;
+        Ok(self)
+    }
+}
+
+impl MCStructure {
+    pub fn mob_spawner(&self, loc: Vec3<i32>) -> Option<MobSpawner> {
+        if !self.contains(loc) {
+            return None;
+        }
+        self.block_entity_at_index(index_from_vec3(loc, self.size) as u32)
+            .and_then(MobSpawner::from_tag)
+    }
+    /// Every mob spawner in the structure along with its position
+    pub fn mob_spawners(&self) -> Vec<(Vec3<i32>, MobSpawner)> {
+        self.block_position_data
+            .iter()
+            .filter_map(|(index, data)| {
+                let spawner = MobSpawner::from_tag(data)?;
+                Some((vec3_from_index(*index as usize, self.size), spawner))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt::TagList;
+
+    #[test]
+    fn spawner_round_trip() {
+        let mut structure = MCStructure::new(Vec3::new(2, 1, 1));
+        let spawner = MobSpawner::new("minecraft:zombie")
+            .set_delay(5)
+            .set_spawn_delay(100, 300)
+            .set_spawn_count(2)
+            .set_max_nearby_entities(3)
+            .set_display_entity_scale(0.5);
+        structure
+            .setblock(Vec3::new(1, 0, 0), BlockType::new(MOB_SPAWNER_BLOCK))
+            .unwrap()
+            .set_mob_spawner(&spawner)
+            .unwrap();
+
+        assert_eq!(
+            structure.mob_spawner(Vec3::new(1, 0, 0)),
+            Some(spawner.clone())
+        );
+        assert_eq!(
+            structure.mob_spawners(),
+            vec![(Vec3::new(1, 0, 0), spawner)]
+        );
+        assert_eq!(structure.mob_spawner(Vec3::new(0, 0, 0)), None);
+        assert_eq!(structure.mob_spawner(Vec3::new(2, 0, 0)), None);
+        assert_eq!(structure.mob_spawner(Vec3::new(0, 0, -1)), None);
+    }
+
+    #[test]
+    fn missing_settings_use_defaults() {
+        let data = TagData::Compound(TagList::from(vec![
+            ("id".to_string(), TagData::String("MobSpawner".to_string())),
+            (
+                "EntityIdentifier".to_string(),
+                TagData::String("minecraft:pig".to_string()),
+            ),
+            ("SpawnCount".to_string(), TagData::Short(1)),
+        ]));
+        assert_eq!(
+            MobSpawner::from_tag(&data),
+            Some(MobSpawner::new("minecraft:pig").set_spawn_count(1))
+        );
+        let chest = TagData::Compound(TagList::from(vec![(
+            "id".to_string(),
+            TagData::String("Chest".to_string()),
+        )]));
+        assert_eq!(MobSpawner::from_tag(&chest), None);
+    }
+
+    #[test]
+    fn only_spawners_hold_spawner_data() {
+        let mut structure = MCStructure::new(Vec3::new(1, 1, 1));
+        assert_eq!(
+            structure
+                .setblock(Vec3::new(0, 0, 0), BlockType::new("minecraft:stone"))
+                .unwrap()
+                .set_mob_spawner(&MobSpawner::new("minecraft:pig"))
+                .err(),
+            Some(Error::UnexpectedBlock {
+                type_id: "minecraft:stone".to_string(),
+                expected: "mob spawner",
+            })
+        );
+    }
+}
@@ -0,0 +1,577 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Error,
+    nbt::TagData,
+    structure::MCStructure,
+    types::{
+        Block, BlockState, BlockType, Facing, Rotation, Vec3, block_entity_or_insert,
+        set_tag_unless_default,
+    },
+};
+
+pub const STRUCTURE_BLOCK: &str = "minecraft:structure_block";
+pub const JIGSAW_BLOCK: &str = "minecraft:jigsaw";
+
+/// Mode of a structure block, stored as `data`
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum StructureBlockMode {
+    Data,
+    Save,
+    Load,
+    Corner,
+    Invalid,
+    Export,
+}
+
+impl StructureBlockMode {
+    pub const ALL: [StructureBlockMode; 6] = [
+        StructureBlockMode::Data,
+        StructureBlockMode::Save,
+        StructureBlockMode::Load,
+        StructureBlockMode::Corner,
+        StructureBlockMode::Invalid,
+        StructureBlockMode::Export,
+    ];
+
+    /// Value of the `structure_block_type` block state
+    pub fn name(self) -> &'static str {
+        match self {
+            StructureBlockMode::Data => "data",
+            StructureBlockMode::Save => "save",
+            StructureBlockMode::Load => "load",
+            StructureBlockMode::Corner => "corner",
+            StructureBlockMode::Invalid => "invalid",
+            StructureBlockMode::Export => "export",
+        }
+    }
+}
+
+/// Typed data of a structure block block entity
+#[derive(Clone, Debug, PartialEq)]
+pub struct StructureBlock {
+    pub mode: StructureBlockMode,
+    pub structure_name: String,
+    /// Text of a data mode structure block
+    pub data_field: String,
+    /// Position of the structure relative to the structure block
+    pub offset: Vec3<i32>,
+    pub size: Vec3<i32>,
+    pub rotation: Option<Rotation>,
+    pub mirror_x: bool,
+    pub mirror_z: bool,
+    /// Percentage of blocks that get placed, 0 to 100
+    pub integrity: f32,
+    pub seed: i64,
+    pub include_entities: bool,
+    pub include_players: bool,
+    pub remove_blocks: bool,
+    pub show_bounding_box: bool,
+    /// Save to disk instead of memory when powered
+    pub redstone_save_to_disk: bool,
+}
+
+impl StructureBlock {
+    pub fn new(mode: StructureBlockMode) -> Self {
+        StructureBlock {
+            mode,
+            structure_name: String::new(),
+            data_field: String::new(),
+            offset: Vec3::new(0, -1, 0),
+            size: Vec3::new(5, 5, 5),
+            rotation: None,
+            mirror_x: false,
+            mirror_z: false,
+            integrity: 100.0,
+            seed: 0,
+            include_entities: true,
+            include_players: false,
+            remove_blocks: false,
+            show_bounding_box: true,
+            redstone_save_to_disk: false,
+        }
+    }
+    pub fn set_structure_name(mut self, name: &str) -> Self {
+        self.structure_name = name.to_string();
+        self
+    }
+    pub fn set_data_field(mut self, data_field: &str) -> Self {
+        self.data_field = data_field.to_string();
+        self
+    }
+    pub fn set_offset(mut self, offset: Vec3<i32>) -> Self {
+        self.offset = offset;
+        self
+    }
+    pub fn set_size(mut self, size: Vec3<i32>) -> Self {
+        self.size = size;
+        self
+    }
+    pub fn set_rotation(mut self, rotation: Option<Rotation>) -> Self {
+        self.rotation = rotation;
+        self
+    }
+    pub fn set_mirror(mut self, mirror_x: bool, mirror_z: bool) -> Self {
+        self.mirror_x = mirror_x;
+        self.mirror_z = mirror_z;
+        self
+    }
+    /// Percentage of blocks that get placed, clamped to 0 to 100
+    pub fn set_integrity(mut self, integrity: f32) -> Self {
+        self.integrity = integrity.clamp(0.0, 100.0);
+        self
+    }
+    pub fn set_seed(mut self, seed: i64) -> Self {
+        self.seed = seed;
+        self
+    }
+    pub fn set_include_entities(mut self, include_entities: bool) -> Self {
+        self.include_entities = include_entities;
+        self
+    }
+    pub fn set_include_players(mut self, include_players: bool) -> Self {
+        self.include_players = include_players;
+        self
+    }
+    pub fn set_remove_blocks(mut self, remove_blocks: bool) -> Self {
+        self.remove_blocks = remove_blocks;
+        self
+    }
+    pub fn set_show_bounding_box(mut self, show_bounding_box: bool) -> Self {
+        self.show_bounding_box = show_bounding_box;
+        self
+    }
+    pub fn set_redstone_save_to_disk(mut self, redstone_save_to_disk: bool) -> Self {
+        self.redstone_save_to_disk = redstone_save_to_disk;
+        self
+    }
+
+    /// The structure block with its `structure_block_type` state
+    pub fn block_type(&self) -> BlockType {
+        BlockType::new(STRUCTURE_BLOCK).set_state(
+            "structure_block_type",
+            &BlockState::String(self.mode.name().to_string()),
+        )
+    }
+
+    pub(crate) fn from_tag(data: &TagData) -> Option<StructureBlock> {
+        let int = |id: &str| match data.find_tag(id) {
+            Some(TagData::Int(value)) => Some(*value),
+            _ => None,
+        };
+        let byte = |id: &str| match data.find_tag(id) {
+            Some(TagData::Byte(value)) => Some(*value),
+            _ => None,
+        };
+        let string = |id: &str| match data.find_tag(id) {
+            Some(TagData::String(value)) => Some(value.clone()),
+            _ => None,
+        };
+        let mirror = byte("mirror")?;
+        Some(StructureBlock {
+            mode: *StructureBlockMode::ALL.get(int("data")? as usize)?,
+            structure_name: string("structureName")?,
+            data_field: string("dataField")?,
+            offset: Vec3::new(
+                int("xStructureOffset")?,
+                int("yStructureOffset")?,
+                int("zStructureOffset")?,
+            ),
+            size: Vec3::new(
+                int("xStructureSize")?,
+                int("yStructureSize")?,
+                int("zStructureSize")?,
+            ),
+            rotation: match byte("rotation")? {
+                0 => None,
+                1 => Some(Rotation::Cw90),
+                2 => Some(Rotation::Cw180),
+                3 => Some(Rotation::Cw270),
+                _ => return None,
+            },
+            mirror_x: mirror & 1 != 0,
+            mirror_z: mirror & 2 != 0,
+            integrity: match data.find_tag("integrity") {
+                Some(TagData::Float(integrity)) => *integrity,
+                _ => return None,
+            },
+            seed: match data.find_tag("seed") {
+                Some(TagData::Long(seed)) => *seed,
+                _ => return None,
+            },
+            include_entities: byte("ignoreEntities")? == 0,
+            include_players: byte("includePlayers")? != 0,
+            remove_blocks: byte("removeBlocks")? != 0,
+            show_bounding_box: byte("showBoundingBox")? != 0,
+            redstone_save_to_disk: int("redstoneSaveMode")? == 1,
+        })
+    }
+    pub(crate) fn write_tags(&self, data: &mut TagData) {
+        data.set_tag(
+            "data",
+            TagData::Int(
+                StructureBlockMode::ALL
+                    .iter()
+                    .position(|mode| *mode == self.mode)
+                    .expect("every mode is in ALL") as i32,
+            ),
+        );
+        data.set_tag(
+            "structureName",
+            TagData::String(self.structure_name.clone()),
+        );
+        data.set_tag("dataField", TagData::String(self.data_field.clone()));
+        data.set_tag("xStructureOffset", TagData::Int(*self.offset.x()));
+        data.set_tag("yStructureOffset", TagData::Int(*self.offset.y()));
+        data.set_tag("zStructureOffset", TagData::Int(*self.offset.z()));
+        data.set_tag("xStructureSize", TagData::Int(*self.size.x()));
+        data.set_tag("yStructureSize", TagData::Int(*self.size.y()));
+        data.set_tag("zStructureSize", TagData::Int(*self.size.z()));
+        data.set_tag(
+            "rotation",
+            TagData::Byte(self.rotation.map_or(0, |rotation| rotation.quarter_turns()) as i8),
+        );
+        data.set_tag(
+            "mirror",
+            TagData::Byte(self.mirror_x as i8 | (self.mirror_z as i8) << 1),
+        );
+        data.set_tag("integrity", TagData::Float(self.integrity));
+        data.set_tag("seed", TagData::Long(self.seed));
+        data.set_tag(
+            "ignoreEntities",
+            TagData::Byte(!self.include_entities as i8),
+        );
+        data.set_tag("includePlayers", TagData::Byte(self.include_players as i8));
+        data.set_tag("removeBlocks", TagData::Byte(self.remove_blocks as i8));
+        data.set_tag(
+            "showBoundingBox",
+            TagData::Byte(self.show_bounding_box as i8),
+        );
+        data.set_tag(
+            "redstoneSaveMode",
+            TagData::Int(self.redstone_save_to_disk as i32),
+        );
+    }
+}
+
+/// How a jigsaw block may be turned when it is joined to another one
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum JigsawJoint {
+    Rollable,
+    Aligned,
+}
+
+impl JigsawJoint {
+    pub fn name(self) -> &'static str {
+        match self {
+            JigsawJoint::Rollable => "rollable",
+            JigsawJoint::Aligned => "aligned",
+        }
+    }
+    pub fn from_name(name: &str) -> Option<JigsawJoint> {
+        [JigsawJoint::Rollable, JigsawJoint::Aligned]
+            .into_iter()
+            .find(|joint| joint.name() == name)
+    }
+}
+
+/// Typed data of a jigsaw block entity
+#[derive(Clone, Debug, PartialEq)]
+pub struct JigsawBlock {
+    pub name: String,
+    /// Name of the jigsaw block this one connects to
+    pub target: String,
+    /// Template pool the connecting piece is picked from
+    pub pool: String,
+    /// Block the jigsaw block turns into once the structure is generated
+    pub final_state: String,
+    pub joint: JigsawJoint,
+    pub selection_priority: i32,
+    pub placement_priority: i32,
+}
+
+impl JigsawBlock {
+    pub fn new(name: &str, target: &str, pool: &str, final_state: &str) -> Self {
+        JigsawBlock {
+            name: name.to_string(),
+            target: target.to_string(),
+            pool: pool.to_string(),
+            final_state: final_state.to_string(),
+            joint: JigsawJoint::Rollable,
+            selection_priority: 0,
+            placement_priority: 0,
+        }
+    }
+    pub fn set_joint(mut self, joint: JigsawJoint) -> Self {
+        self.joint = joint;
+        self
+    }
+    pub fn set_selection_priority(mut self, selection_priority: i32) -> Self {
+        self.selection_priority = selection_priority;
+        self
+    }
+    pub fn set_placement_priority(mut self, placement_priority: i32) -> Self {
+        self.placement_priority = placement_priority;
+        self
+    }
+
+    /// The jigsaw block with its front facing `facing`. `rotation` (0 to 3) turns jigsaw
+    /// blocks that face up or down.
+    pub fn block_type(facing: Facing, rotation: u8) -> BlockType {
+        BlockType::new(JIGSAW_BLOCK)
+            .set_state(
+                "facing_direction",
+                &BlockState::Int(facing.facing_direction()),
+            )
+            .set_state("rotation", &BlockState::Int((rotation % 4) as i32))
+    }
+
+    pub(crate) fn from_tag(data: &TagData) -> Option<JigsawBlock> {
+        let string = |id: &str| match data.find_tag(id) {
+            Some(TagData::String(value)) => Some(value.clone()),
+            _ => None,
+        };
+        let int = |id: &str| match data.find_tag(id) {
+            Some(TagData::Int(value)) => Some(*value),
+            None => Some(0),
+            _ => None,
+        };
+        Some(JigsawBlock {
+            name: string("name")?,
+            target: string("target")?,
+            pool: string("target_pool")?,
+            final_state: string("final_state")?,
+            joint: JigsawJoint::from_name(&string("joint")?)?,
+            selection_priority: int("selection_priority")?,
+            placement_priority: int("placement_priority")?,
+        })
+    }
+    pub(crate) fn write_tags(&self, data: &mut TagData) {
+        data.set_tag("name", TagData::String(self.name.clone()));
+        data.set_tag("target", TagData::String(self.target.clone()));
+        data.set_tag("target_pool", TagData::String(self.pool.clone()));
+        data.set_tag("final_state", TagData::String(self.final_state.clone()));
+        data.set_tag("joint", TagData::String(self.joint.name().to_string()));
+        set_tag_unless_default(
+            data,
+            "selection_priority",
+            TagData::Int(self.selection_priority),
+            TagData::Int(0),
+        );
+        set_tag_unless_default(
+            data,
+            "placement_priority",
+            TagData::Int(self.placement_priority),
+            TagData::Int(0),
+        );
+    }
+}
+
+pub(crate) fn set_structure_block_of_block(
+    structure: &mut MCStructure,
+    permutation: &BlockType,
+    index: u32,
+    structure_block: &StructureBlock,
+) -> Result<(), Error> {
+    if permutation.type_id != STRUCTURE_BLOCK {
+        return Err(Error::UnexpectedBlock {
+            type_id: permutation.type_id.clone(),
+            expected: "structure block",
+        });
+    }
+    let data = block_entity_or_insert(
+        structure,
+        index,
+        "StructureBlock",
+        vec![
+            ("animationMode".to_string(), TagData::Byte(0)),
+            ("animationSeconds".to_string(), TagData::Float(0.0)),
+            ("isPowered".to_string(), TagData::Byte(0)),
+        ],
+    )?;
+    structure_block.write_tags(data);
+    Ok(())
+}
+
+pub(crate) fn set_jigsaw_of_block(
+    structure: &mut MCStructure,
+    permutation: &BlockType,
+    index: u32,
+    jigsaw: &JigsawBlock,
+) -> Result<(), Error> {
+    if permutation.type_id != JIGSAW_BLOCK {
+        return Err(Error::UnexpectedBlock {
+            type_id: permutation.type_id.clone(),
+            expected: "jigsaw block",
+        });
+    }
+    let data = block_entity_or_insert(structure, index, "JigsawBlock", vec![])?;
+    jigsaw.write_tags(data);
+    Ok(())
+}
+
+impl<'a> Block<'a> {
+    /// Writes the structure block settings. The mode block state is not changed, use
+    /// `StructureBlock::block_type` or `MCStructure::place_structure_block` to get it right.
+    pub fn set_structure_block(self, structure_block: &StructureBlock) -> Result<Self, Error> {
+        set_structure_block_of_block(
+            self.structure,
+            &self.permutation,
+            self.index,
+            structure_block,
+        )?;
+        Ok(self)
+    }
+    pub fn set_jigsaw(self, jigsaw: &JigsawBlock) -> Result<Self, Error> {
+        set_jigsaw_of_block(self.structure, &self.permutation, self.index, jigsaw)?;
+        Ok(self)
+    }
+}
+
+impl MCStructure {
+    pub fn place_structure_block(
+        &mut self,
+        loc: Vec3<i32>,
+        structure_block: &StructureBlock,
+    ) -> Result<Block<'_>, Error> {
+        self.setblock(loc, structure_block.block_type())?
+            .set_structure_block(structure_block)
+    }
+    pub fn place_jigsaw(
+        &mut self,
+        loc: Vec3<i32>,
+        facing: Facing,
+        rotation: u8,
+        jigsaw: &JigsawBlock,
+    ) -> Result<Block<'_>, Error> {
+        self.setblock(loc, JigsawBlock::block_type(facing, rotation))?
+            .set_jigsaw(jigsaw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BlockEntity;
+
+    #[test]
+    fn structure_block_round_trip() {
+        let mut structure = MCStructure::new(Vec3::new(2, 1, 1));
+        let structure_block = StructureBlock::new(StructureBlockMode::Load)
+            .set_structure_name("mystructure:house")
+            .set_offset(Vec3::new(1, 2, -3))
+            .set_size(Vec3::new(7, 4, 9))
+            .set_rotation(Some(Rotation::Cw270))
+            .set_mirror(true, true)
+            .set_integrity(150.0)
+            .set_seed(-42)
+            .set_include_entities(false)
+            .set_redstone_save_to_disk(true);
+        assert_eq!(structure_block.integrity, 100.0);
+        structure
+            .place_structure_block(Vec3::new(1, 0, 0), &structure_block)
+            .unwrap();
+
+        assert_eq!(
+            structure.getblock(Vec3::new(1, 0, 0)),
+            structure_block.block_type()
+        );
+        assert!(matches!(
+            structure.block_entity(Vec3::new(1, 0, 0)),
+            Some(BlockEntity::StructureBlock(read, _)) if read == structure_block
+        ));
+    }
+
+    #[test]
+    fn structure_block_modes() {
+        let names: Vec<_> = StructureBlockMode::ALL
+            .iter()
+            .map(|mode| mode.name())
+            .collect();
+        assert_eq!(
+            names,
+            ["data", "save", "load", "corner", "invalid", "export"]
+        );
+        for (index, mode) in StructureBlockMode::ALL.into_iter().enumerate() {
+            let mut data = TagData::Compound(Default::default());
+            StructureBlock::new(mode).write_tags(&mut data);
+            assert_eq!(data.find_tag("data"), Some(&TagData::Int(index as i32)));
+            assert_eq!(
+                StructureBlock::from_tag(&data),
+                Some(StructureBlock::new(mode))
+            );
+        }
+    }
+
+    #[test]
+    fn jigsaw_round_trip() {
+        let mut structure = MCStructure::new(Vec3::new(1, 2, 1));
+        let jigsaw = JigsawBlock::new(
+            "minecraft:bottom",
+            "minecraft:top",
+            "minecraft:village/plains/houses",
+            "minecraft:air",
+        )
+        .set_joint(JigsawJoint::Aligned)
+        .set_selection_priority(2)
+        .set_placement_priority(-1);
+        structure
+            .place_jigsaw(Vec3::new(0, 1, 0), Facing::Up, 5, &jigsaw)
+            .unwrap();
+
+        assert_eq!(
+            structure.getblock(Vec3::new(0, 1, 0)),
+            JigsawBlock::block_type(Facing::Up, 1)
+        );
+        assert!(matches!(
+            structure.block_entity(Vec3::new(0, 1, 0)),
+            Some(BlockEntity::Jigsaw(read, _)) if read == jigsaw
+        ));
+    }
+
+    #[test]
+    fn jigsaw_priorities_default_to_zero() {
+        let jigsaw = JigsawBlock::new("a", "b", "minecraft:empty", "minecraft:air");
+        let mut data = TagData::Compound(Default::default());
+        jigsaw.write_tags(&mut data);
+        assert_eq!(data.find_tag("selection_priority"), None);
+        assert_eq!(data.find_tag("placement_priority"), None);
+        assert_eq!(JigsawBlock::from_tag(&data), Some(jigsaw));
+    }
+
+    #[test]
+    fn jigsaw_joint_names() {
+        for joint in [JigsawJoint::Rollable, JigsawJoint::Aligned] {
+            assert_eq!(JigsawJoint::from_name(joint.name()), Some(joint));
+        }
+        assert_eq!(JigsawJoint::from_name("twisted"), None);
+    }
+
+    #[test]
+    fn only_matching_blocks_hold_the_data() {
+        let mut structure = MCStructure::new(Vec3::new(1, 1, 1));
+        let stone = BlockType::new("minecraft:stone");
+        assert_eq!(
+            structure
+                .setblock(Vec3::new(0, 0, 0), stone.clone())
+                .unwrap()
+                .set_structure_block(&StructureBlock::new(StructureBlockMode::Save))
+                .err(),
+            Some(Error::UnexpectedBlock {
+                type_id: "minecraft:stone".to_string(),
+                expected: "structure block",
+            })
+        );
+        assert_eq!(
+            structure
+                .setblock(Vec3::new(0, 0, 0), stone)
+                .unwrap()
+                .set_jigsaw(&JigsawBlock::new("a", "b", "c", "minecraft:air"))
+                .err(),
+            Some(Error::UnexpectedBlock {
+                type_id: "minecraft:stone".to_string(),
+                expected: "jigsaw block",
+            })
+        );
+    }
+}
@@ -0,0 +1,483 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::{BlockState, BlockType, Cardinal, JIGSAW_BLOCK, Vec3};
+
+/// Clockwise rotation around the Y axis, as seen from above
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum Rotation {
+    Cw90,
+    Cw180,
+    Cw270,
+}
+
+impl Rotation {
+    pub(crate) fn quarter_turns(self) -> u8 {
+        match self {
+            Rotation::Cw90 => 1,
+            Rotation::Cw180 => 2,
+            Rotation::Cw270 => 3,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum Transform {
+    Rotate(Rotation),
+    Mirror(Axis),
+}
+
+impl Cardinal {
+    pub(crate) fn transform(self, transform: Transform) -> Cardinal {
+        match transform {
+            Transform::Rotate(rotation) => {
+                let idx = Cardinal::CLOCKWISE.iter().position(|c| *c == self).unwrap();
+                Cardinal::CLOCKWISE[(idx + rotation.quarter_turns() as usize) % 4]
+            }
+            Transform::Mirror(Axis::X) => match self {
+                Cardinal::East => Cardinal::West,
+                Cardinal::West => Cardinal::East,
+                other => other,
+            },
+            Transform::Mirror(Axis::Z) => match self {
+                Cardinal::North => Cardinal::South,
+                Cardinal::South => Cardinal::North,
+                other => other,
+            },
+            Transform::Mirror(Axis::Y) => self,
+        }
+    }
+}
+
+// int encodings of horizontal directions, indexed by state value
+pub(crate) const WEIRDO_DIRECTION: [Cardinal; 4] = [
+    Cardinal::East,
+    Cardinal::West,
+    Cardinal::South,
+    Cardinal::North,
+];
+pub(crate) const LEGACY_DIRECTION: [Cardinal; 4] = [
+    Cardinal::South,
+    Cardinal::West,
+    Cardinal::North,
+    Cardinal::East,
+];
+pub(crate) const DOOR_DIRECTION: [Cardinal; 4] = [
+    Cardinal::East,
+    Cardinal::South,
+    Cardinal::West,
+    Cardinal::North,
+];
+const CORAL_DIRECTION: [Cardinal; 4] = [
+    Cardinal::West,
+    Cardinal::East,
+    Cardinal::North,
+    Cardinal::South,
+];
+// facing_direction 0 and 1 are down and up
+const FACING_DIRECTION: [Cardinal; 4] = [
+    Cardinal::North,
+    Cardinal::South,
+    Cardinal::West,
+    Cardinal::East,
+];
+
+fn transform_int_direction(table: &[Cardinal; 4], value: i32, transform: Transform) -> i32 {
+    if !(0..4).contains(&value) {
+        return value;
+    }
+    let dir = table[value as usize].transform(transform);
+    table.iter().position(|c| *c == dir).unwrap() as i32
+}
+
+fn transform_facing_direction(value: i32, transform: Transform) -> i32 {
+    match value {
+        0 | 1 if transform == Transform::Mirror(Axis::Y) => 1 - value,
+        2..=5 => transform_int_direction(&FACING_DIRECTION, value - 2, transform) + 2,
+        _ => value,
+    }
+}
+
+fn transform_direction_name(name: &str, transform: Transform) -> String {
+    if let Some(dir) = Cardinal::from_name(name) {
+        return dir.transform(transform).name().to_string();
+    }
+    match (name, transform) {
+        ("up", Transform::Mirror(Axis::Y)) => "down".to_string(),
+        ("down", Transform::Mirror(Axis::Y)) => "up".to_string(),
+        _ => name.to_string(),
+    }
+}
+
+// rail_direction: 0-1 flat, 2-5 ascending, 6-9 curved
+fn transform_rail_direction(value: i32, transform: Transform) -> i32 {
+    const ASCENDING: [Cardinal; 4] = [
+        Cardinal::East,
+        Cardinal::West,
+        Cardinal::North,
+        Cardinal::South,
+    ];
+    const CURVES: [(Cardinal, Cardinal); 4] = [
+        (Cardinal::South, Cardinal::East),
+        (Cardinal::South, Cardinal::West),
+        (Cardinal::North, Cardinal::West),
+        (Cardinal::North, Cardinal::East),
+    ];
+    match value {
+        0 | 1 => match transform {
+            Transform::Rotate(Rotation::Cw90 | Rotation::Cw270) => 1 - value,
+            _ => value,
+        },
+        2..=5 => transform_int_direction(&ASCENDING, value - 2, transform) + 2,
+        6..=9 => {
+            let (a, b) = CURVES[(value - 6) as usize];
+            let (a, b) = (a.transform(transform), b.transform(transform));
+            let idx = CURVES
+                .iter()
+                .position(|(c, d)| (*c == a && *d == b) || (*c == b && *d == a))
+                .unwrap();
+            idx as i32 + 6
+        }
+        _ => value,
+    }
+}
+
+// ground_sign_direction: 16 steps clockwise starting at south
+fn transform_ground_sign_direction(value: i32, transform: Transform) -> i32 {
+    match transform {
+        Transform::Rotate(rotation) => (value + 4 * rotation.quarter_turns() as i32) % 16,
+        Transform::Mirror(Axis::X) => (16 - value) % 16,
+        Transform::Mirror(Axis::Z) => (24 - value) % 16,
+        Transform::Mirror(Axis::Y) => value,
+    }
+}
+
+// vine_direction_bits: 1 south, 2 west, 4 north, 8 east
+fn transform_vine_direction_bits(value: i32, transform: Transform) -> i32 {
+    let mut bits = 0;
+    for (i, dir) in LEGACY_DIRECTION.iter().enumerate() {
+        if value & (1 << i) != 0 {
+            let new_dir = dir.transform(transform);
+            bits |= 1 << LEGACY_DIRECTION.iter().position(|c| *c == new_dir).unwrap();
+        }
+    }
+    bits
+}
+
+fn transform_lever_direction(value: &str, transform: Transform) -> String {
+    let value = match transform {
+        Transform::Mirror(Axis::Y) => match value.split_once('_') {
+            Some(("up", rest)) => format!("down_{}", rest),
+            Some(("down", rest)) => format!("up_{}", rest),
+            _ => value.to_string(),
+        },
+        _ => transform_direction_name(value, transform),
+    };
+    let quarter = matches!(
+        transform,
+        Transform::Rotate(Rotation::Cw90 | Rotation::Cw270)
+    );
+    match value.as_str() {
+        "up_north_south" if quarter => "up_east_west".to_string(),
+        "up_east_west" if quarter => "up_north_south".to_string(),
+        "down_north_south" if quarter => "down_east_west".to_string(),
+        "down_east_west" if quarter => "down_north_south".to_string(),
+        _ => value,
+    }
+}
+
+fn flip_bool(state: &BlockState) -> BlockState {
+    match state {
+        BlockState::Bool(b) => BlockState::Bool(1 - b.min(&1)),
+        other => other.clone(),
+    }
+}
+
+impl BlockType {
+    /// Rewrites orientation states so the block faces the same way after rotating the structure
+    pub fn rotated(&self, rotation: Rotation) -> BlockType {
+        self.transformed(Transform::Rotate(rotation))
+    }
+    /// Rewrites orientation states so the block faces the same way after mirroring the structure
+    pub fn mirrored(&self, axis: Axis) -> BlockType {
+        self.transformed(Transform::Mirror(axis))
+    }
+    pub(crate) fn transformed(&self, transform: Transform) -> BlockType {
+        let is_door = self.type_id.ends_with("_door") && !self.type_id.ends_with("trapdoor");
+        let is_trapdoor = self.type_id.ends_with("trapdoor");
+        let is_jigsaw = self.type_id == JIGSAW_BLOCK;
+        let mirror = matches!(transform, Transform::Mirror(Axis::X | Axis::Z));
+        let mirror_y = transform == Transform::Mirror(Axis::Y);
+
+        let mut new = self.clone();
+        for (name, state) in &mut new.states {
+            *state = match (name.as_str(), &*state) {
+                (
+                    "minecraft:cardinal_direction"
+                    | "minecraft:facing_direction"
+                    | "minecraft:block_face"
+                    | "torch_facing_direction",
+                    BlockState::String(value),
+                ) => BlockState::String(transform_direction_name(value, transform)),
+                ("facing_direction", BlockState::Int(value)) => {
+                    BlockState::Int(transform_facing_direction(*value, transform))
+                }
+                ("weirdo_direction", BlockState::Int(value)) => BlockState::Int(
+                    transform_int_direction(&WEIRDO_DIRECTION, *value, transform),
+                ),
+                ("direction", BlockState::Int(value)) => {
+                    let table = if is_door {
+                        &DOOR_DIRECTION
+                    } else if is_trapdoor {
+                        &WEIRDO_DIRECTION
+                    } else {
+                        &LEGACY_DIRECTION
+                    };
+                    BlockState::Int(transform_int_direction(table, *value, transform))
+                }
+                ("coral_direction", BlockState::Int(value)) => {
+                    BlockState::Int(transform_int_direction(&CORAL_DIRECTION, *value, transform))
+                }
+                ("pillar_axis", BlockState::String(value)) => BlockState::String(
+                    match (value.as_str(), transform) {
+                        ("x", Transform::Rotate(Rotation::Cw90 | Rotation::Cw270)) => "z",
+                        ("z", Transform::Rotate(Rotation::Cw90 | Rotation::Cw270)) => "x",
+                        (other, _) => other,
+                    }
+                    .to_string(),
+                ),
+                ("rail_direction", BlockState::Int(value)) => {
+                    BlockState::Int(transform_rail_direction(*value, transform))
+                }
+                ("ground_sign_direction", BlockState::Int(value)) => {
+                    BlockState::Int(transform_ground_sign_direction(*value, transform))
+                }
+                ("vine_direction_bits", BlockState::Int(value)) => {
+                    BlockState::Int(transform_vine_direction_bits(*value, transform))
+                }
+                ("lever_direction", BlockState::String(value)) => {
+                    BlockState::String(transform_lever_direction(value, transform))
+                }
+                ("orientation", BlockState::String(value)) => BlockState::String(
+                    value
+                        .split('_')
+                        .map(|part| transform_direction_name(part, transform))
+                        .collect::<Vec<String>>()
+                        .join("_"),
+                ),
+                ("minecraft:vertical_half", BlockState::String(value)) if mirror_y => {
+                    BlockState::String(
+                        match value.as_str() {
+                            "top" => "bottom",
+                            "bottom" => "top",
+                            other => other,
+                        }
+                        .to_string(),
+                    )
+                }
+                // which way the top of an upwards or downwards facing jigsaw block points
+                ("rotation", BlockState::Int(value)) if is_jigsaw => match transform {
+                    Transform::Rotate(rotation) => {
+                        BlockState::Int((value + rotation.quarter_turns() as i32) % 4)
+                    }
+                    _ => BlockState::Int(*value),
+                },
+                ("door_hinge_bit", state) if mirror => flip_bool(state),
+                ("upside_down_bit" | "top_slot_bit" | "hanging", state) if mirror_y => {
+                    flip_bool(state)
+                }
+                (_, state) => state.clone(),
+            };
+        }
+        new
+    }
+}
+
+impl Transform {
+    pub(crate) fn size(&self, size: Vec3<i32>) -> Vec3<i32> {
+        match self {
+            Transform::Rotate(Rotation::Cw90 | Rotation::Cw270) => {
+                Vec3::new(*size.z(), *size.y(), *size.x())
+            }
+            _ => size,
+        }
+    }
+    /// Maps a block coordinate inside a structure of `size` to its transformed coordinate
+    pub(crate) fn block_position(&self, loc: Vec3<i32>, size: Vec3<i32>) -> Vec3<i32> {
+        let (x, y, z) = (*loc.x(), *loc.y(), *loc.z());
+        let (sx, sy, sz) = (*size.x(), *size.y(), *size.z());
+        match self {
+            Transform::Rotate(Rotation::Cw90) => Vec3::new(sz - 1 - z, y, x),
+            Transform::Rotate(Rotation::Cw180) => Vec3::new(sx - 1 - x, y, sz - 1 - z),
+            Transform::Rotate(Rotation::Cw270) => Vec3::new(z, y, sx - 1 - x),
+            Transform::Mirror(Axis::X) => Vec3::new(sx - 1 - x, y, z),
+            Transform::Mirror(Axis::Y) => Vec3::new(x, sy - 1 - y, z),
+            Transform::Mirror(Axis::Z) => Vec3::new(x, y, sz - 1 - z),
+        }
+    }
+    /// Maps the difference between two block coordinates, wrapping instead of overflowing
+    pub(crate) fn offset(&self, offset: Vec3<i32>) -> Vec3<i32> {
+        let (x, y, z) = (*offset.x(), *offset.y(), *offset.z());
+        match self {
+            Transform::Rotate(Rotation::Cw90) => Vec3::new(z.wrapping_neg(), y, x),
+            Transform::Rotate(Rotation::Cw180) => Vec3::new(x.wrapping_neg(), y, z.wrapping_neg()),
+            Transform::Rotate(Rotation::Cw270) => Vec3::new(z, y, x.wrapping_neg()),
+            Transform::Mirror(Axis::X) => Vec3::new(x.wrapping_neg(), y, z),
+            Transform::Mirror(Axis::Y) => Vec3::new(x, y.wrapping_neg(), z),
+            Transform::Mirror(Axis::Z) => Vec3::new(x, y, z.wrapping_neg()),
+        }
+    }
+    /// Same as `block_position`, for continuous coordinates such as entity positions
+    pub(crate) fn point(&self, loc: Vec3<f32>, size: Vec3<i32>) -> Vec3<f32> {
+        let (x, y, z) = (*loc.x(), *loc.y(), *loc.z());
+        let (sx, sy, sz) = (*size.x() as f32, *size.y() as f32, *size.z() as f32);
+        match self {
+            Transform::Rotate(Rotation::Cw90) => Vec3::new(sz - z, y, x),
+            Transform::Rotate(Rotation::Cw180) => Vec3::new(sx - x, y, sz - z),
+            Transform::Rotate(Rotation::Cw270) => Vec3::new(z, y, sx - x),
+            Transform::Mirror(Axis::X) => Vec3::new(sx - x, y, z),
+            Transform::Mirror(Axis::Y) => Vec3::new(x, sy - y, z),
+            Transform::Mirror(Axis::Z) => Vec3::new(x, y, sz - z),
+        }
+    }
+    /// Transforms an entity's `[yaw, pitch]` rotation in degrees
+    pub(crate) fn yaw_pitch(&self, yaw: f32, pitch: f32) -> (f32, f32) {
+        match self {
+            Transform::Rotate(rotation) => (
+                (yaw + 90.0 * rotation.quarter_turns() as f32 + 180.0).rem_euclid(360.0) - 180.0,
+                pitch,
+            ),
+            Transform::Mirror(Axis::X) => (-yaw, pitch),
+            Transform::Mirror(Axis::Z) => ((360.0 - yaw).rem_euclid(360.0) - 180.0, pitch),
+            Transform::Mirror(Axis::Y) => (yaw, -pitch),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int_state(block: &BlockType, name: &str) -> i32 {
+        match block.states.iter().find(|(state, _)| state == name) {
+            Some((_, BlockState::Int(value))) => *value,
+            other => panic!("expected an int {name} state, found {other:?}"),
+        }
+    }
+
+    fn with_int(type_id: &str, name: &str, value: i32) -> BlockType {
+        BlockType::new(type_id).set_state(name, &BlockState::Int(value))
+    }
+
+    #[test]
+    fn weirdo_direction() {
+        // 0..4 are east, west, south, north
+        let stairs = |value| with_int("minecraft:oak_stairs", "weirdo_direction", value);
+        let rotated =
+            |value, rotation| int_state(&stairs(value).rotated(rotation), "weirdo_direction");
+        assert_eq!(rotated(0, Rotation::Cw90), 2);
+        assert_eq!(rotated(2, Rotation::Cw90), 1);
+        assert_eq!(rotated(1, Rotation::Cw90), 3);
+        assert_eq!(rotated(3, Rotation::Cw90), 0);
+        assert_eq!(rotated(0, Rotation::Cw180), 1);
+        assert_eq!(rotated(3, Rotation::Cw270), 1);
+
+        let mirrored = |value, axis| int_state(&stairs(value).mirrored(axis), "weirdo_direction");
+        assert_eq!(mirrored(0, Axis::X), 1);
+        assert_eq!(mirrored(2, Axis::X), 2);
+        assert_eq!(mirrored(2, Axis::Z), 3);
+        assert_eq!(mirrored(0, Axis::Z), 0);
+        assert_eq!(mirrored(3, Axis::Y), 3);
+    }
+
+    #[test]
+    fn facing_direction() {
+        // 0 and 1 are down and up, 2..6 are north, south, west, east
+        let observer = |value| with_int("minecraft:observer", "facing_direction", value);
+        let rotated =
+            |value, rotation| int_state(&observer(value).rotated(rotation), "facing_direction");
+        assert_eq!(rotated(2, Rotation::Cw90), 5);
+        assert_eq!(rotated(5, Rotation::Cw90), 3);
+        assert_eq!(rotated(3, Rotation::Cw90), 4);
+        assert_eq!(rotated(4, Rotation::Cw90), 2);
+        assert_eq!(rotated(2, Rotation::Cw270), 4);
+        assert_eq!(rotated(0, Rotation::Cw90), 0);
+        assert_eq!(rotated(1, Rotation::Cw180), 1);
+
+        let mirrored = |value, axis| int_state(&observer(value).mirrored(axis), "facing_direction");
+        assert_eq!(mirrored(4, Axis::X), 5);
+        assert_eq!(mirrored(2, Axis::X), 2);
+        assert_eq!(mirrored(2, Axis::Z), 3);
+        assert_eq!(mirrored(0, Axis::Y), 1);
+        assert_eq!(mirrored(1, Axis::Y), 0);
+        assert_eq!(mirrored(0, Axis::X), 0);
+    }
+
+    #[test]
+    fn rail_direction() {
+        let rail = |value| with_int("minecraft:rail", "rail_direction", value);
+        let rotated = |value, rotation| int_state(&rail(value).rotated(rotation), "rail_direction");
+        // flat rails swap between north-south and east-west
+        assert_eq!(rotated(0, Rotation::Cw90), 1);
+        assert_eq!(rotated(1, Rotation::Cw270), 0);
+        assert_eq!(rotated(0, Rotation::Cw180), 0);
+        // ascending east, west, north, south
+        assert_eq!(rotated(2, Rotation::Cw90), 5);
+        assert_eq!(rotated(5, Rotation::Cw90), 3);
+        assert_eq!(rotated(4, Rotation::Cw180), 5);
+        // curves south-east, south-west, north-west, north-east
+        assert_eq!(rotated(6, Rotation::Cw90), 7);
+        assert_eq!(rotated(9, Rotation::Cw90), 6);
+        assert_eq!(rotated(6, Rotation::Cw180), 8);
+
+        let mirrored = |value, axis| int_state(&rail(value).mirrored(axis), "rail_direction");
+        assert_eq!(mirrored(2, Axis::X), 3);
+        assert_eq!(mirrored(4, Axis::X), 4);
+        assert_eq!(mirrored(6, Axis::X), 7);
+        assert_eq!(mirrored(6, Axis::Z), 9);
+        assert_eq!(mirrored(1, Axis::Z), 1);
+    }
+
+    #[test]
+    fn four_rotations_are_identity() {
+        let blocks = [
+            with_int("minecraft:oak_stairs", "weirdo_direction", 2),
+            with_int("minecraft:observer", "facing_direction", 4),
+            with_int("minecraft:rail", "rail_direction", 8),
+            with_int("minecraft:standing_sign", "ground_sign_direction", 5),
+        ];
+        for block in blocks {
+            let mut rotated = block.clone();
+            for _ in 0..4 {
+                rotated = rotated.rotated(Rotation::Cw90);
+            }
+            assert_eq!(rotated, block);
+            assert_eq!(block.mirrored(Axis::X).mirrored(Axis::X), block);
+        }
+    }
+
+    #[test]
+    fn offset_matches_block_position() {
+        let size = Vec3::new(3, 2, 5);
+        let (a, b) = (Vec3::new(1, 0, 3), Vec3::new(2, 1, 4));
+        for transform in [
+            Transform::Rotate(Rotation::Cw90),
+            Transform::Rotate(Rotation::Cw180),
+            Transform::Rotate(Rotation::Cw270),
+            Transform::Mirror(Axis::X),
+            Transform::Mirror(Axis::Y),
+            Transform::Mirror(Axis::Z),
+        ] {
+            let moved = transform.block_position(b, size) - transform.block_position(a, size);
+            assert_eq!(transform.offset(b - a), moved);
+        }
+        let far = Transform::Rotate(Rotation::Cw90).offset(Vec3::new(0, 0, i32::MIN));
+        assert_eq!(far, Vec3::new(i32::MIN, 0, 0));
+    }
+}
@@ -12,7 +12,7 @@ extern "C" {
     fn log_jsValue(s: &JsValue);
 }
 
-use crate::types::{Vec3, set_item_slot_of_block};
+use crate::types::{ItemStack, Vec3, set_item_slot_of_block};
 use crate::{
     structure::MCStructure,
     types::{BlockState, BlockType},
@@ -46,7 +46,8 @@ impl WASM_MCStructure {
 
         self.mcstructure
             .borrow_mut()
-            .setblock(loc, block.blocktype.clone().expect("unreachable code"));
+            .setblock(loc, block.blocktype.clone().expect("unreachable code"))
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
         Ok(WASM_Block::new(
             block,
             (self.mcstructure.borrow().size.z() * self.mcstructure.borrow().size.y() * loc.x()
@@ -55,12 +56,18 @@ impl WASM_MCStructure {
             self,
         ))
     }
-    pub fn palette_add(&mut self, block: WASM_BlockType) -> i32 {
-        self.mcstructure.borrow_mut().palette_add(block.blocktype.clone().expect("unreachable code"))
+    pub fn palette_add(&mut self, block: WASM_BlockType) -> Result<i32, JsValue> {
+        self.mcstructure
+            .borrow_mut()
+            .palette_add(block.blocktype.clone().expect("unreachable code"))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
     }
     pub fn setblock_palette(&mut self, loc: &[i32], palette_block: i32) -> Result<WASM_Block, JsValue> {
         let loc = vec3_from_slice(loc);
-        self.mcstructure.borrow_mut().setblock_palette(loc, palette_block);
+        self.mcstructure
+            .borrow_mut()
+            .setblock_palette(loc, palette_block)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
         Ok(WASM_Block::new(
             WASM_BlockType::from(self.mcstructure.borrow().palette[palette_block as usize].clone()),
             (self.mcstructure.borrow().size.z() * self.mcstructure.borrow().size.y() * loc.x()
@@ -124,7 +131,7 @@ impl WASM_Block {
             index,
         }
     }
-    pub fn set_item_slot(&mut self, slot: u8, item_type_id: &str, count: u8) {
+    pub fn set_item_slot(&mut self, slot: u8, item_type_id: &str, count: u8) -> Result<(), JsValue> {
         set_item_slot_of_block(
             &mut self.structure.borrow_mut(),
             self.permutation
@@ -133,8 +140,8 @@ impl WASM_Block {
                 .expect("unreachable code"),
             self.index,
             slot,
-            item_type_id,
-            count,
-        );
+            &ItemStack::new(item_type_id, count).map_err(|e| JsValue::from_str(&e.to_string()))?,
+        )
+        .map_err(|e| JsValue::from_str(&e.to_string()))
     }
 }